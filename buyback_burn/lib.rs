@@ -0,0 +1,714 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::buyback_burn::BuybackBurnRef;
+
+#[ink::contract]
+pub mod buyback_burn {
+
+    use ink::codegen::TraitCallBuilder; // Importing TraitCallBuilder to call payable messages of the pairs
+    use ink::env::{
+        call::{
+            build_call,
+            ExecutionInput,
+            FromAccountId,
+            Selector,
+        },
+        DefaultEnvironment,
+    };
+    use ink::storage::Mapping; // Importing Mapping from ink storage
+    use ink::LangError; // Importing LangError from ink
+    use openbrush::contracts::traits::psp22::{
+        PSP22Error,
+        PSP22Ref,
+    };
+    use primitive_types::U256;
+    use trading_pair_azero::trading_pair_azero::{
+        TradingPairAzeroRef,
+        TradingPairErrors,
+    };
+
+    /// Shortest window (30 minutes, in seconds) a time weighted average price is measured over.
+    pub const MIN_TWAP_PERIOD: u64 = 30 * 60;
+
+    /// Oldest time weighted average price (one day, in seconds) a buyback can be bounded by.
+    pub const MAX_TWAP_AGE: u64 = 86400;
+
+    /// Denominator of the max slippage, in basis points (100% = 10000).
+    pub const SLIPPAGE_DENOMINATOR: Balance = 10_000;
+
+    /// What happens to the bought PANX.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum PanxSink {
+        Burn, // PANX is burned through PSP22Burnable
+        Lock, // PANX stays in this contract, which has no message to move it
+    }
+
+    /// Price cumulatives of a pair read by `update_twap`.
+    #[derive(Debug, Default, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct PriceObservation {
+        pub psp22_price_cumulative: Balance, // Time weighted sum of the PSP22 price in AZERO
+        pub a0_price_cumulative: Balance,    // Time weighted sum of the AZERO price in PSP22
+        pub timestamp: u64,                  // Timestamp (in seconds) of the reading
+    }
+
+    /// Time weighted average prices of a pair between two observations, scaled by 10^12.
+    #[derive(Debug, Default, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Twap {
+        pub psp22_price: Balance, // Average price of one PSP22 in AZERO
+        pub a0_price: Balance,    // Average price of one AZERO in PSP22
+        pub timestamp: u64,       // Timestamp (in seconds) the window ended at
+    }
+
+    /// function to get the time weighted average prices between two observations.
+    fn get_twap(
+        previous: &PriceObservation,
+        current: &PriceObservation,
+    ) -> Option<Twap> {
+        let elapsed_seconds = current.timestamp.checked_sub(previous.timestamp)?;
+
+        // cumulatives wrap around on overflow, so their difference does too
+        Some(Twap {
+            psp22_price: current
+                .psp22_price_cumulative
+                .wrapping_sub(previous.psp22_price_cumulative)
+                .checked_div(elapsed_seconds as Balance)?,
+            a0_price: current
+                .a0_price_cumulative
+                .wrapping_sub(previous.a0_price_cumulative)
+                .checked_div(elapsed_seconds as Balance)?,
+            timestamp: current.timestamp,
+        })
+    }
+
+    /// function to get the least amount out a trade can return: the amount at the average price minus the max slippage.
+    fn get_min_amount_out(
+        amount_in: Balance,
+        twap_price: Balance,
+        max_slippage_bps: Balance,
+    ) -> Option<Balance> {
+        let min_amount_out = U256::from(amount_in)
+            * U256::from(twap_price)
+            * U256::from(SLIPPAGE_DENOMINATOR.checked_sub(max_slippage_bps)?)
+            / U256::from(10u128.pow(12))
+            / U256::from(SLIPPAGE_DENOMINATOR);
+
+        Balance::try_from(min_amount_out).ok()
+    }
+
+    /// Maps every failure of a swap of a pair to an error carrying its cause.
+    fn check_swap_result(
+        result: Result<Result<Result<(), TradingPairErrors>, LangError>, ink::env::Error>,
+    ) -> Result<(), BuybackErrors> {
+        match result {
+            Ok(Ok(Ok(()))) => Ok(()),
+            Ok(Ok(Err(error))) => Err(BuybackErrors::Swap(error)),
+            Ok(Err(error)) => Err(BuybackErrors::Lang(error)),
+            Err(_) => Err(BuybackErrors::PairCallFailed),
+        }
+    }
+
+    #[ink(storage)]
+    pub struct BuybackBurn {
+        // Account allowed to change the settings
+        admin: AccountId,
+        // PANX PSP22 token
+        panx_token: AccountId,
+        // PANX/AZERO trading pair the PANX is bought from
+        panx_pair: AccountId,
+        // Largest distance below the average price a trade may fill at, in basis points
+        max_slippage_bps: Balance,
+        // What happens to the bought PANX
+        sink: PanxSink,
+        // Trading pairs the admin allowed to be traded and read, the PANX pair always is
+        allowed_pairs: Mapping<AccountId, bool>,
+        // Last price cumulatives read from each pair
+        observations: Mapping<AccountId, PriceObservation>,
+        // Last time weighted average prices of each pair
+        twaps: Mapping<AccountId, Twap>,
+        // Overall amount spent of each token, None standing for AZERO
+        total_spent: Mapping<Option<AccountId>, Balance>,
+        // Overall PANX bought
+        total_panx_bought: Balance,
+        // Overall PANX burned
+        total_panx_burned: Balance,
+        // Overall PANX locked in this contract
+        total_panx_locked: Balance,
+    }
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+    pub enum BuybackErrors {
+        CallerIsNotAdmin,                  // Error code for an admin message called by another account
+        PairNotAllowed,                    // Error code for a pair the admin did not allow
+        InvalidSlippage,                   // Error code for a max slippage above 100%
+        ZeroAmount,                        // Error code for a buyback of 0 tokens
+        InsufficientBalance,               // Error code for spending more than this contract holds
+        TwapPeriodNotElapsed,              // Error code for updating a TWAP before MIN_TWAP_PERIOD passed
+        TwapNotAvailable,                  // Error code for a pair without a TWAP from the last MAX_TWAP_AGE
+        PriceBelowTwap,                    // Error code for a trade filling below the TWAP minus the max slippage
+        Overflow,                          // Error code for overflow
+        Swap(TradingPairErrors),           // Error code for a swap the pair rejected
+        Lang(LangError),                   // Error code for a pair message that could not be dispatched
+        PairCallFailed,                    // Error code for a pair call that failed in the environment
+        PSP22ApproveFailed(PSP22Error),    // Error code for a failed approval of the pair
+        PSP22BurnFailed,                   // Error code for a failed burn of the bought PANX
+    }
+
+    #[ink(event)]
+    pub struct TwapUpdated {
+        #[ink(topic)]
+        pair: AccountId,     // Address of the trading pair
+        psp22_price: Balance, // Average price of one PSP22 in AZERO, scaled by 10^12
+        a0_price: Balance,   // Average price of one AZERO in PSP22, scaled by 10^12
+    }
+
+    #[ink(event)]
+    pub struct AllowedPairSet {
+        #[ink(topic)]
+        pair: AccountId, // Address of the trading pair
+        allowed: bool,   // Whether buybacks can trade and read the pair
+    }
+
+    #[ink(event)]
+    pub struct BuybackExecuted {
+        #[ink(topic)]
+        token: Option<AccountId>, // Address of the spent PSP22 token, None for AZERO
+        amount_in: Balance,       // Amount of the token spent
+        a0_amount: Balance,       // AZERO spent on PANX, after selling the PSP22 token
+        panx_amount: Balance,     // PANX bought
+        sink: PanxSink,           // Whether the PANX was burned or locked
+    }
+
+    impl BuybackBurn {
+        #[ink(constructor)]
+        pub fn new(
+            panx_token: AccountId,    // Address of the PANX token contract
+            panx_pair: AccountId,     // Address of the PANX/AZERO trading pair
+            max_slippage_bps: Balance, // Largest distance below the average price, in basis points
+            sink: PanxSink,           // Whether the bought PANX is burned or locked
+        ) -> Self {
+            let admin = Self::env().caller(); // The deployer changes the settings
+            let allowed_pairs = Mapping::default(); // Mapping to store the pairs allowed by the admin
+            let observations = Mapping::default(); // Mapping to store the price cumulatives of the pairs
+            let twaps = Mapping::default(); // Mapping to store the average prices of the pairs
+            let total_spent = Mapping::default(); // Mapping to store the spent amount of each token
+            let total_panx_bought: Balance = 0; // Overall PANX bought
+            let total_panx_burned: Balance = 0; // Overall PANX burned
+            let total_panx_locked: Balance = 0; // Overall PANX locked
+
+            Self {
+                admin,
+                panx_token,
+                panx_pair,
+                max_slippage_bps: max_slippage_bps.min(SLIPPAGE_DENOMINATOR),
+                sink,
+                allowed_pairs,
+                observations,
+                twaps,
+                total_spent,
+                total_panx_bought,
+                total_panx_burned,
+                total_panx_locked,
+            }
+        }
+
+        /// function to read the price cumulatives of a pair, and to store its TWAP since the last reading
+        /// once MIN_TWAP_PERIOD passed. anyone can call it for an allowed pair, buybacks need a TWAP from the last MAX_TWAP_AGE.
+        #[ink(message)]
+        pub fn update_twap(&mut self, pair: AccountId) -> Result<(), BuybackErrors> {
+            // a pair not allowed by the admin could report any price
+            self.check_allowed_pair(pair)?;
+
+            let pair_ref: TradingPairAzeroRef = FromAccountId::from_account_id(pair);
+
+            let (psp22_price_cumulative, a0_price_cumulative, timestamp) =
+                pair_ref.get_price_cumulatives();
+
+            let observation = PriceObservation {
+                psp22_price_cumulative,
+                a0_price_cumulative,
+                timestamp,
+            };
+
+            if let Some(previous_observation) = self.observations.get(pair) {
+                if timestamp.saturating_sub(previous_observation.timestamp) < MIN_TWAP_PERIOD {
+                    return Err(BuybackErrors::TwapPeriodNotElapsed)
+                }
+
+                let twap = get_twap(&previous_observation, &observation)
+                    .ok_or(BuybackErrors::TwapPeriodNotElapsed)?;
+
+                Self::env().emit_event(TwapUpdated {
+                    pair,
+                    psp22_price: twap.psp22_price,
+                    a0_price: twap.a0_price,
+                });
+
+                self.twaps.insert(pair, &twap);
+            }
+
+            self.observations.insert(pair, &observation);
+
+            Ok(())
+        }
+
+        /// function to spend `amount` of the AZERO held by this contract on PANX, then burn or lock it.
+        #[ink(message)]
+        pub fn buyback_a0(&mut self, amount: Balance) -> Result<Balance, BuybackErrors> {
+            if amount == 0 {
+                return Err(BuybackErrors::ZeroAmount)
+            }
+
+            // the existential deposit of this contract can't be spent
+            if amount
+                > self
+                    .env()
+                    .balance()
+                    .saturating_sub(self.env().minimum_balance())
+            {
+                return Err(BuybackErrors::InsufficientBalance)
+            }
+
+            let panx_amount = self.buy_panx_with_a0(amount)?;
+
+            self.sink_panx(panx_amount)?;
+
+            self.add_to_total_spent(None, amount)?;
+
+            Self::env().emit_event(BuybackExecuted {
+                token: None,
+                amount_in: amount,
+                a0_amount: amount,
+                panx_amount,
+                sink: self.sink,
+            });
+
+            Ok(panx_amount)
+        }
+
+        /// function to sell `amount` of the PSP22 token of an allowed `pair` held by this contract for AZERO, spend it on PANX,
+        /// then burn or lock the PANX. PANX itself (fees of the PANX pair) goes straight to the sink.
+        #[ink(message)]
+        pub fn buyback_psp22(
+            &mut self,
+            pair: AccountId,
+            amount: Balance,
+        ) -> Result<Balance, BuybackErrors> {
+            if amount == 0 {
+                return Err(BuybackErrors::ZeroAmount)
+            }
+
+            // a pair not allowed by the admin could report any token and price, then take the approved amount
+            self.check_allowed_pair(pair)?;
+
+            let mut pair_ref: TradingPairAzeroRef = FromAccountId::from_account_id(pair);
+
+            let token = pair_ref.get_psp22_token();
+
+            if amount > PSP22Ref::balance_of(&token, self.env().account_id()) {
+                return Err(BuybackErrors::InsufficientBalance)
+            }
+
+            let a0_amount: Balance;
+
+            let panx_amount: Balance;
+
+            if token == self.panx_token {
+                a0_amount = 0;
+
+                panx_amount = amount;
+            } else {
+                let twap = self.get_recent_twap(pair)?;
+
+                // amount of AZERO the trade returns at the current reserves
+                let a0_amount_out = match pair_ref.get_est_price_psp22_to_a0(amount) {
+                    Ok(result) => result,
+                    Err(error) => return Err(BuybackErrors::Swap(error)),
+                };
+
+                self.check_amount_out(amount, a0_amount_out, twap.psp22_price)?;
+
+                // cross contract call to the PSP22 token to let the pair take the sold amount
+                if let Err(error) = PSP22Ref::approve(&token, pair, amount) {
+                    return Err(BuybackErrors::PSP22ApproveFailed(error))
+                }
+
+                let a0_balance_before = self.env().balance();
+
+                check_swap_result(
+                    pair_ref
+                        .call_mut()
+                        .swap_psp22(amount, a0_amount_out, 0)
+                        .try_invoke(),
+                )?;
+
+                a0_amount = self.env().balance().saturating_sub(a0_balance_before);
+
+                panx_amount = self.buy_panx_with_a0(a0_amount)?;
+            }
+
+            self.sink_panx(panx_amount)?;
+
+            self.add_to_total_spent(Some(token), amount)?;
+
+            Self::env().emit_event(BuybackExecuted {
+                token: Some(token),
+                amount_in: amount,
+                a0_amount,
+                panx_amount,
+                sink: self.sink,
+            });
+
+            Ok(panx_amount)
+        }
+
+        /// function for the admin to set the largest distance below the TWAP a trade may fill at, in basis points.
+        /// the distance includes the fees of the pairs.
+        #[ink(message)]
+        pub fn set_max_slippage_bps(&mut self, max_slippage_bps: Balance) -> Result<(), BuybackErrors> {
+            if self.env().caller() != self.admin {
+                return Err(BuybackErrors::CallerIsNotAdmin)
+            }
+
+            if max_slippage_bps > SLIPPAGE_DENOMINATOR {
+                return Err(BuybackErrors::InvalidSlippage)
+            }
+
+            self.max_slippage_bps = max_slippage_bps;
+
+            Ok(())
+        }
+
+        /// function for the admin to set whether the bought PANX is burned or locked
+        #[ink(message)]
+        pub fn set_sink(&mut self, sink: PanxSink) -> Result<(), BuybackErrors> {
+            if self.env().caller() != self.admin {
+                return Err(BuybackErrors::CallerIsNotAdmin)
+            }
+
+            self.sink = sink;
+
+            Ok(())
+        }
+
+        /// function for the admin to allow or disallow a trading pair for buybacks and TWAP updates
+        #[ink(message)]
+        pub fn set_allowed_pair(
+            &mut self,
+            pair: AccountId,
+            allowed: bool,
+        ) -> Result<(), BuybackErrors> {
+            if self.env().caller() != self.admin {
+                return Err(BuybackErrors::CallerIsNotAdmin)
+            }
+
+            if allowed {
+                self.allowed_pairs.insert(pair, &true);
+            } else {
+                self.allowed_pairs.remove(pair);
+            }
+
+            Self::env().emit_event(AllowedPairSet { pair, allowed });
+
+            Ok(())
+        }
+
+        /// function to get whether buybacks can trade and read a pair
+        #[ink(message)]
+        pub fn is_allowed_pair(&self, pair: AccountId) -> bool {
+            pair == self.panx_pair || self.allowed_pairs.get(pair).unwrap_or(false)
+        }
+
+        /// function to get the last time weighted average prices of a pair
+        #[ink(message)]
+        pub fn get_twap_of(&self, pair: AccountId) -> Option<Twap> {
+            self.twaps.get(pair)
+        }
+
+        /// function to get the last price cumulatives read from a pair
+        #[ink(message)]
+        pub fn get_observation(&self, pair: AccountId) -> Option<PriceObservation> {
+            self.observations.get(pair)
+        }
+
+        /// function to get the overall amount spent of a token, None for AZERO
+        #[ink(message)]
+        pub fn get_total_spent(&self, token: Option<AccountId>) -> Balance {
+            self.total_spent.get(token).unwrap_or(0)
+        }
+
+        /// function to get the overall PANX bought, burned and locked
+        #[ink(message)]
+        pub fn get_panx_totals(&self) -> (Balance, Balance, Balance) {
+            (
+                self.total_panx_bought,
+                self.total_panx_burned,
+                self.total_panx_locked,
+            )
+        }
+
+        /// function to get the max slippage in basis points and the PANX sink
+        #[ink(message)]
+        pub fn get_settings(&self) -> (Balance, PanxSink) {
+            (self.max_slippage_bps, self.sink)
+        }
+
+        /// function to get the current timestamp in seconds
+        #[ink(message)]
+        pub fn get_current_timestamp(&self) -> u64 {
+            self.env().block_timestamp() / 1000
+        }
+
+        /// function to make sure that the admin allowed a pair.
+        fn check_allowed_pair(&self, pair: AccountId) -> Result<(), BuybackErrors> {
+            if !self.is_allowed_pair(pair) {
+                return Err(BuybackErrors::PairNotAllowed)
+            }
+
+            Ok(())
+        }
+
+        /// function to get the TWAP of a pair, failing if there is none from the last MAX_TWAP_AGE.
+        fn get_recent_twap(&self, pair: AccountId) -> Result<Twap, BuybackErrors> {
+            match self.twaps.get(pair) {
+                Some(twap)
+                    if self.get_current_timestamp().saturating_sub(twap.timestamp)
+                        <= MAX_TWAP_AGE =>
+                {
+                    Ok(twap)
+                }
+                _ => Err(BuybackErrors::TwapNotAvailable),
+            }
+        }
+
+        /// function to make sure that a trade fills no further below the TWAP than the max slippage.
+        fn check_amount_out(
+            &self,
+            amount_in: Balance,
+            amount_out: Balance,
+            twap_price: Balance,
+        ) -> Result<(), BuybackErrors> {
+            let min_amount_out = get_min_amount_out(amount_in, twap_price, self.max_slippage_bps)
+                .ok_or(BuybackErrors::Overflow)?;
+
+            if amount_out < min_amount_out {
+                return Err(BuybackErrors::PriceBelowTwap)
+            }
+
+            Ok(())
+        }
+
+        /// function to swap AZERO for PANX through the PANX pair, returns the PANX received.
+        fn buy_panx_with_a0(&mut self, a0_amount: Balance) -> Result<Balance, BuybackErrors> {
+            let mut panx_pair_ref: TradingPairAzeroRef =
+                FromAccountId::from_account_id(self.panx_pair);
+
+            let twap = self.get_recent_twap(self.panx_pair)?;
+
+            // amount of PANX the trade returns at the current reserves
+            let panx_amount_out = match panx_pair_ref.get_est_price_a0_to_psp22(a0_amount) {
+                Ok(result) => result,
+                Err(error) => return Err(BuybackErrors::Swap(error)),
+            };
+
+            self.check_amount_out(a0_amount, panx_amount_out, twap.a0_price)?;
+
+            let panx_balance_before = PSP22Ref::balance_of(&self.panx_token, self.env().account_id());
+
+            check_swap_result(
+                panx_pair_ref
+                    .call_mut()
+                    .swap_a0(panx_amount_out, 0)
+                    .transferred_value(a0_amount)
+                    .try_invoke(),
+            )?;
+
+            let panx_amount = PSP22Ref::balance_of(&self.panx_token, self.env().account_id())
+                .saturating_sub(panx_balance_before);
+
+            match self.total_panx_bought.checked_add(panx_amount) {
+                Some(result) => {
+                    self.total_panx_bought = result;
+                }
+                None => return Err(BuybackErrors::Overflow),
+            };
+
+            Ok(panx_amount)
+        }
+
+        /// function to burn the bought PANX, or to count it as locked in this contract.
+        fn sink_panx(&mut self, panx_amount: Balance) -> Result<(), BuybackErrors> {
+            match self.sink {
+                PanxSink::Burn => {
+                    // cross contract call to the PANX token to burn this contract's PANX
+                    let burn_result = build_call::<DefaultEnvironment>()
+                        .call(self.panx_token)
+                        .exec_input(
+                            ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                                "PSP22Burnable::burn"
+                            )))
+                            .push_arg(self.env().account_id())
+                            .push_arg(panx_amount),
+                        )
+                        .returns::<Result<(), PSP22Error>>()
+                        .try_invoke();
+
+                    if !matches!(burn_result, Ok(Ok(Ok(())))) {
+                        return Err(BuybackErrors::PSP22BurnFailed)
+                    }
+
+                    match self.total_panx_burned.checked_add(panx_amount) {
+                        Some(result) => {
+                            self.total_panx_burned = result;
+                        }
+                        None => return Err(BuybackErrors::Overflow),
+                    };
+                }
+                PanxSink::Lock => {
+                    match self.total_panx_locked.checked_add(panx_amount) {
+                        Some(result) => {
+                            self.total_panx_locked = result;
+                        }
+                        None => return Err(BuybackErrors::Overflow),
+                    };
+                }
+            }
+
+            Ok(())
+        }
+
+        /// function to add a spent amount to the overall amount spent of a token.
+        fn add_to_total_spent(
+            &mut self,
+            token: Option<AccountId>,
+            amount: Balance,
+        ) -> Result<(), BuybackErrors> {
+            match self.get_total_spent(token).checked_add(amount) {
+                Some(result) => {
+                    self.total_spent.insert(token, &result);
+                }
+                None => return Err(BuybackErrors::Overflow),
+            };
+
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[ink::test]
+        fn twap_and_min_amount_out_math() {
+            let previous = PriceObservation {
+                psp22_price_cumulative: Balance::MAX - 99,
+                a0_price_cumulative: 0,
+                timestamp: 1000,
+            };
+
+            // the PSP22 cumulative wrapped around during the window
+            let current = PriceObservation {
+                psp22_price_cumulative: 1900,
+                a0_price_cumulative: 4000,
+                timestamp: 3000,
+            };
+
+            assert_eq!(
+                get_twap(&previous, &current),
+                Some(Twap {
+                    psp22_price: 1,
+                    a0_price: 2,
+                    timestamp: 3000,
+                })
+            );
+            assert_eq!(get_twap(&current, &previous), None);
+            assert_eq!(get_twap(&current, &current), None);
+
+            // 100 tokens at a price of 2, up to 1% below it
+            assert_eq!(
+                get_min_amount_out(100, 2 * 10u128.pow(12), 100),
+                Some(198)
+            );
+            assert_eq!(get_min_amount_out(100, 2 * 10u128.pow(12), 10_001), None);
+        }
+
+        #[ink::test]
+        fn settings_are_admin_only() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut buyback_burn =
+                BuybackBurn::new(accounts.django, accounts.eve, 300, PanxSink::Burn);
+
+            assert_eq!(
+                buyback_burn.set_max_slippage_bps(10_001),
+                Err(BuybackErrors::InvalidSlippage)
+            );
+
+            buyback_burn.set_max_slippage_bps(500).unwrap();
+            buyback_burn.set_sink(PanxSink::Lock).unwrap();
+
+            assert_eq!(buyback_burn.get_settings(), (500, PanxSink::Lock));
+            assert_eq!(
+                buyback_burn.buyback_a0(0),
+                Err(BuybackErrors::ZeroAmount)
+            );
+            assert_eq!(
+                buyback_burn.get_recent_twap(accounts.eve),
+                Err(BuybackErrors::TwapNotAvailable)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            assert_eq!(
+                buyback_burn.set_sink(PanxSink::Burn),
+                Err(BuybackErrors::CallerIsNotAdmin)
+            );
+        }
+
+        #[ink::test]
+        fn only_allowed_pairs_are_traded_and_read() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut buyback_burn =
+                BuybackBurn::new(accounts.django, accounts.eve, 300, PanxSink::Burn);
+
+            // the PANX pair is always allowed
+            assert!(buyback_burn.is_allowed_pair(accounts.eve));
+            assert!(!buyback_burn.is_allowed_pair(accounts.frank));
+
+            assert_eq!(
+                buyback_burn.buyback_psp22(accounts.frank, 100),
+                Err(BuybackErrors::PairNotAllowed)
+            );
+            assert_eq!(
+                buyback_burn.update_twap(accounts.frank),
+                Err(BuybackErrors::PairNotAllowed)
+            );
+
+            buyback_burn.set_allowed_pair(accounts.frank, true).unwrap();
+
+            assert!(buyback_burn.is_allowed_pair(accounts.frank));
+
+            buyback_burn.set_allowed_pair(accounts.frank, false).unwrap();
+
+            assert!(!buyback_burn.is_allowed_pair(accounts.frank));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            assert_eq!(
+                buyback_burn.set_allowed_pair(accounts.bob, true),
+                Err(BuybackErrors::CallerIsNotAdmin)
+            );
+        }
+    }
+}
@@ -1,30 +1,59 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub use self::pair_creator::PairCreatorRef;
 
 #[ink::contract]
 pub mod pair_creator {
 
+    use ink::storage::Mapping;
     use ink::LangError;
-    use trading_pair_azero::trading_pair_azero::TradingPairAzeroRef;
+    use trading_pair_azero::trading_pair_azero::{
+        EnvErrorCode,
+        TradingPairAzeroRef,
+        TradingPairErrors,
+        MAX_FEE,
+    };
 
     #[ink(storage)]
-    pub struct PairCreator {}
+    pub struct PairCreator {
+        // Admin of the created pairs, allowed to rescue tokens sent to them by mistake
+        admin: AccountId,
+        // Code hash of trading_pair_azero every pair is instantiated from, set by the admin
+        pair_code_hash: Hash,
+        // Number of created AZERO trading pairs
+        pair_count: u32,
+        // Created AZERO trading pairs by creation index
+        pairs: Mapping<u32, AccountId>,
+        // Whether an address is an AZERO trading pair created by this contract
+        registered_pairs: Mapping<AccountId, bool>,
+    }
 
     #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
     pub enum PairCreatorErrors {
-        InstantiatingFailed,
+        Instantiate(EnvErrorCode), // Instantiation failed in the environment (e.g. code hash not found)
+        Lang(LangError),           // The pair constructor could not be dispatched
+        Pair(TradingPairErrors),   // The pair constructor rejected its arguments
+        InvalidFee,                // Error code for an LP fee above the pair's MAX_FEE
+        CallerIsNotAdmin,          // Error code for an admin message called by another account
+        Overflow,                  // Error code for overflow
     }
 
     impl From<ink::env::Error> for PairCreatorErrors {
         fn from(cause: ink::env::Error) -> Self {
-            PairCreatorErrors::InstantiatingFailed
+            PairCreatorErrors::Instantiate(EnvErrorCode::from(cause))
         }
     }
 
     impl From<LangError> for PairCreatorErrors {
         fn from(cause: LangError) -> Self {
-            PairCreatorErrors::InstantiatingFailed
+            PairCreatorErrors::Lang(cause)
+        }
+    }
+
+    impl From<TradingPairErrors> for PairCreatorErrors {
+        fn from(cause: TradingPairErrors) -> Self {
+            PairCreatorErrors::Pair(cause)
         }
     }
 
@@ -45,21 +74,40 @@ pub mod pair_creator {
 
     impl PairCreator {
         #[ink(constructor)]
-        pub fn new() -> Self {
-            Self {}
+        pub fn new(
+            pair_code_hash: Hash, // Code hash of the uploaded trading_pair_azero contract
+        ) -> Self {
+            let admin = Self::env().caller(); // The creator deployer administers the created pairs
+            let pair_count: u32 = 0; // No pairs created yet
+            let pairs = Mapping::default(); // Mapping to store the created pairs by index
+            let registered_pairs = Mapping::default(); // Mapping to store whether an address is a created pair
+
+            Self {
+                admin,
+                pair_code_hash,
+                pair_count,
+                pairs,
+                registered_pairs,
+            }
         }
 
+        /// function to create an AZERO trading pair from the code hash set by the admin and register it.
+        /// only pairs instantiated from that code are registered, so `is_pair` vouches for their code.
         #[ink(message, payable)]
         pub fn create_azero_trading_pair(
             &mut self,
-            azero_trading_pair_hash: Hash,
             version: u32,
             psp22_addrr: AccountId,
-            fee: Balance,
+            fee: Balance, // LP fee in parts per million (1% = 10000)
             panx_contract: AccountId,
             vault_address: AccountId,
             lp_lock_timestamp: u64, // Lp lock timestamp
         ) -> Result<AccountId, PairCreatorErrors> {
+            // the fee is in parts per million, a pair with a higher fee could not quote any swap
+            if fee > MAX_FEE {
+                return Err(PairCreatorErrors::InvalidFee)
+            }
+
             let salt = version.to_le_bytes();
 
             let deployer = self.env().caller();
@@ -70,21 +118,151 @@ pub mod pair_creator {
                 panx_contract,
                 vault_address,
                 lp_lock_timestamp,
-                deployer
+                deployer,
+                self.admin,
             )
             .endowment(0)
-            .code_hash(azero_trading_pair_hash)
+            .code_hash(self.pair_code_hash)
             .salt_bytes(salt)
-            .try_instantiate()??;
+            .try_instantiate()???;
 
             let new_pair_address = trading_pair.get_account_id();
 
+            // registering the pair, so gauges and other contracts can check it was created here
+            self.register_pair(new_pair_address)?;
+
             Ok(new_pair_address)
         }
 
+        /// function for the admin to set the trading_pair_azero code hash new pairs are instantiated from.
+        /// pairs created before keep their registration.
+        #[ink(message)]
+        pub fn set_pair_code_hash(&mut self, pair_code_hash: Hash) -> Result<(), PairCreatorErrors> {
+            if self.env().caller() != self.admin {
+                return Err(PairCreatorErrors::CallerIsNotAdmin)
+            }
+
+            self.pair_code_hash = pair_code_hash;
+
+            Ok(())
+        }
+
+        /// function to get the trading_pair_azero code hash new pairs are instantiated from
+        #[ink(message)]
+        pub fn get_pair_code_hash(&self) -> Hash {
+            self.pair_code_hash
+        }
+
+        /// function to get the number of created AZERO trading pairs
+        #[ink(message)]
+        pub fn get_pair_count(&self) -> u32 {
+            self.pair_count
+        }
 
+        /// function to get the AZERO trading pair created at an index, None past the last pair
+        #[ink(message)]
+        pub fn get_pair(&self, index: u32) -> Option<AccountId> {
+            self.pairs.get(index)
+        }
 
+        /// function to check whether an address is an AZERO trading pair created by this contract
+        #[ink(message)]
+        pub fn is_pair(&self, pair: AccountId) -> bool {
+            self.registered_pairs.get(pair).unwrap_or(false)
+        }
 
+        /// function to get the admin of the created pairs
+        #[ink(message)]
+        pub fn get_admin(&self) -> AccountId {
+            self.admin
+        }
+
+        /// function to list a created pair under the next index and mark it as registered.
+        fn register_pair(&mut self, pair: AccountId) -> Result<(), PairCreatorErrors> {
+            self.pairs.insert(self.pair_count, &pair);
+
+            self.registered_pairs.insert(pair, &true);
+
+            match self.pair_count.checked_add(1) {
+                Some(result) => {
+                    self.pair_count = result;
+                }
+                None => return Err(PairCreatorErrors::Overflow),
+            };
+
+            Ok(())
+        }
 
+
+
+
+
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[ink::test]
+        fn fees_above_the_pair_maximum_are_rejected() {
+            let mut pair_creator = PairCreator::new(Hash::from([0x01; 32]));
+
+            assert_eq!(
+                pair_creator.create_azero_trading_pair(
+                    0,
+                    AccountId::from([0x02; 32]),
+                    MAX_FEE + 1,
+                    AccountId::from([0x03; 32]),
+                    AccountId::from([0x04; 32]),
+                    0,
+                ),
+                Err(PairCreatorErrors::InvalidFee)
+            );
+            assert_eq!(pair_creator.get_pair_count(), 0);
+        }
+
+        #[ink::test]
+        fn registered_pairs_are_listed_by_index() {
+            let mut pair_creator = PairCreator::new(Hash::from([0x01; 32]));
+            let first_pair = AccountId::from([0x02; 32]);
+            let second_pair = AccountId::from([0x03; 32]);
+
+            assert_eq!(pair_creator.get_pair_count(), 0);
+            assert_eq!(pair_creator.get_pair(0), None);
+            assert!(!pair_creator.is_pair(first_pair));
+
+            pair_creator.register_pair(first_pair).unwrap();
+            pair_creator.register_pair(second_pair).unwrap();
+
+            assert_eq!(pair_creator.get_pair_count(), 2);
+            assert_eq!(pair_creator.get_pair(0), Some(first_pair));
+            assert_eq!(pair_creator.get_pair(1), Some(second_pair));
+            assert_eq!(pair_creator.get_pair(2), None);
+            assert!(pair_creator.is_pair(first_pair));
+            assert!(pair_creator.is_pair(second_pair));
+            assert!(!pair_creator.is_pair(AccountId::from([0x04; 32])));
+        }
+
+        #[ink::test]
+        fn pair_code_hash_is_set_by_the_admin() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut pair_creator = PairCreator::new(Hash::from([0x01; 32]));
+
+            assert_eq!(pair_creator.get_pair_code_hash(), Hash::from([0x01; 32]));
+
+            pair_creator
+                .set_pair_code_hash(Hash::from([0x05; 32]))
+                .unwrap();
+
+            assert_eq!(pair_creator.get_pair_code_hash(), Hash::from([0x05; 32]));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            assert_eq!(
+                pair_creator.set_pair_code_hash(Hash::from([0x06; 32])),
+                Err(PairCreatorErrors::CallerIsNotAdmin)
+            );
+            assert_eq!(pair_creator.get_pair_code_hash(), Hash::from([0x05; 32]));
+        }
     }
 }
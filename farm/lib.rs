@@ -0,0 +1,997 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::farm::FarmRef;
+
+#[ink::contract]
+pub mod farm {
+
+    use ink::env::{
+        call::{
+            build_call,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+    }; // Importing call builders from ink env
+    use ink::prelude::vec; // Importing vec from ink prelude
+    use ink::storage::Mapping; // Importing Mapping from ink storage
+    use openbrush::contracts::traits::psp22::{
+        PSP22Error,
+        PSP22Ref,
+    };
+    use primitive_types::U256;
+
+    /// Scale of the PANX per deposited LP share accumulators.
+    pub const REWARD_PRECISION: Balance = 1_000_000_000_000;
+
+    /// Largest number of pools, so that updating every pool fits in a block.
+    pub const MAX_POOLS: u32 = 50;
+
+    /// function to get `amount` times `price` divided by `denominator`, computed in U256.
+    fn mul_div(amount: Balance, price: Balance, denominator: Balance) -> Option<Balance> {
+        let result = (U256::from(amount) * U256::from(price)).checked_div(U256::from(denominator))?;
+
+        Balance::try_from(result).ok()
+    }
+
+    /// Pool of a trading pair's LP token.
+    #[derive(Debug, Default, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Pool {
+        pub lp_token: AccountId,           // Trading pair whose LP shares are deposited
+        pub alloc_point: Balance,          // Share of the PANX emission, relative to the other pools
+        pub last_reward_timestamp: u64,    // Timestamp (in seconds) the pool was last updated at
+        pub acc_panx_per_share: Balance,   // PANX per deposited LP share since the pool was added, scaled by REWARD_PRECISION
+        pub total_deposited: Balance,      // Overall deposited LP shares
+    }
+
+    /// Deposit of an account in a pool.
+    #[derive(Debug, Default, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct UserInfo {
+        pub amount: Balance,      // Deposited LP shares
+        pub reward_debt: Balance, // PANX per share already accounted for, times the deposited LP shares
+    }
+
+    #[ink(storage)]
+    pub struct Farm {
+        // Account allowed to add pools and set the emission
+        admin: AccountId,
+        // PairCreator whose AZERO trading pairs can get a pool
+        pair_creator: AccountId,
+        // PANX PSP22 token the pools are rewarded in
+        panx_token: AccountId,
+        // PANX emitted every second, shared by the pools by allocation point
+        panx_per_second: Balance,
+        // Sum of the allocation points of all pools
+        total_alloc_point: Balance,
+        // Number of pools
+        pool_count: u32,
+        // Pools by id
+        pools: Mapping<u32, Pool>,
+        // Pool id of each LP token
+        pool_of_lp_token: Mapping<AccountId, u32>,
+        // Deposit of each account in each pool
+        users: Mapping<(u32, AccountId), UserInfo>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+    pub enum FarmErrors {
+        CallerIsNotAdmin,                    // Error code for an admin message called by another account
+        TooManyPools,                        // Error code for adding more than MAX_POOLS pools
+        PoolAlreadyAdded,                    // Error code for adding a second pool of an LP token
+        PanxPoolNotAllowed,                  // Error code for adding a pool of the PANX token, whose rewards would be deposits
+        PairNotRegistered,                   // Error code for adding a pool of a pair PairCreator did not create
+        UnknownPool,                         // Error code for a pool id that does not exist
+        ZeroAmount,                          // Error code for depositing or withdrawing 0 LP shares
+        InsufficientDeposit,                 // Error code for withdrawing more than the deposited LP shares
+        NothingToHarvest,                    // Error code for harvesting with no pending PANX
+        Overflow,                            // Error code for overflow
+        PSP22TransferFromFailed(PSP22Error), // Error code for a failed transfer of LP shares to the farm
+        PSP22TransferFailed(PSP22Error),     // Error code for a failed transfer of LP shares or PANX
+    }
+
+    #[ink(event)]
+    pub struct PoolAdded {
+        #[ink(topic)]
+        pool_id: u32,         // Id of the pool
+        #[ink(topic)]
+        lp_token: AccountId,  // Trading pair whose LP shares are deposited
+        alloc_point: Balance, // Allocation point of the pool
+    }
+
+    #[ink(event)]
+    pub struct PoolSet {
+        #[ink(topic)]
+        pool_id: u32,         // Id of the pool
+        alloc_point: Balance, // New allocation point of the pool
+    }
+
+    #[ink(event)]
+    pub struct Deposited {
+        #[ink(topic)]
+        account: AccountId, // Address of the depositor
+        #[ink(topic)]
+        pool_id: u32,       // Id of the pool
+        amount: Balance,    // Deposited LP shares
+    }
+
+    #[ink(event)]
+    pub struct Withdrawn {
+        #[ink(topic)]
+        account: AccountId, // Address of the depositor
+        #[ink(topic)]
+        pool_id: u32,       // Id of the pool
+        amount: Balance,    // LP shares sent back to the depositor
+    }
+
+    #[ink(event)]
+    pub struct Harvested {
+        #[ink(topic)]
+        account: AccountId, // Address of the depositor
+        #[ink(topic)]
+        pool_id: u32,       // Id of the pool
+        amount: Balance,    // PANX sent to the depositor
+    }
+
+    #[ink(event)]
+    pub struct EmergencyWithdrawn {
+        #[ink(topic)]
+        account: AccountId, // Address of the depositor
+        #[ink(topic)]
+        pool_id: u32,       // Id of the pool
+        amount: Balance,    // LP shares sent back to the depositor, its pending PANX forfeited
+    }
+
+    impl Farm {
+        #[ink(constructor)]
+        pub fn new(
+            pair_creator: AccountId,  // Address of the PairCreator contract
+            panx_token: AccountId,    // Address of the PANX token contract
+            panx_per_second: Balance, // PANX emitted every second
+        ) -> Self {
+            let admin = Self::env().caller(); // The deployer adds the pools
+            let total_alloc_point: Balance = 0; // No pools yet
+            let pool_count: u32 = 0; // No pools yet
+            let pools = Mapping::default(); // Mapping to store the pools
+            let pool_of_lp_token = Mapping::default(); // Mapping to store the pool of each LP token
+            let users = Mapping::default(); // Mapping to store the deposits of accounts
+
+            Self {
+                admin,
+                pair_creator,
+                panx_token,
+                panx_per_second,
+                total_alloc_point,
+                pool_count,
+                pools,
+                pool_of_lp_token,
+                users,
+            }
+        }
+
+        /// function for the admin to add a pool of the LP token of an AZERO trading pair created by PairCreator.
+        #[ink(message)]
+        pub fn add_pool(
+            &mut self,
+            lp_token: AccountId,
+            alloc_point: Balance,
+        ) -> Result<u32, FarmErrors> {
+            self.check_admin()?;
+
+            // the farm holds the PANX rewards, PANX deposits could be paid out as rewards
+            if lp_token == self.panx_token {
+                return Err(FarmErrors::PanxPoolNotAllowed)
+            }
+
+            // cross contract call to PairCreator to check it created the pair
+            let is_pair = build_call::<DefaultEnvironment>()
+                .call(self.pair_creator)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("is_pair")))
+                        .push_arg(lp_token),
+                )
+                .returns::<bool>()
+                .try_invoke();
+
+            if !matches!(is_pair, Ok(Ok(true))) {
+                return Err(FarmErrors::PairNotRegistered)
+            }
+
+            self.insert_pool(lp_token, alloc_point)
+        }
+
+        /// function to add a pool of a validated LP token, returns the pool id.
+        fn insert_pool(&mut self, lp_token: AccountId, alloc_point: Balance) -> Result<u32, FarmErrors> {
+            if self.pool_count >= MAX_POOLS {
+                return Err(FarmErrors::TooManyPools)
+            }
+
+            if self.pool_of_lp_token.contains(lp_token) {
+                return Err(FarmErrors::PoolAlreadyAdded)
+            }
+
+            // the emission of the other pools changes from now on
+            self.mass_update_pools()?;
+
+            match self.total_alloc_point.checked_add(alloc_point) {
+                Some(result) => {
+                    self.total_alloc_point = result;
+                }
+                None => return Err(FarmErrors::Overflow),
+            };
+
+            let pool_id = self.pool_count;
+
+            self.pools.insert(
+                pool_id,
+                &Pool {
+                    lp_token,
+                    alloc_point,
+                    last_reward_timestamp: self.get_current_timestamp(),
+                    acc_panx_per_share: 0,
+                    total_deposited: 0,
+                },
+            );
+
+            self.pool_of_lp_token.insert(lp_token, &pool_id);
+
+            // pool_count is below MAX_POOLS
+            self.pool_count += 1;
+
+            Self::env().emit_event(PoolAdded {
+                pool_id,
+                lp_token,
+                alloc_point,
+            });
+
+            Ok(pool_id)
+        }
+
+        /// function for the admin to set the allocation point of a pool.
+        #[ink(message)]
+        pub fn set_pool(&mut self, pool_id: u32, alloc_point: Balance) -> Result<(), FarmErrors> {
+            self.check_admin()?;
+
+            let mut pool = self.get_pool(pool_id).ok_or(FarmErrors::UnknownPool)?;
+
+            // the emission of every pool changes from now on
+            self.mass_update_pools()?;
+
+            // the pool's allocation point is part of the total
+            match (self.total_alloc_point - pool.alloc_point).checked_add(alloc_point) {
+                Some(result) => {
+                    self.total_alloc_point = result;
+                }
+                None => return Err(FarmErrors::Overflow),
+            };
+
+            pool = self.get_pool(pool_id).ok_or(FarmErrors::UnknownPool)?;
+
+            pool.alloc_point = alloc_point;
+
+            self.pools.insert(pool_id, &pool);
+
+            Self::env().emit_event(PoolSet {
+                pool_id,
+                alloc_point,
+            });
+
+            Ok(())
+        }
+
+        /// function for the admin to set the PANX emitted every second.
+        #[ink(message)]
+        pub fn set_panx_per_second(&mut self, panx_per_second: Balance) -> Result<(), FarmErrors> {
+            self.check_admin()?;
+
+            // PANX emitted until now uses the previous rate
+            self.mass_update_pools()?;
+
+            self.panx_per_second = panx_per_second;
+
+            Ok(())
+        }
+
+        /// function to update the PANX per share of every pool, anyone can call it.
+        #[ink(message)]
+        pub fn mass_update_pools(&mut self) -> Result<(), FarmErrors> {
+            for pool_id in 0..self.pool_count {
+                self.update_pool(pool_id)?;
+            }
+
+            Ok(())
+        }
+
+        /// function to update the PANX per share of a pool up to now, anyone can call it.
+        #[ink(message)]
+        pub fn update_pool(&mut self, pool_id: u32) -> Result<Pool, FarmErrors> {
+            let mut pool = self.get_pool(pool_id).ok_or(FarmErrors::UnknownPool)?;
+
+            let current_timestamp = self.get_current_timestamp();
+
+            if current_timestamp <= pool.last_reward_timestamp {
+                return Ok(pool)
+            }
+
+            pool.acc_panx_per_share = self.get_acc_panx_per_share(&pool, current_timestamp)?;
+
+            pool.last_reward_timestamp = current_timestamp;
+
+            self.pools.insert(pool_id, &pool);
+
+            Ok(pool)
+        }
+
+        /// function to deposit `amount` LP shares in a pool, harvesting the pending PANX.
+        /// the caller has to approve the farm first.
+        #[ink(message)]
+        pub fn deposit(&mut self, pool_id: u32, amount: Balance) -> Result<(), FarmErrors> {
+            let caller = self.env().caller();
+
+            if amount == 0 {
+                return Err(FarmErrors::ZeroAmount)
+            }
+
+            let mut pool = self.update_pool(pool_id)?;
+
+            let mut user = self.get_user_info(pool_id, caller);
+
+            let pending_panx = self.get_pending_of(&pool, &user)?;
+
+            match user.amount.checked_add(amount) {
+                Some(result) => {
+                    user.amount = result;
+                }
+                None => return Err(FarmErrors::Overflow),
+            };
+
+            match pool.total_deposited.checked_add(amount) {
+                Some(result) => {
+                    pool.total_deposited = result;
+                }
+                None => return Err(FarmErrors::Overflow),
+            };
+
+            user.reward_debt = self.get_reward_debt(&pool, user.amount)?;
+
+            self.users.insert((pool_id, caller), &user);
+
+            self.pools.insert(pool_id, &pool);
+
+            // cross contract call to the pair to transfer the LP shares
+            if let Err(error) = PSP22Ref::transfer_from(
+                &pool.lp_token,
+                caller,
+                self.env().account_id(),
+                amount,
+                vec![],
+            ) {
+                return Err(FarmErrors::PSP22TransferFromFailed(error))
+            }
+
+            self.send_panx(caller, pool_id, pending_panx)?;
+
+            Self::env().emit_event(Deposited {
+                account: caller,
+                pool_id,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        /// function to withdraw `amount` deposited LP shares from a pool, harvesting the pending PANX.
+        #[ink(message)]
+        pub fn withdraw(&mut self, pool_id: u32, amount: Balance) -> Result<(), FarmErrors> {
+            let caller = self.env().caller();
+
+            if amount == 0 {
+                return Err(FarmErrors::ZeroAmount)
+            }
+
+            let mut pool = self.update_pool(pool_id)?;
+
+            let mut user = self.get_user_info(pool_id, caller);
+
+            let pending_panx = self.get_pending_of(&pool, &user)?;
+
+            match user.amount.checked_sub(amount) {
+                Some(result) => {
+                    user.amount = result;
+                }
+                None => return Err(FarmErrors::InsufficientDeposit),
+            };
+
+            // amount is at most the caller's deposit, part of the total
+            pool.total_deposited -= amount;
+
+            user.reward_debt = self.get_reward_debt(&pool, user.amount)?;
+
+            self.users.insert((pool_id, caller), &user);
+
+            self.pools.insert(pool_id, &pool);
+
+            // cross contract call to the pair to send the LP shares back
+            if let Err(error) = PSP22Ref::transfer(&pool.lp_token, caller, amount, vec![]) {
+                return Err(FarmErrors::PSP22TransferFailed(error))
+            }
+
+            self.send_panx(caller, pool_id, pending_panx)?;
+
+            Self::env().emit_event(Withdrawn {
+                account: caller,
+                pool_id,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        /// function to send the caller its pending PANX of a pool.
+        #[ink(message)]
+        pub fn harvest(&mut self, pool_id: u32) -> Result<(), FarmErrors> {
+            let caller = self.env().caller();
+
+            let pool = self.update_pool(pool_id)?;
+
+            let mut user = self.get_user_info(pool_id, caller);
+
+            let pending_panx = self.get_pending_of(&pool, &user)?;
+
+            if pending_panx == 0 {
+                return Err(FarmErrors::NothingToHarvest)
+            }
+
+            user.reward_debt = self.get_reward_debt(&pool, user.amount)?;
+
+            self.users.insert((pool_id, caller), &user);
+
+            self.send_panx(caller, pool_id, pending_panx)?;
+
+            Ok(())
+        }
+
+        /// function to withdraw all of the caller's LP shares from a pool without harvesting, its pending PANX is forfeited.
+        /// it does not depend on the PANX held by the farm, so the LP shares can always be withdrawn.
+        #[ink(message)]
+        pub fn emergency_withdraw(&mut self, pool_id: u32) -> Result<(), FarmErrors> {
+            let caller = self.env().caller();
+
+            let mut pool = self.get_pool(pool_id).ok_or(FarmErrors::UnknownPool)?;
+
+            let user = self.get_user_info(pool_id, caller);
+
+            if user.amount == 0 {
+                return Err(FarmErrors::ZeroAmount)
+            }
+
+            self.users.remove((pool_id, caller));
+
+            // the caller's deposit is part of the total
+            pool.total_deposited -= user.amount;
+
+            self.pools.insert(pool_id, &pool);
+
+            // cross contract call to the pair to send the LP shares back
+            if let Err(error) = PSP22Ref::transfer(&pool.lp_token, caller, user.amount, vec![]) {
+                return Err(FarmErrors::PSP22TransferFailed(error))
+            }
+
+            Self::env().emit_event(EmergencyWithdrawn {
+                account: caller,
+                pool_id,
+                amount: user.amount,
+            });
+
+            Ok(())
+        }
+
+        /// function to get the PANX an account can harvest from a pool, as of now.
+        #[ink(message)]
+        pub fn get_pending_panx(&self, pool_id: u32, account: AccountId) -> Balance {
+            let Some(mut pool) = self.get_pool(pool_id) else {
+                return 0
+            };
+
+            let current_timestamp = self.get_current_timestamp();
+
+            if current_timestamp > pool.last_reward_timestamp {
+                pool.acc_panx_per_share = self
+                    .get_acc_panx_per_share(&pool, current_timestamp)
+                    .unwrap_or(pool.acc_panx_per_share);
+            }
+
+            self.get_pending_of(&pool, &self.get_user_info(pool_id, account))
+                .unwrap_or(0)
+        }
+
+        /// function to get a pool, None if it does not exist
+        #[ink(message)]
+        pub fn get_pool(&self, pool_id: u32) -> Option<Pool> {
+            self.pools.get(pool_id)
+        }
+
+        /// function to get the number of pools
+        #[ink(message)]
+        pub fn get_pool_count(&self) -> u32 {
+            self.pool_count
+        }
+
+        /// function to get the pool of an LP token, None if it has none
+        #[ink(message)]
+        pub fn get_pool_of_lp_token(&self, lp_token: AccountId) -> Option<u32> {
+            self.pool_of_lp_token.get(lp_token)
+        }
+
+        /// function to get the deposit of an account in a pool
+        #[ink(message)]
+        pub fn get_user_info(&self, pool_id: u32, account: AccountId) -> UserInfo {
+            self.users.get((pool_id, account)).unwrap_or_default()
+        }
+
+        /// function to get the PANX emitted every second and the sum of the allocation points
+        #[ink(message)]
+        pub fn get_emission(&self) -> (Balance, Balance) {
+            (self.panx_per_second, self.total_alloc_point)
+        }
+
+        /// function to get the current timestamp in seconds
+        #[ink(message)]
+        pub fn get_current_timestamp(&self) -> u64 {
+            self.env().block_timestamp() / 1000
+        }
+
+        /// function to return an error if the caller is not the admin.
+        fn check_admin(&self) -> Result<(), FarmErrors> {
+            if self.env().caller() != self.admin {
+                return Err(FarmErrors::CallerIsNotAdmin)
+            }
+
+            Ok(())
+        }
+
+        /// function to get the PANX per share of a pool after adding its emission up to `timestamp`.
+        fn get_acc_panx_per_share(&self, pool: &Pool, timestamp: u64) -> Result<Balance, FarmErrors> {
+            // PANX emitted while nothing is deposited is not given to anyone
+            if pool.total_deposited == 0 || self.total_alloc_point == 0 {
+                return Ok(pool.acc_panx_per_share)
+            }
+
+            let elapsed_seconds = timestamp.saturating_sub(pool.last_reward_timestamp);
+
+            let pool_panx = mul_div(
+                self.panx_per_second
+                    .checked_mul(elapsed_seconds as Balance)
+                    .ok_or(FarmErrors::Overflow)?,
+                pool.alloc_point,
+                self.total_alloc_point,
+            )
+            .ok_or(FarmErrors::Overflow)?;
+
+            let acc_panx_per_share_increase =
+                mul_div(pool_panx, REWARD_PRECISION, pool.total_deposited)
+                    .ok_or(FarmErrors::Overflow)?;
+
+            match pool
+                .acc_panx_per_share
+                .checked_add(acc_panx_per_share_increase)
+            {
+                Some(result) => Ok(result),
+                None => Err(FarmErrors::Overflow),
+            }
+        }
+
+        /// function to get the PANX per share of a pool already accounted for an amount of deposited LP shares.
+        fn get_reward_debt(&self, pool: &Pool, amount: Balance) -> Result<Balance, FarmErrors> {
+            mul_div(amount, pool.acc_panx_per_share, REWARD_PRECISION).ok_or(FarmErrors::Overflow)
+        }
+
+        /// function to get the PANX of a deposit not harvested yet, with the pool updated.
+        fn get_pending_of(&self, pool: &Pool, user: &UserInfo) -> Result<Balance, FarmErrors> {
+            Ok(self
+                .get_reward_debt(pool, user.amount)?
+                .saturating_sub(user.reward_debt))
+        }
+
+        /// function to send harvested PANX to an account.
+        fn send_panx(
+            &mut self,
+            account: AccountId,
+            pool_id: u32,
+            amount: Balance,
+        ) -> Result<(), FarmErrors> {
+            if amount == 0 {
+                return Ok(())
+            }
+
+            // cross contract call to the PANX token to send the harvested PANX
+            if let Err(error) = PSP22Ref::transfer(&self.panx_token, account, amount, vec![]) {
+                return Err(FarmErrors::PSP22TransferFailed(error))
+            }
+
+            Self::env().emit_event(Harvested {
+                account,
+                pool_id,
+                amount,
+            });
+
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn set_timestamp(seconds: u64) {
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(seconds * 1000);
+        }
+
+        #[ink::test]
+        fn emission_is_shared_by_allocation_point_and_deposit() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let panx_token = AccountId::from([0x01; 32]);
+            let mut farm = Farm::new(AccountId::from([0x02; 32]), panx_token, 100);
+            let lp_token_a = AccountId::from([0x0A; 32]);
+            let lp_token_b = AccountId::from([0x0B; 32]);
+
+            set_timestamp(1000);
+
+            assert_eq!(
+                farm.add_pool(panx_token, 1),
+                Err(FarmErrors::PanxPoolNotAllowed)
+            );
+
+            // the pairs are checked with PairCreator by add_pool
+            assert_eq!(farm.insert_pool(lp_token_a, 1), Ok(0));
+            assert_eq!(farm.insert_pool(lp_token_b, 3), Ok(1));
+            assert_eq!(
+                farm.insert_pool(lp_token_a, 1),
+                Err(FarmErrors::PoolAlreadyAdded)
+            );
+
+            // Alice deposits 300 LP shares in pool 1 and Bob 100
+            let mut pool = farm.get_pool(1).unwrap();
+            pool.total_deposited = 400;
+            farm.pools.insert(1, &pool);
+            farm.users.insert(
+                (1, accounts.alice),
+                &UserInfo {
+                    amount: 300,
+                    reward_debt: 0,
+                },
+            );
+            farm.users.insert(
+                (1, accounts.bob),
+                &UserInfo {
+                    amount: 100,
+                    reward_debt: 0,
+                },
+            );
+
+            // pool 1 gets 75 PANX every second
+            set_timestamp(1010);
+
+            assert_eq!(farm.get_pending_panx(1, accounts.alice), 562);
+            assert_eq!(farm.get_pending_panx(1, accounts.bob), 187);
+            assert_eq!(farm.get_pending_panx(0, accounts.alice), 0);
+
+            // doubling pool 0 gives pool 1 60 PANX every second from now on
+            farm.set_pool(0, 2).unwrap();
+
+            assert_eq!(farm.get_emission(), (100, 5));
+
+            set_timestamp(1020);
+
+            assert_eq!(farm.get_pending_panx(1, accounts.alice), 562 + 450);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            assert_eq!(
+                farm.set_panx_per_second(0),
+                Err(FarmErrors::CallerIsNotAdmin)
+            );
+            assert_eq!(farm.harvest(0), Err(FarmErrors::NothingToHarvest));
+            assert_eq!(farm.emergency_withdraw(0), Err(FarmErrors::ZeroAmount));
+        }
+    }
+
+    /// ink! end-to-end (E2E) tests
+    ///
+    /// cargo test --features e2e-tests -- --nocapture
+    ///
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use contract_creator::PairCreatorRef;
+        use ink::primitives::AccountId;
+        use ink_e2e::build_message;
+        use my_psp22::my_psp22::MyPsp22Ref;
+        use openbrush::{
+            contracts::psp22::psp22_external::PSP22,
+            traits::String,
+        };
+        use trading_pair_azero::trading_pair_azero::TradingPairAzeroRef;
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        /// Helper to get Alice's account_id from `ink_e2e::alice()` PairSigner
+        fn get_alice_account_id() -> AccountId {
+            let alice = ink_e2e::alice::<ink_e2e::PolkadotConfig>();
+            let alice_account_id_32 = alice.account_id();
+            let alice_account_id = AccountId::try_from(alice_account_id_32.as_ref()).unwrap();
+
+            alice_account_id
+        }
+
+        /// Helper to get Charlie's account_id from `ink_e2e::charlie()` PairSigner
+        fn get_charlie_account_id() -> AccountId {
+            let charlie = ink_e2e::charlie::<ink_e2e::PolkadotConfig>();
+            let charlie_account_id_32 = charlie.account_id();
+            let charlie_account_id = AccountId::try_from(charlie_account_id_32.as_ref()).unwrap();
+
+            charlie_account_id
+        }
+
+        /// Tests included in "deposit_harvest_and_emergency_withdraw_work":
+        /// 1. add_pool of a pair created by PairCreator
+        /// 2. add_pool of a contract PairCreator did not create
+        /// 3. deposit
+        /// 4. harvest
+        /// 5. emergency_withdraw
+        #[ink_e2e::test(
+            additional_contracts = "../my_psp22/Cargo.toml ../trading_pair_azero/Cargo.toml ../contract_creator/Cargo.toml"
+        )]
+        async fn deposit_harvest_and_emergency_withdraw_work(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            // PSP22 token of the trading pair
+            let psp22_constructor = MyPsp22Ref::new(
+                10000000000000000,
+                Some(String::from("TOKEN").into()),
+                Some(String::from("TKN").into()),
+                12,
+            );
+
+            let psp22_acc_id = client
+                .instantiate("my_psp22", &ink_e2e::alice(), psp22_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // PANX token the pools are rewarded in
+            let panx_constructor = MyPsp22Ref::new(
+                10000000000000000,
+                Some(String::from("PANX").into()),
+                Some(String::from("PANX").into()),
+                12,
+            );
+
+            let panx_acc_id = client
+                .instantiate("my_psp22", &ink_e2e::alice(), panx_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // PairCreator instantiating pairs from the uploaded trading_pair_azero code
+            let pair_code_hash = client
+                .upload("trading_pair_azero", &ink_e2e::alice(), None)
+                .await
+                .expect("upload failed")
+                .code_hash;
+
+            let pair_creator_acc_id = client
+                .instantiate(
+                    "contract_creator",
+                    &ink_e2e::alice(),
+                    PairCreatorRef::new(pair_code_hash),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // 1% LP fee pair of the PSP22 token, without LP lock
+            let create_pair = build_message::<PairCreatorRef>(pair_creator_acc_id.clone()).call(
+                |pair_creator| {
+                    pair_creator.create_azero_trading_pair(
+                        0,
+                        psp22_acc_id,
+                        10000,
+                        panx_acc_id,
+                        get_charlie_account_id(),
+                        0,
+                    )
+                },
+            );
+
+            let pair_acc_id = client
+                .call(&ink_e2e::alice(), create_pair, 0, None)
+                .await
+                .expect("calling `create_pair` failed")
+                .return_value()
+                .expect("pair creation failed");
+
+            // Alice provides 100 PSP22 tokens and 10 A0 to the pair
+            let approve_psp22 = build_message::<MyPsp22Ref>(psp22_acc_id.clone())
+                .call(|my_psp22| my_psp22.approve(pair_acc_id, 100000000000000));
+
+            client
+                .call(&ink_e2e::alice(), approve_psp22, 0, None)
+                .await
+                .expect("calling `approve_psp22` failed");
+
+            let provide_to_pair = build_message::<TradingPairAzeroRef>(pair_acc_id.clone()).call(
+                |trading_pair_azero| {
+                    trading_pair_azero.provide_to_pool(
+                        100000000000000,
+                        10000000000000,
+                        1000000000000000,
+                        500000000000,
+                    )
+                },
+            );
+
+            client
+                .call(&ink_e2e::alice(), provide_to_pair, 10000000000000, None)
+                .await
+                .expect("calling `provide_to_pair` failed");
+
+            // Farm emitting 0.001 PANX every second
+            let farm_acc_id = client
+                .instantiate(
+                    "farm",
+                    &ink_e2e::alice(),
+                    FarmRef::new(pair_creator_acc_id, panx_acc_id, 1000000000),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            let add_pool = build_message::<FarmRef>(farm_acc_id.clone())
+                .call(|farm| farm.add_pool(pair_acc_id, 1));
+
+            let add_pool_result = client
+                .call(&ink_e2e::alice(), add_pool, 0, None)
+                .await
+                .expect("calling `add_pool` failed")
+                .return_value();
+
+            assert_eq!(add_pool_result, Ok(0));
+
+            // a contract PairCreator did not create can't get a pool
+            let add_psp22_pool = build_message::<FarmRef>(farm_acc_id.clone())
+                .call(|farm| farm.add_pool(psp22_acc_id, 1));
+
+            let add_psp22_pool_result = client
+                .call_dry_run(&ink_e2e::alice(), &add_psp22_pool, 0, None)
+                .await
+                .return_value();
+
+            assert_eq!(add_psp22_pool_result, Err(FarmErrors::PairNotRegistered));
+
+            // Alice funds the farm with the PANX it emits
+            let transfer_panx = build_message::<MyPsp22Ref>(panx_acc_id.clone())
+                .call(|my_psp22| my_psp22.transfer(farm_acc_id, 1000000000000000, vec![]));
+
+            client
+                .call(&ink_e2e::alice(), transfer_panx, 0, None)
+                .await
+                .expect("calling `transfer_panx` failed");
+
+            // Alice deposits 400 of her 1000 LP shares
+            let approve_lp = build_message::<TradingPairAzeroRef>(pair_acc_id.clone()).call(
+                |trading_pair_azero| trading_pair_azero.approve(farm_acc_id, 400000000000000),
+            );
+
+            client
+                .call(&ink_e2e::alice(), approve_lp, 0, None)
+                .await
+                .expect("calling `approve_lp` failed");
+
+            let deposit = build_message::<FarmRef>(farm_acc_id.clone())
+                .call(|farm| farm.deposit(0, 400000000000000));
+
+            client
+                .call(&ink_e2e::alice(), deposit, 0, None)
+                .await
+                .expect("calling `deposit` failed");
+
+            let get_user_info = build_message::<FarmRef>(farm_acc_id.clone())
+                .call(|farm| farm.get_user_info(0, get_alice_account_id()));
+
+            let user_info = client
+                .call_dry_run(&ink_e2e::alice(), &get_user_info, 0, None)
+                .await
+                .return_value();
+
+            assert_eq!(user_info.amount, 400000000000000);
+
+            // Let some seconds of emission pass before the next block
+            std::thread::sleep(std::time::Duration::from_secs(3));
+
+            let get_panx_balance = build_message::<MyPsp22Ref>(panx_acc_id.clone())
+                .call(|my_psp22| my_psp22.balance_of(get_alice_account_id()));
+
+            let panx_balance_before = client
+                .call_dry_run(&ink_e2e::alice(), &get_panx_balance, 0, None)
+                .await
+                .return_value();
+
+            let harvest = build_message::<FarmRef>(farm_acc_id.clone())
+                .call(|farm| farm.harvest(0));
+
+            client
+                .call(&ink_e2e::alice(), harvest, 0, None)
+                .await
+                .expect("calling `harvest` failed");
+
+            let panx_balance_after_harvest = client
+                .call_dry_run(&ink_e2e::alice(), &get_panx_balance, 0, None)
+                .await
+                .return_value();
+
+            // Alice is the only depositor of the only pool, she got the seconds emitted so far
+            assert!(panx_balance_after_harvest - panx_balance_before >= 1000000000);
+
+            std::thread::sleep(std::time::Duration::from_secs(3));
+
+            // Alice takes her LP shares back without the PANX emitted since the harvest
+            let emergency_withdraw = build_message::<FarmRef>(farm_acc_id.clone())
+                .call(|farm| farm.emergency_withdraw(0));
+
+            client
+                .call(&ink_e2e::alice(), emergency_withdraw, 0, None)
+                .await
+                .expect("calling `emergency_withdraw` failed");
+
+            let get_lp_balance = build_message::<TradingPairAzeroRef>(pair_acc_id.clone())
+                .call(|trading_pair_azero| trading_pair_azero.balance_of(get_alice_account_id()));
+
+            let lp_balance = client
+                .call_dry_run(&ink_e2e::alice(), &get_lp_balance, 0, None)
+                .await
+                .return_value();
+
+            assert_eq!(lp_balance, 1000000000000000);
+
+            let panx_balance_after_withdraw = client
+                .call_dry_run(&ink_e2e::alice(), &get_panx_balance, 0, None)
+                .await
+                .return_value();
+
+            assert_eq!(panx_balance_after_withdraw, panx_balance_after_harvest);
+
+            let user_info = client
+                .call_dry_run(&ink_e2e::alice(), &get_user_info, 0, None)
+                .await
+                .return_value();
+
+            assert_eq!(user_info, UserInfo::default());
+
+            let get_pool = build_message::<FarmRef>(farm_acc_id.clone())
+                .call(|farm| farm.get_pool(0));
+
+            let pool = client
+                .call_dry_run(&ink_e2e::alice(), &get_pool, 0, None)
+                .await
+                .return_value()
+                .expect("pool 0 was added");
+
+            assert_eq!(pool.total_deposited, 0);
+
+            Ok(())
+        }
+    }
+}
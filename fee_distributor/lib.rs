@@ -0,0 +1,604 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::fee_distributor::FeeDistributorRef;
+
+#[ink::contract]
+pub mod fee_distributor {
+
+    use ink::prelude::vec; // Importing vec from ink prelude
+    use ink::prelude::vec::Vec; // Importing Vec from ink prelude
+    use ink::storage::Mapping; // Importing Mapping from ink storage
+    use openbrush::contracts::traits::psp22::{
+        PSP22Error,
+        PSP22Ref,
+    };
+    use primitive_types::U256;
+    use trading_pair_azero::trading_pair_azero::EnvErrorCode;
+
+    /// Largest number of recipients, so that a distribution always fits in a block.
+    pub const MAX_RECIPIENTS: usize = 10;
+
+    /// function to get the part of `amount` a recipient with `weight` out of `total_weight` receives.
+    fn get_recipient_amount(amount: Balance, weight: u32, total_weight: u32) -> Option<Balance> {
+        let recipient_amount = U256::from(amount)
+            .checked_mul(U256::from(weight))?
+            .checked_div(U256::from(total_weight))?;
+
+        Balance::try_from(recipient_amount).ok()
+    }
+
+    #[ink(storage)]
+    pub struct FeeDistributor {
+        // Account allowed to change the recipients
+        admin: AccountId,
+        // Recipients of the distributions and their weights
+        recipients: Vec<(AccountId, u32)>,
+        // Sum of the recipients weights
+        total_weight: u32,
+        // Overall amount each recipient received of each token, None standing for AZERO
+        total_distributed: Mapping<(AccountId, Option<AccountId>), Balance>,
+        // AZERO distributions that failed to transfer, collected by the recipients with `claim_a0`
+        pending_a0_claims: Mapping<AccountId, Balance>,
+        // Sum of the pending AZERO claims, kept out of the next AZERO distributions
+        total_pending_a0_claims: Balance,
+    }
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+    pub enum FeeDistributorErrors {
+        CallerIsNotAdmin,                // Error code for an admin message called by another account
+        NoRecipients,                    // Error code for distributing or setting no recipients
+        TooManyRecipients,               // Error code for setting more than MAX_RECIPIENTS recipients
+        ZeroWeight,                      // Error code for a recipient with a weight of 0
+        NothingToDistribute,             // Error code for distributing a token the distributor does not hold
+        Overflow,                        // Error code for overflow
+        ZeroClaimableAmount,             // Error code for claiming without a pending AZERO claim
+        PSP22TransferFailed(PSP22Error), // Error code for a failed PSP22 transfer to a recipient
+        A0TransferFailed(EnvErrorCode),  // Error code for a failed AZERO claim transfer, with the environment error
+    }
+
+    #[ink(event)]
+    pub struct RecipientsSet {
+        recipients: Vec<(AccountId, u32)>, // New recipients and their weights
+    }
+
+    #[ink(event)]
+    pub struct Distributed {
+        #[ink(topic)]
+        token: Option<AccountId>, // Address of the distributed PSP22 token, None for AZERO
+        #[ink(topic)]
+        recipient: AccountId,     // Address of the recipient
+        amount: Balance,          // Amount the recipient received
+    }
+
+    #[ink(event)]
+    pub struct A0ClaimRecorded {
+        #[ink(topic)]
+        recipient: AccountId, // Address of the recipient whose AZERO transfer failed
+        amount: Balance,      // Amount of AZERO added to the recipient's pending claim
+    }
+
+    #[ink(event)]
+    pub struct A0Claimed {
+        #[ink(topic)]
+        caller: AccountId, // Address of the recipient who collected the pending AZERO
+        amount: Balance,   // Amount of AZERO transferred to the recipient
+    }
+
+    impl FeeDistributor {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            let admin = Self::env().caller(); // The deployer sets the recipients
+            let recipients = Vec::new(); // Recipients are set with `set_recipients`
+            let total_weight: u32 = 0; // Sum of the recipients weights
+            let total_distributed = Mapping::default(); // Mapping to store the totals of each recipient
+            let pending_a0_claims = Mapping::default(); // Mapping to store failed AZERO transfers for recipients
+            let total_pending_a0_claims: Balance = 0; // Total AZERO owed to recipients through pending claims
+
+            Self {
+                admin,
+                recipients,
+                total_weight,
+                total_distributed,
+                pending_a0_claims,
+                total_pending_a0_claims,
+            }
+        }
+
+        /// function for the admin to replace the recipients and their weights, e.g. treasury, PANX stakers and buyback.
+        #[ink(message)]
+        pub fn set_recipients(
+            &mut self,
+            recipients: Vec<(AccountId, u32)>,
+        ) -> Result<(), FeeDistributorErrors> {
+            if self.env().caller() != self.admin {
+                return Err(FeeDistributorErrors::CallerIsNotAdmin)
+            }
+
+            if recipients.is_empty() {
+                return Err(FeeDistributorErrors::NoRecipients)
+            }
+
+            if recipients.len() > MAX_RECIPIENTS {
+                return Err(FeeDistributorErrors::TooManyRecipients)
+            }
+
+            let mut total_weight: u32 = 0;
+
+            for (_, weight) in recipients.iter() {
+                if *weight == 0 {
+                    return Err(FeeDistributorErrors::ZeroWeight)
+                }
+
+                match total_weight.checked_add(*weight) {
+                    Some(result) => {
+                        total_weight = result;
+                    }
+                    None => return Err(FeeDistributorErrors::Overflow),
+                };
+            }
+
+            self.recipients = recipients.clone();
+
+            self.total_weight = total_weight;
+
+            Self::env().emit_event(RecipientsSet { recipients });
+
+            Ok(())
+        }
+
+        /// function to split the whole balance of `token` (None for AZERO) across the recipients by weight.
+        /// rounding leftovers stay in the distributor for the next distribution, returns the distributed amount.
+        /// a recipient rejecting its AZERO does not stop the others: its part is kept as a pending claim instead.
+        #[ink(message)]
+        pub fn distribute(&mut self, token: Option<AccountId>) -> Result<Balance, FeeDistributorErrors> {
+            if self.recipients.is_empty() {
+                return Err(FeeDistributorErrors::NoRecipients)
+            }
+
+            let amount = self.get_distributable_amount(token);
+
+            if amount == 0 {
+                return Err(FeeDistributorErrors::NothingToDistribute)
+            }
+
+            let mut distributed_amount: Balance = 0;
+
+            for (recipient, weight) in self.recipients.clone() {
+                let recipient_amount: Balance;
+
+                match get_recipient_amount(amount, weight, self.total_weight) {
+                    Some(result) => {
+                        recipient_amount = result;
+                    }
+                    None => return Err(FeeDistributorErrors::Overflow),
+                };
+
+                if recipient_amount == 0 {
+                    continue
+                }
+
+                match self
+                    .get_total_distributed(recipient, token)
+                    .checked_add(recipient_amount)
+                {
+                    Some(result) => {
+                        self.total_distributed.insert((recipient, token), &result);
+                    }
+                    None => return Err(FeeDistributorErrors::Overflow),
+                };
+
+                // recipient amounts add up to at most `amount`
+                distributed_amount += recipient_amount;
+
+                match token {
+                    Some(token) => {
+                        // cross contract call to the PSP22 token to transfer the recipient part
+                        if let Err(error) =
+                            PSP22Ref::transfer(&token, recipient, recipient_amount, vec![])
+                        {
+                            return Err(FeeDistributorErrors::PSP22TransferFailed(error))
+                        }
+                    }
+                    None => {
+                        if self.env().transfer(recipient, recipient_amount).is_err() {
+                            self.record_a0_claim(recipient, recipient_amount)?;
+
+                            continue
+                        }
+                    }
+                }
+
+                Self::env().emit_event(Distributed {
+                    token,
+                    recipient,
+                    amount: recipient_amount,
+                });
+            }
+
+            Ok(distributed_amount)
+        }
+
+        /// function to add a failed AZERO transfer to the recipient's pending claim.
+        fn record_a0_claim(
+            &mut self,
+            recipient: AccountId,
+            amount: Balance,
+        ) -> Result<(), FeeDistributorErrors> {
+            let new_recipient_claim: Balance;
+
+            match self.get_pending_a0_claim(recipient).checked_add(amount) {
+                Some(result) => {
+                    new_recipient_claim = result;
+                }
+                None => return Err(FeeDistributorErrors::Overflow),
+            };
+
+            let new_total_pending_a0_claims: Balance;
+
+            match self.total_pending_a0_claims.checked_add(amount) {
+                Some(result) => {
+                    new_total_pending_a0_claims = result;
+                }
+                None => return Err(FeeDistributorErrors::Overflow),
+            };
+
+            self.pending_a0_claims.insert(recipient, &new_recipient_claim);
+
+            self.total_pending_a0_claims = new_total_pending_a0_claims;
+
+            Self::env().emit_event(A0ClaimRecorded { recipient, amount });
+
+            Ok(())
+        }
+
+        /// function for a recipient to collect the AZERO distributions that failed to transfer earlier.
+        #[ink(message)]
+        pub fn claim_a0(&mut self) -> Result<Balance, FeeDistributorErrors> {
+            let caller = self.env().caller();
+
+            let claimable_amount: Balance = self.get_pending_a0_claim(caller);
+
+            // making sure that the caller has a pending AZERO claim
+            if claimable_amount == 0 {
+                return Err(FeeDistributorErrors::ZeroClaimableAmount)
+            }
+
+            let new_total_pending_a0_claims: Balance;
+
+            match self.total_pending_a0_claims.checked_sub(claimable_amount) {
+                Some(result) => {
+                    new_total_pending_a0_claims = result;
+                }
+                None => return Err(FeeDistributorErrors::Overflow),
+            };
+
+            self.pending_a0_claims.remove(caller);
+
+            self.total_pending_a0_claims = new_total_pending_a0_claims;
+
+            if let Err(error) = self.env().transfer(caller, claimable_amount) {
+                return Err(FeeDistributorErrors::A0TransferFailed(EnvErrorCode::from(error)))
+            }
+
+            Self::env().emit_event(A0Claimed {
+                caller,
+                amount: claimable_amount,
+            });
+
+            Ok(claimable_amount)
+        }
+
+        /// function to get the amount of AZERO a recipient can collect with `claim_a0`
+        #[ink(message)]
+        pub fn get_pending_a0_claim(&self, recipient: AccountId) -> Balance {
+            self.pending_a0_claims.get(recipient).unwrap_or(0)
+        }
+
+        /// function to get the total AZERO owed to recipients through pending claims
+        #[ink(message)]
+        pub fn get_total_pending_a0_claims(&self) -> Balance {
+            self.total_pending_a0_claims
+        }
+
+        /// function to get the amount of `token` (None for AZERO) the next distribution splits.
+        /// the existential deposit of the distributor and the pending AZERO claims are kept out of AZERO distributions.
+        #[ink(message)]
+        pub fn get_distributable_amount(&self, token: Option<AccountId>) -> Balance {
+            match token {
+                Some(token) => PSP22Ref::balance_of(&token, self.env().account_id()),
+                None => self
+                    .env()
+                    .balance()
+                    .saturating_sub(self.env().minimum_balance())
+                    .saturating_sub(self.total_pending_a0_claims),
+            }
+        }
+
+        /// function to get the overall amount of `token` (None for AZERO) a recipient received or can claim
+        #[ink(message)]
+        pub fn get_total_distributed(&self, recipient: AccountId, token: Option<AccountId>) -> Balance {
+            self.total_distributed.get((recipient, token)).unwrap_or(0)
+        }
+
+        /// function to get the recipients and their weights
+        #[ink(message)]
+        pub fn get_recipients(&self) -> Vec<(AccountId, u32)> {
+            self.recipients.clone()
+        }
+
+        /// function to get the sum of the recipients weights
+        #[ink(message)]
+        pub fn get_total_weight(&self) -> u32 {
+            self.total_weight
+        }
+
+        /// function to get the account allowed to change the recipients
+        #[ink(message)]
+        pub fn get_admin(&self) -> AccountId {
+            self.admin
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[ink::test]
+        fn recipients_are_validated_and_weighted() {
+            let mut fee_distributor = FeeDistributor::new();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(
+                fee_distributor.set_recipients(vec![]),
+                Err(FeeDistributorErrors::NoRecipients)
+            );
+            assert_eq!(
+                fee_distributor.set_recipients(vec![(accounts.bob, 1); MAX_RECIPIENTS + 1]),
+                Err(FeeDistributorErrors::TooManyRecipients)
+            );
+            assert_eq!(
+                fee_distributor.set_recipients(vec![(accounts.bob, 1), (accounts.charlie, 0)]),
+                Err(FeeDistributorErrors::ZeroWeight)
+            );
+            assert_eq!(
+                fee_distributor.set_recipients(vec![(accounts.bob, u32::MAX), (accounts.charlie, 1)]),
+                Err(FeeDistributorErrors::Overflow)
+            );
+
+            fee_distributor
+                .set_recipients(vec![(accounts.bob, 50), (accounts.charlie, 30), (accounts.django, 20)])
+                .unwrap();
+
+            assert_eq!(fee_distributor.get_total_weight(), 100);
+
+            assert_eq!(get_recipient_amount(1001, 50, 100), Some(500));
+            assert_eq!(get_recipient_amount(1001, 30, 100), Some(300));
+            assert_eq!(get_recipient_amount(Balance::MAX, 100, 100), Some(Balance::MAX));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            assert_eq!(
+                fee_distributor.set_recipients(vec![(accounts.bob, 1)]),
+                Err(FeeDistributorErrors::CallerIsNotAdmin)
+            );
+        }
+
+        #[ink::test]
+        fn distribute_splits_azero_by_weight() {
+            let mut fee_distributor = FeeDistributor::new();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
+
+            fee_distributor
+                .set_recipients(vec![(accounts.eve, 3), (accounts.frank, 1)])
+                .unwrap();
+
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+                contract,
+                1001 + fee_distributor.env().minimum_balance(),
+            );
+
+            let eve_balance =
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.eve)
+                    .unwrap();
+
+            // 1001 split 3:1, the rounding leftover stays
+            assert_eq!(fee_distributor.distribute(None), Ok(1000));
+            assert_eq!(fee_distributor.get_total_distributed(accounts.eve, None), 750);
+            assert_eq!(fee_distributor.get_total_distributed(accounts.frank, None), 250);
+            assert_eq!(
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.eve),
+                Ok(eve_balance + 750)
+            );
+            assert_eq!(fee_distributor.get_distributable_amount(None), 1);
+        }
+
+        #[ink::test]
+        fn failed_azero_transfers_become_claims() {
+            let mut fee_distributor = FeeDistributor::new();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
+
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+                contract,
+                1000 + fee_distributor.env().minimum_balance(),
+            );
+
+            // Eve rejected a 300 AZERO distribution
+            fee_distributor.record_a0_claim(accounts.eve, 300).unwrap();
+
+            assert_eq!(fee_distributor.get_pending_a0_claim(accounts.eve), 300);
+            assert_eq!(fee_distributor.get_total_pending_a0_claims(), 300);
+
+            // her claim is not distributed again
+            assert_eq!(fee_distributor.get_distributable_amount(None), 700);
+
+            assert_eq!(
+                fee_distributor.claim_a0(),
+                Err(FeeDistributorErrors::ZeroClaimableAmount)
+            );
+
+            let eve_balance =
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.eve)
+                    .unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+
+            assert_eq!(fee_distributor.claim_a0(), Ok(300));
+            assert_eq!(fee_distributor.get_total_pending_a0_claims(), 0);
+            assert_eq!(fee_distributor.get_distributable_amount(None), 700);
+            assert_eq!(
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.eve),
+                Ok(eve_balance + 300)
+            );
+        }
+    }
+
+    /// ink! end-to-end (E2E) tests
+    ///
+    /// cargo test --features e2e-tests -- --nocapture
+    ///
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink::primitives::AccountId;
+        use ink_e2e::build_message;
+        use my_psp22::my_psp22::MyPsp22Ref;
+        use openbrush::{
+            contracts::psp22::psp22_external::PSP22,
+            traits::String,
+        };
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        /// Helper to get Bob's account_id from `ink_e2e::bob()` PairSigner
+        fn get_bob_account_id() -> AccountId {
+            let bob = ink_e2e::bob::<ink_e2e::PolkadotConfig>();
+            let bob_account_id_32 = bob.account_id();
+            let bob_account_id = AccountId::try_from(bob_account_id_32.as_ref()).unwrap();
+
+            bob_account_id
+        }
+
+        /// Helper to get Charlie's account_id from `ink_e2e::charlie()` PairSigner
+        fn get_charlie_account_id() -> AccountId {
+            let charlie = ink_e2e::charlie::<ink_e2e::PolkadotConfig>();
+            let charlie_account_id_32 = charlie.account_id();
+            let charlie_account_id = AccountId::try_from(charlie_account_id_32.as_ref()).unwrap();
+
+            charlie_account_id
+        }
+
+        /// Tests included in "distribute_psp22_works":
+        /// 1. set_recipients
+        /// 2. distribute of a PSP22 token
+        /// 3. get_total_distributed
+        #[ink_e2e::test(additional_contracts = "../my_psp22/Cargo.toml")]
+        async fn distribute_psp22_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // PSP22 token constructor object
+            let psp22_constructor = MyPsp22Ref::new(
+                10000000000000000,
+                Some(String::from("TOKEN").into()),
+                Some(String::from("TKN").into()),
+                12,
+            );
+
+            // Instantiate new PSP22 token using the constructor
+            let psp22_acc_id = client
+                .instantiate("my_psp22", &ink_e2e::alice(), psp22_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // Instantiate new fee distributor
+            let distributor_acc_id = client
+                .instantiate(
+                    "fee_distributor",
+                    &ink_e2e::alice(),
+                    FeeDistributorRef::new(),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // Bob gets 70% and Charlie 30% of the distributions
+            let set_recipients = build_message::<FeeDistributorRef>(distributor_acc_id.clone())
+                .call(|fee_distributor| {
+                    fee_distributor.set_recipients(vec![
+                        (get_bob_account_id(), 70),
+                        (get_charlie_account_id(), 30),
+                    ])
+                });
+
+            client
+                .call(&ink_e2e::alice(), set_recipients, 0, None)
+                .await
+                .expect("calling `set_recipients` failed");
+
+            // Fees of 100 PSP22 tokens reach the distributor
+            let transfer_to_distributor = build_message::<MyPsp22Ref>(psp22_acc_id.clone())
+                .call(|my_psp22| my_psp22.transfer(distributor_acc_id, 100000000000000, vec![]));
+
+            client
+                .call(&ink_e2e::alice(), transfer_to_distributor, 0, None)
+                .await
+                .expect("calling `transfer_to_distributor` failed");
+
+            // Anyone can distribute
+            let distribute = build_message::<FeeDistributorRef>(distributor_acc_id.clone())
+                .call(|fee_distributor| fee_distributor.distribute(Some(psp22_acc_id)));
+
+            let distributed_amount = client
+                .call(&ink_e2e::bob(), distribute, 0, None)
+                .await
+                .expect("calling `distribute` failed")
+                .return_value();
+
+            assert_eq!(distributed_amount, Ok(100000000000000));
+
+            // Charlie received 30 PSP22 tokens
+            let psp22_balance_of = build_message::<MyPsp22Ref>(psp22_acc_id.clone())
+                .call(|my_psp22| my_psp22.balance_of(get_charlie_account_id()));
+
+            let charlie_balance = client
+                .call(&ink_e2e::alice(), psp22_balance_of, 0, None)
+                .await
+                .expect("psp22_balance_of failed")
+                .return_value();
+
+            assert_eq!(charlie_balance, 30000000000000);
+
+            // Bob's total is recorded
+            let get_total_distributed = build_message::<FeeDistributorRef>(
+                distributor_acc_id.clone(),
+            )
+            .call(|fee_distributor| {
+                fee_distributor.get_total_distributed(get_bob_account_id(), Some(psp22_acc_id))
+            });
+
+            let bob_total = client
+                .call(&ink_e2e::alice(), get_total_distributed, 0, None)
+                .await
+                .expect("get_total_distributed failed")
+                .return_value();
+
+            assert_eq!(bob_total, 70000000000000);
+
+            // Nothing left to distribute
+            let distribute = build_message::<FeeDistributorRef>(distributor_acc_id.clone())
+                .call(|fee_distributor| fee_distributor.distribute(Some(psp22_acc_id)));
+
+            let distribute_res = client
+                .call_dry_run(&ink_e2e::bob(), &distribute, 0, None)
+                .await
+                .return_value();
+
+            assert_eq!(distribute_res, Err(FeeDistributorErrors::NothingToDistribute));
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,795 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::gauge::GaugeRef;
+
+#[ink::contract]
+pub mod gauge {
+
+    use ink::prelude::vec; // Importing vec from ink prelude
+    use ink::storage::Mapping; // Importing Mapping from ink storage
+    use openbrush::contracts::traits::psp22::{
+        PSP22Error,
+        PSP22Ref,
+    };
+    use primitive_types::U256;
+
+    /// Scale of the reward per staked LP share accumulator.
+    pub const REWARD_PRECISION: Balance = 1_000_000_000_000;
+
+    /// Duration (one week, in seconds) PANX notified by the gauge controller is streamed over.
+    pub const REWARD_DURATION: u64 = 7 * 86400;
+
+    /// function to get `amount` times `price` divided by `denominator`, computed in U256.
+    fn mul_div(amount: Balance, price: Balance, denominator: Balance) -> Option<Balance> {
+        let result = (U256::from(amount) * U256::from(price)).checked_div(U256::from(denominator))?;
+
+        Balance::try_from(result).ok()
+    }
+
+    #[ink(storage)]
+    pub struct Gauge {
+        // Gauge controller allowed to notify PANX rewards
+        controller: AccountId,
+        // AZERO trading pair whose LP shares are staked
+        pair: AccountId,
+        // PANX PSP22 token the stakers are rewarded in
+        panx_token: AccountId,
+        // Staked LP shares of each account
+        stakes: Mapping<AccountId, Balance>,
+        // Overall staked LP shares
+        total_staked: Balance,
+        // PANX streamed to the stakers every second
+        reward_rate: Balance,
+        // Timestamp (in seconds) the current reward period ends at
+        period_finish: u64,
+        // Timestamp (in seconds) the reward per share was last updated at
+        last_update_time: u64,
+        // PANX per staked LP share since deployment as of the last update, scaled by REWARD_PRECISION
+        reward_per_share_stored: Balance,
+        // Reward per staked LP share of each account when its rewards were last updated
+        reward_per_share_paid: Mapping<AccountId, Balance>,
+        // PANX rewards of each account that can be claimed
+        pending_rewards: Mapping<AccountId, Balance>,
+        // PANX streamed while nothing was staked or left over by the reward rate, streamed again with the next rewards
+        unallocated_rewards: Balance,
+    }
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+    pub enum GaugeErrors {
+        CallerIsNotController,               // Error code for notifying rewards from another account than the controller
+        ZeroAmount,                          // Error code for staking or withdrawing 0 LP shares
+        InsufficientStake,                   // Error code for withdrawing more than the staked LP shares
+        NothingToClaim,                      // Error code for claiming with no pending rewards
+        RewardTooHigh,                       // Error code for notifying more PANX than the gauge holds
+        Overflow,                            // Error code for overflow
+        PSP22TransferFromFailed(PSP22Error), // Error code for a failed transfer of LP shares to the gauge
+        PSP22TransferFailed(PSP22Error),     // Error code for a failed transfer of LP shares or PANX rewards
+    }
+
+    #[ink(event)]
+    pub struct Staked {
+        #[ink(topic)]
+        account: AccountId, // Address of the staker
+        shares: Balance,    // Staked LP shares
+    }
+
+    #[ink(event)]
+    pub struct Withdrawn {
+        #[ink(topic)]
+        account: AccountId, // Address of the staker
+        shares: Balance,    // LP shares sent back to the staker
+    }
+
+    #[ink(event)]
+    pub struct RewardClaimed {
+        #[ink(topic)]
+        account: AccountId, // Address of the staker
+        amount: Balance,    // Claimed PANX
+    }
+
+    #[ink(event)]
+    pub struct RewardNotified {
+        amount: Balance,      // PANX added to the stream
+        reward_rate: Balance, // PANX streamed every second from now on
+        period_finish: u64,   // Timestamp (in seconds) the stream ends at
+    }
+
+    impl Gauge {
+        #[ink(constructor)]
+        pub fn new(
+            pair: AccountId,       // Address of the AZERO trading pair
+            panx_token: AccountId, // Address of the PANX token contract
+            controller: AccountId, // Address of the gauge controller
+        ) -> Self {
+            let stakes = Mapping::default(); // Mapping to store the staked LP shares of accounts
+            let total_staked: Balance = 0; // Overall staked LP shares
+            let reward_rate: Balance = 0; // Nothing streamed before the first notified rewards
+            let period_finish: u64 = 0; // No reward period yet
+            let last_update_time: u64 = 0; // No update yet
+            let reward_per_share_stored: Balance = 0; // No rewards yet
+            let reward_per_share_paid = Mapping::default(); // Mapping to store the accumulator accounts were updated at
+            let pending_rewards = Mapping::default(); // Mapping to store the claimable rewards of accounts
+            let unallocated_rewards: Balance = 0; // No rewards streamed to no one yet
+
+            Self {
+                controller,
+                pair,
+                panx_token,
+                stakes,
+                total_staked,
+                reward_rate,
+                period_finish,
+                last_update_time,
+                reward_per_share_stored,
+                reward_per_share_paid,
+                pending_rewards,
+                unallocated_rewards,
+            }
+        }
+
+        /// function to stake `shares` LP shares of the pair, the caller has to approve the gauge first.
+        #[ink(message)]
+        pub fn stake(&mut self, shares: Balance) -> Result<(), GaugeErrors> {
+            let caller = self.env().caller();
+
+            if shares == 0 {
+                return Err(GaugeErrors::ZeroAmount)
+            }
+
+            self.update_rewards_of(Some(caller))?;
+
+            match self.get_stake(caller).checked_add(shares) {
+                Some(result) => {
+                    self.stakes.insert(caller, &result);
+                }
+                None => return Err(GaugeErrors::Overflow),
+            };
+
+            match self.total_staked.checked_add(shares) {
+                Some(result) => {
+                    self.total_staked = result;
+                }
+                None => return Err(GaugeErrors::Overflow),
+            };
+
+            // cross contract call to the pair to transfer the LP shares
+            if let Err(error) =
+                PSP22Ref::transfer_from(&self.pair, caller, self.env().account_id(), shares, vec![])
+            {
+                return Err(GaugeErrors::PSP22TransferFromFailed(error))
+            }
+
+            Self::env().emit_event(Staked {
+                account: caller,
+                shares,
+            });
+
+            Ok(())
+        }
+
+        /// function to withdraw `shares` staked LP shares.
+        #[ink(message)]
+        pub fn withdraw(&mut self, shares: Balance) -> Result<(), GaugeErrors> {
+            let caller = self.env().caller();
+
+            if shares == 0 {
+                return Err(GaugeErrors::ZeroAmount)
+            }
+
+            self.update_rewards_of(Some(caller))?;
+
+            match self.get_stake(caller).checked_sub(shares) {
+                Some(result) => {
+                    self.stakes.insert(caller, &result);
+                }
+                None => return Err(GaugeErrors::InsufficientStake),
+            };
+
+            // shares is at most the caller's stake, part of the total
+            self.total_staked -= shares;
+
+            // cross contract call to the pair to send the LP shares back
+            if let Err(error) = PSP22Ref::transfer(&self.pair, caller, shares, vec![]) {
+                return Err(GaugeErrors::PSP22TransferFailed(error))
+            }
+
+            Self::env().emit_event(Withdrawn {
+                account: caller,
+                shares,
+            });
+
+            Ok(())
+        }
+
+        /// function to claim the caller's PANX rewards.
+        #[ink(message)]
+        pub fn claim_rewards(&mut self) -> Result<(), GaugeErrors> {
+            let caller = self.env().caller();
+
+            self.update_rewards_of(Some(caller))?;
+
+            let amount = self.pending_rewards.get(caller).unwrap_or(0);
+
+            if amount == 0 {
+                return Err(GaugeErrors::NothingToClaim)
+            }
+
+            self.pending_rewards.remove(caller);
+
+            // cross contract call to the PANX token to transfer the rewards
+            if let Err(error) = PSP22Ref::transfer(&self.panx_token, caller, amount, vec![]) {
+                return Err(GaugeErrors::PSP22TransferFailed(error))
+            }
+
+            Self::env().emit_event(RewardClaimed {
+                account: caller,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        /// function for the gauge controller to stream `amount` PANX it sent to the gauge over the next REWARD_DURATION.
+        /// PANX left from the current reward period and the unallocated PANX are streamed along with it.
+        #[ink(message)]
+        pub fn notify_reward_amount(&mut self, amount: Balance) -> Result<(), GaugeErrors> {
+            if self.env().caller() != self.controller {
+                return Err(GaugeErrors::CallerIsNotController)
+            }
+
+            self.start_reward_period(amount)?;
+
+            // the gauge has to hold the PANX of the whole period
+            let panx_balance = PSP22Ref::balance_of(&self.panx_token, self.env().account_id());
+
+            if self.reward_rate > panx_balance / REWARD_DURATION as Balance {
+                return Err(GaugeErrors::RewardTooHigh)
+            }
+
+            Ok(())
+        }
+
+        /// function to get the staked LP shares of an account
+        #[ink(message)]
+        pub fn get_stake(&self, account: AccountId) -> Balance {
+            self.stakes.get(account).unwrap_or(0)
+        }
+
+        /// function to get the overall staked LP shares
+        #[ink(message)]
+        pub fn get_total_staked(&self) -> Balance {
+            self.total_staked
+        }
+
+        /// function to get the PANX an account can claim
+        #[ink(message)]
+        pub fn get_earned(&self, account: AccountId) -> Balance {
+            let earned_rewards = mul_div(
+                self.get_stake(account),
+                self.get_reward_per_share()
+                    .saturating_sub(self.reward_per_share_paid.get(account).unwrap_or(0)),
+                REWARD_PRECISION,
+            )
+            .unwrap_or(0);
+
+            self.pending_rewards
+                .get(account)
+                .unwrap_or(0)
+                .saturating_add(earned_rewards)
+        }
+
+        /// function to get the PANX per staked LP share since deployment, scaled by REWARD_PRECISION
+        #[ink(message)]
+        pub fn get_reward_per_share(&self) -> Balance {
+            // rewards streamed while nothing is staked are not given to anyone
+            if self.total_staked == 0 {
+                return self.reward_per_share_stored
+            }
+
+            let elapsed_seconds = self
+                .get_last_time_reward_applicable()
+                .saturating_sub(self.last_update_time);
+
+            let reward_per_share_increase = mul_div(
+                self.reward_rate.saturating_mul(elapsed_seconds as Balance),
+                REWARD_PRECISION,
+                self.total_staked,
+            )
+            .unwrap_or(0);
+
+            self.reward_per_share_stored
+                .saturating_add(reward_per_share_increase)
+        }
+
+        /// function to get the PANX streamed while nothing was staked or left over by the reward rate, streamed with the next rewards
+        #[ink(message)]
+        pub fn get_unallocated_rewards(&self) -> Balance {
+            self.unallocated_rewards
+        }
+
+        /// function to get the PANX streamed every second and the timestamp the current reward period ends at
+        #[ink(message)]
+        pub fn get_reward_rate(&self) -> (Balance, u64) {
+            (self.reward_rate, self.period_finish)
+        }
+
+        /// function to get the AZERO trading pair whose LP shares are staked
+        #[ink(message)]
+        pub fn get_pair(&self) -> AccountId {
+            self.pair
+        }
+
+        /// function to get the gauge controller
+        #[ink(message)]
+        pub fn get_controller(&self) -> AccountId {
+            self.controller
+        }
+
+        /// function to get the current timestamp in seconds
+        #[ink(message)]
+        pub fn get_current_timestamp(&self) -> u64 {
+            self.env().block_timestamp() / 1000
+        }
+
+        /// function to get the latest timestamp rewards were streamed at, the current one or the end of the reward period.
+        fn get_last_time_reward_applicable(&self) -> u64 {
+            self.get_current_timestamp().min(self.period_finish)
+        }
+
+        /// function to update the reward per share, then move the rewards an account earned since its last update to its pending rewards.
+        fn update_rewards_of(&mut self, account: Option<AccountId>) -> Result<(), GaugeErrors> {
+            // the PANX streamed while nothing was staked is kept for the next reward period
+            if self.total_staked == 0 {
+                let elapsed_seconds = self
+                    .get_last_time_reward_applicable()
+                    .saturating_sub(self.last_update_time);
+
+                match self
+                    .reward_rate
+                    .checked_mul(elapsed_seconds as Balance)
+                    .and_then(|result| result.checked_add(self.unallocated_rewards))
+                {
+                    Some(result) => {
+                        self.unallocated_rewards = result;
+                    }
+                    None => return Err(GaugeErrors::Overflow),
+                };
+            }
+
+            self.reward_per_share_stored = self.get_reward_per_share();
+
+            self.last_update_time = self.get_last_time_reward_applicable();
+
+            if let Some(account) = account {
+                let earned_rewards = self.get_earned(account);
+
+                self.pending_rewards.insert(account, &earned_rewards);
+
+                self.reward_per_share_paid
+                    .insert(account, &self.reward_per_share_stored);
+            }
+
+            Ok(())
+        }
+
+        /// function to stream `amount` PANX plus what is left from the current reward period and the unallocated PANX
+        /// over the next REWARD_DURATION.
+        fn start_reward_period(&mut self, amount: Balance) -> Result<(), GaugeErrors> {
+            self.update_rewards_of(None)?;
+
+            let current_timestamp = self.get_current_timestamp();
+
+            let mut reward_amount: Balance;
+
+            match amount.checked_add(self.unallocated_rewards) {
+                Some(result) => {
+                    reward_amount = result;
+                }
+                None => return Err(GaugeErrors::Overflow),
+            };
+
+            if current_timestamp < self.period_finish {
+                let remaining_rewards = self
+                    .reward_rate
+                    .saturating_mul((self.period_finish - current_timestamp) as Balance);
+
+                match reward_amount.checked_add(remaining_rewards) {
+                    Some(result) => {
+                        reward_amount = result;
+                    }
+                    None => return Err(GaugeErrors::Overflow),
+                };
+            }
+
+            self.reward_rate = reward_amount / REWARD_DURATION as Balance;
+
+            // the PANX the reward rate can't stream is kept for the next reward period
+            self.unallocated_rewards = reward_amount % REWARD_DURATION as Balance;
+
+            self.last_update_time = current_timestamp;
+
+            self.period_finish = current_timestamp + REWARD_DURATION;
+
+            Self::env().emit_event(RewardNotified {
+                amount,
+                reward_rate: self.reward_rate,
+                period_finish: self.period_finish,
+            });
+
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn set_timestamp(seconds: u64) {
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(seconds * 1000);
+        }
+
+        #[ink::test]
+        fn rewards_are_streamed_pro_rata_over_the_period() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut gauge = Gauge::new(
+                AccountId::from([0x01; 32]),
+                AccountId::from([0x02; 32]),
+                accounts.alice,
+            );
+
+            // Alice stakes 300 LP shares and Bob 100
+            gauge.stakes.insert(accounts.alice, &300);
+            gauge.stakes.insert(accounts.bob, &100);
+            gauge.total_staked = 400;
+
+            // 1000 PANX every second for a week
+            set_timestamp(1000);
+            gauge
+                .start_reward_period(1000 * REWARD_DURATION as Balance)
+                .unwrap();
+
+            assert_eq!(gauge.get_reward_rate(), (1000, 1000 + REWARD_DURATION));
+
+            set_timestamp(1100);
+
+            assert_eq!(gauge.get_earned(accounts.alice), 75000);
+            assert_eq!(gauge.get_earned(accounts.bob), 25000);
+
+            // half way through, the PANX left and half a week of new PANX are streamed over a new week
+            set_timestamp(1000 + REWARD_DURATION / 2);
+            gauge
+                .start_reward_period(1000 * REWARD_DURATION as Balance / 2)
+                .unwrap();
+
+            assert_eq!(
+                gauge.get_reward_rate(),
+                (1000, 1000 + REWARD_DURATION / 2 + REWARD_DURATION)
+            );
+
+            // nothing is streamed after the period
+            set_timestamp(1000 + REWARD_DURATION * 3);
+
+            assert_eq!(
+                gauge.get_earned(accounts.alice) + gauge.get_earned(accounts.bob),
+                1000 * REWARD_DURATION as Balance * 3 / 2
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            assert_eq!(
+                gauge.notify_reward_amount(1),
+                Err(GaugeErrors::CallerIsNotController)
+            );
+        }
+
+        #[ink::test]
+        fn rewards_streamed_to_no_one_are_carried_over() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut gauge = Gauge::new(
+                AccountId::from([0x01; 32]),
+                AccountId::from([0x02; 32]),
+                accounts.alice,
+            );
+
+            // 1000 PANX every second for a week, and 5 PANX the rate can't stream
+            set_timestamp(1000);
+            gauge
+                .start_reward_period(1000 * REWARD_DURATION as Balance + 5)
+                .unwrap();
+
+            assert_eq!(gauge.get_reward_rate(), (1000, 1000 + REWARD_DURATION));
+            assert_eq!(gauge.get_unallocated_rewards(), 5);
+
+            // nothing is staked during the first half of the week
+            set_timestamp(1000 + REWARD_DURATION / 2);
+            gauge.update_rewards_of(Some(accounts.alice)).unwrap();
+
+            assert_eq!(
+                gauge.get_unallocated_rewards(),
+                1000 * REWARD_DURATION as Balance / 2 + 5
+            );
+
+            gauge.stakes.insert(accounts.alice, &100);
+            gauge.total_staked = 100;
+
+            // the next rewards stream the PANX left and the unallocated PANX over a new week
+            gauge.start_reward_period(0).unwrap();
+
+            assert_eq!(
+                gauge.get_reward_rate(),
+                (1000, 1000 + REWARD_DURATION / 2 + REWARD_DURATION)
+            );
+            assert_eq!(gauge.get_unallocated_rewards(), 5);
+
+            set_timestamp(1000 + REWARD_DURATION * 3);
+
+            assert_eq!(
+                gauge.get_earned(accounts.alice),
+                1000 * REWARD_DURATION as Balance
+            );
+        }
+    }
+
+    /// ink! end-to-end (E2E) tests
+    ///
+    /// cargo test --features e2e-tests -- --nocapture
+    ///
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink::primitives::AccountId;
+        use ink_e2e::build_message;
+        use my_psp22::my_psp22::MyPsp22Ref;
+        use openbrush::{
+            contracts::psp22::psp22_external::PSP22,
+            traits::String,
+        };
+        use trading_pair_azero::trading_pair_azero::TradingPairAzeroRef;
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        /// Helper to get Alice's account_id from `ink_e2e::alice()` PairSigner
+        fn get_alice_account_id() -> AccountId {
+            let alice = ink_e2e::alice::<ink_e2e::PolkadotConfig>();
+            let alice_account_id_32 = alice.account_id();
+            let alice_account_id = AccountId::try_from(alice_account_id_32.as_ref()).unwrap();
+
+            alice_account_id
+        }
+
+        /// Helper to get Charlie's account_id from `ink_e2e::charlie()` PairSigner
+        fn get_charlie_account_id() -> AccountId {
+            let charlie = ink_e2e::charlie::<ink_e2e::PolkadotConfig>();
+            let charlie_account_id_32 = charlie.account_id();
+            let charlie_account_id = AccountId::try_from(charlie_account_id_32.as_ref()).unwrap();
+
+            charlie_account_id
+        }
+
+        /// Tests included in "stake_notify_and_claim_work":
+        /// 1. stake LP shares of a trading pair
+        /// 2. notify_reward_amount by the controller
+        /// 3. get_earned and claim_rewards
+        /// 4. withdraw
+        #[ink_e2e::test(
+            additional_contracts = "../my_psp22/Cargo.toml ../trading_pair_azero/Cargo.toml"
+        )]
+        async fn stake_notify_and_claim_work(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // PSP22 token of the trading pair
+            let psp22_constructor = MyPsp22Ref::new(
+                10000000000000000,
+                Some(String::from("TOKEN").into()),
+                Some(String::from("TKN").into()),
+                12,
+            );
+
+            let psp22_acc_id = client
+                .instantiate("my_psp22", &ink_e2e::alice(), psp22_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // PANX token the stakers are rewarded in
+            let panx_constructor = MyPsp22Ref::new(
+                10000000000000000,
+                Some(String::from("PANX").into()),
+                Some(String::from("PANX").into()),
+                12,
+            );
+
+            let panx_acc_id = client
+                .instantiate("my_psp22", &ink_e2e::alice(), panx_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // 1% LP fee pair of the PSP22 token, without LP lock
+            let tpa_constructor = TradingPairAzeroRef::new(
+                psp22_acc_id,
+                10000,
+                panx_acc_id,
+                get_charlie_account_id(),
+                0,
+                get_alice_account_id(),
+                get_alice_account_id(),
+            );
+
+            let tpa_acc_id = client
+                .instantiate(
+                    "trading_pair_azero",
+                    &ink_e2e::alice(),
+                    tpa_constructor,
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // Alice provides 100 PSP22 tokens and 10 A0 to the pair
+            let approve_psp22 = build_message::<MyPsp22Ref>(psp22_acc_id.clone())
+                .call(|my_psp22| my_psp22.approve(tpa_acc_id, 100000000000000));
+
+            client
+                .call(&ink_e2e::alice(), approve_psp22, 0, None)
+                .await
+                .expect("calling `approve_psp22` failed");
+
+            let provide_to_tpa = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone()).call(
+                |trading_pair_azero| {
+                    trading_pair_azero.provide_to_pool(
+                        100000000000000,
+                        10000000000000,
+                        1000000000000000,
+                        500000000000,
+                    )
+                },
+            );
+
+            client
+                .call(&ink_e2e::alice(), provide_to_tpa, 10000000000000, None)
+                .await
+                .expect("calling `provide_to_tpa` failed");
+
+            // Gauge of the pair, Alice stands in for the gauge controller
+            let gauge_acc_id = client
+                .instantiate(
+                    "gauge",
+                    &ink_e2e::alice(),
+                    GaugeRef::new(tpa_acc_id, panx_acc_id, get_alice_account_id()),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // Alice stakes 400 of her 1000 LP shares
+            let approve_lp = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone()).call(
+                |trading_pair_azero| trading_pair_azero.approve(gauge_acc_id, 400000000000000),
+            );
+
+            client
+                .call(&ink_e2e::alice(), approve_lp, 0, None)
+                .await
+                .expect("calling `approve_lp` failed");
+
+            let stake = build_message::<GaugeRef>(gauge_acc_id.clone())
+                .call(|gauge| gauge.stake(400000000000000));
+
+            client
+                .call(&ink_e2e::alice(), stake, 0, None)
+                .await
+                .expect("calling `stake` failed");
+
+            let get_total_staked = build_message::<GaugeRef>(gauge_acc_id.clone())
+                .call(|gauge| gauge.get_total_staked());
+
+            let total_staked = client
+                .call_dry_run(&ink_e2e::alice(), &get_total_staked, 0, None)
+                .await
+                .return_value();
+
+            assert_eq!(total_staked, 400000000000000);
+
+            // The controller sends a week of 0.001 PANX every second and notifies it
+            let reward_amount: Balance = REWARD_DURATION as Balance * 1000000000;
+
+            let transfer_panx = build_message::<MyPsp22Ref>(panx_acc_id.clone())
+                .call(|my_psp22| my_psp22.transfer(gauge_acc_id, reward_amount, vec![]));
+
+            client
+                .call(&ink_e2e::alice(), transfer_panx, 0, None)
+                .await
+                .expect("calling `transfer_panx` failed");
+
+            let notify_reward_amount = build_message::<GaugeRef>(gauge_acc_id.clone())
+                .call(|gauge| gauge.notify_reward_amount(reward_amount));
+
+            client
+                .call(&ink_e2e::alice(), notify_reward_amount, 0, None)
+                .await
+                .expect("calling `notify_reward_amount` failed");
+
+            let get_reward_rate = build_message::<GaugeRef>(gauge_acc_id.clone())
+                .call(|gauge| gauge.get_reward_rate());
+
+            let (reward_rate, _) = client
+                .call_dry_run(&ink_e2e::alice(), &get_reward_rate, 0, None)
+                .await
+                .return_value();
+
+            assert_eq!(reward_rate, 1000000000);
+
+            // Let some seconds of the stream pass before the next block
+            std::thread::sleep(std::time::Duration::from_secs(3));
+
+            let get_panx_balance = build_message::<MyPsp22Ref>(panx_acc_id.clone())
+                .call(|my_psp22| my_psp22.balance_of(get_alice_account_id()));
+
+            let panx_balance_before = client
+                .call_dry_run(&ink_e2e::alice(), &get_panx_balance, 0, None)
+                .await
+                .return_value();
+
+            let claim_rewards = build_message::<GaugeRef>(gauge_acc_id.clone())
+                .call(|gauge| gauge.claim_rewards());
+
+            client
+                .call(&ink_e2e::alice(), claim_rewards, 0, None)
+                .await
+                .expect("calling `claim_rewards` failed");
+
+            let panx_balance_after = client
+                .call_dry_run(&ink_e2e::alice(), &get_panx_balance, 0, None)
+                .await
+                .return_value();
+
+            // Alice is the only staker, she got the seconds streamed so far and no more
+            let claimed = panx_balance_after - panx_balance_before;
+
+            assert!(claimed >= reward_rate);
+            assert!(claimed < reward_amount);
+
+            // Nothing is left to claim in the same block
+            let get_earned = build_message::<GaugeRef>(gauge_acc_id.clone())
+                .call(|gauge| gauge.get_earned(get_alice_account_id()));
+
+            let earned = client
+                .call_dry_run(&ink_e2e::alice(), &get_earned, 0, None)
+                .await
+                .return_value();
+
+            assert!(earned < claimed);
+
+            // Alice withdraws her stake and gets her LP shares back
+            let withdraw = build_message::<GaugeRef>(gauge_acc_id.clone())
+                .call(|gauge| gauge.withdraw(400000000000000));
+
+            client
+                .call(&ink_e2e::alice(), withdraw, 0, None)
+                .await
+                .expect("calling `withdraw` failed");
+
+            let get_lp_balance = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| trading_pair_azero.balance_of(get_alice_account_id()));
+
+            let lp_balance = client
+                .call_dry_run(&ink_e2e::alice(), &get_lp_balance, 0, None)
+                .await
+                .return_value();
+
+            assert_eq!(lp_balance, 1000000000000000);
+
+            let total_staked = client
+                .call_dry_run(&ink_e2e::alice(), &get_total_staked, 0, None)
+                .await
+                .return_value();
+
+            assert_eq!(total_staked, 0);
+
+            Ok(())
+        }
+    }
+}
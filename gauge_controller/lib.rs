@@ -0,0 +1,915 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::gauge_controller::GaugeControllerRef;
+
+#[ink::contract]
+pub mod gauge_controller {
+
+    use gauge::gauge::{
+        GaugeErrors,
+        GaugeRef,
+    };
+    use ink::codegen::TraitCallBuilder; // Importing TraitCallBuilder to call the gauges with try_invoke
+    use ink::env::{
+        call::{
+            build_call,
+            ExecutionInput,
+            FromAccountId,
+            Selector,
+        },
+        DefaultEnvironment,
+    };
+    use ink::prelude::vec; // Importing vec from ink prelude
+    use ink::prelude::vec::Vec; // Importing Vec from ink prelude
+    use ink::storage::Mapping; // Importing Mapping from ink storage
+    use ink::LangError; // Importing LangError from ink
+    use openbrush::contracts::traits::psp22::{
+        PSP22Error,
+        PSP22Ref,
+    };
+    use primitive_types::U256;
+
+    /// Duration of a voting epoch (one week, in seconds), votes of an epoch split the emissions of the next one.
+    pub const EPOCH_DURATION: u64 = 7 * 86400;
+
+    /// Largest number of gauges.
+    pub const MAX_GAUGES: usize = 50;
+
+    /// Largest number of gauges an account can vote for in an epoch.
+    pub const MAX_VOTES_PER_ACCOUNT: usize = 10;
+
+    /// Denominator of the vote weights, in basis points (100% = 10000).
+    pub const VOTE_DENOMINATOR: u32 = 10_000;
+
+    /// function to get the votes given to a gauge with `weight` basis points of `voting_power`.
+    fn get_vote_amount(voting_power: Balance, weight: u32) -> Balance {
+        // weight is at most VOTE_DENOMINATOR, the votes are at most the voting power
+        (U256::from(voting_power) * U256::from(weight) / U256::from(VOTE_DENOMINATOR)).low_u128()
+    }
+
+    /// Vote of an account in an epoch.
+    #[derive(Debug, Default, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct AccountVote {
+        pub voting_power: Balance,          // Voting power of the account when it voted
+        pub weights: Vec<(AccountId, u32)>, // Gauges voted for and their weights in basis points
+    }
+
+    #[ink(storage)]
+    pub struct GaugeController {
+        // Account allowed to add gauges and set the emissions
+        admin: AccountId,
+        // PairCreator whose AZERO trading pairs can get a gauge
+        pair_creator: AccountId,
+        // vePANX contract whose checkpointed voting power at the start of an epoch is voted with
+        ve_panx: AccountId,
+        // PANX PSP22 token the gauges are rewarded in
+        panx_token: AccountId,
+        // PANX split among the gauges every epoch
+        panx_per_epoch: Balance,
+        // Gauges that can be voted for
+        gauges: Vec<AccountId>,
+        // Gauge of each AZERO trading pair
+        gauge_of_pair: Mapping<AccountId, AccountId>,
+        // Votes of each gauge in each epoch
+        gauge_votes: Mapping<(u64, AccountId), Balance>,
+        // Overall votes in each epoch
+        total_votes: Mapping<u64, Balance>,
+        // Vote of each account in each epoch
+        account_votes: Mapping<(AccountId, u64), AccountVote>,
+        // PANX sent to each gauge in each epoch
+        distributed: Mapping<(u64, AccountId), Balance>,
+        // PANX sent to all the gauges in each epoch
+        epoch_distributed: Mapping<u64, Balance>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+    pub enum GaugeControllerErrors {
+        CallerIsNotAdmin,                // Error code for an admin message called by another account
+        TooManyGauges,                   // Error code for adding more than MAX_GAUGES gauges
+        GaugeAlreadyAdded,               // Error code for adding a gauge twice or a second gauge to a pair
+        GaugeOfAnotherController,        // Error code for adding a gauge notified by another controller
+        PairNotRegistered,               // Error code for adding a gauge of a pair PairCreator did not create
+        UnknownGauge,                    // Error code for voting for or distributing to a gauge that was not added
+        TooManyVotes,                    // Error code for voting for more than MAX_VOTES_PER_ACCOUNT gauges
+        DuplicateVote,                   // Error code for voting for the same gauge twice
+        InvalidVoteWeights,              // Error code for vote weights adding up to more than VOTE_DENOMINATOR
+        NoVotingPower,                   // Error code for voting with no voting power
+        AlreadyDistributed,              // Error code for distributing to a gauge twice in an epoch
+        NothingToDistribute,             // Error code for distributing to a gauge no one voted for
+        SweepTooHigh,                    // Error code for sweeping PANX the gauges can still get this epoch
+        Overflow,                        // Error code for overflow
+        PSP22TransferFailed(PSP22Error), // Error code for a failed transfer of PANX to a gauge
+        Gauge(GaugeErrors),              // Error code for a gauge rejecting the notified PANX
+        Lang(LangError),                 // Error code for a gauge call the gauge could not dispatch
+        GaugeCallFailed,                 // Error code for a gauge call the environment failed
+    }
+
+    #[ink(event)]
+    pub struct GaugeAdded {
+        #[ink(topic)]
+        pair: AccountId,  // Address of the AZERO trading pair
+        #[ink(topic)]
+        gauge: AccountId, // Address of the gauge
+    }
+
+    #[ink(event)]
+    pub struct Voted {
+        #[ink(topic)]
+        account: AccountId,             // Address of the voter
+        epoch: u64,                     // Epoch the vote was cast in
+        voting_power: Balance,          // Voting power of the voter
+        weights: Vec<(AccountId, u32)>, // Gauges voted for and their weights in basis points
+    }
+
+    #[ink(event)]
+    pub struct EmissionDistributed {
+        #[ink(topic)]
+        gauge: AccountId, // Address of the gauge
+        epoch: u64,       // Epoch the emission belongs to
+        amount: Balance,  // PANX sent to the gauge
+    }
+
+    #[ink(event)]
+    pub struct PanxSwept {
+        #[ink(topic)]
+        to: AccountId,   // Address the PANX was sent to
+        amount: Balance, // Swept PANX
+    }
+
+    impl GaugeController {
+        #[ink(constructor)]
+        pub fn new(
+            pair_creator: AccountId,  // Address of the PairCreator contract
+            ve_panx: AccountId,       // Address of the vePANX contract
+            panx_token: AccountId,    // Address of the PANX token contract
+            panx_per_epoch: Balance,  // PANX split among the gauges every epoch
+        ) -> Self {
+            let admin = Self::env().caller(); // The deployer adds the gauges
+            let gauges = Vec::new(); // No gauges yet
+            let gauge_of_pair = Mapping::default(); // Mapping to store the gauge of each pair
+            let gauge_votes = Mapping::default(); // Mapping to store the votes of gauges
+            let total_votes = Mapping::default(); // Mapping to store the overall votes of epochs
+            let account_votes = Mapping::default(); // Mapping to store the votes of accounts
+            let distributed = Mapping::default(); // Mapping to store the PANX sent to gauges
+            let epoch_distributed = Mapping::default(); // Mapping to store the PANX sent to all gauges in epochs
+
+            Self {
+                admin,
+                pair_creator,
+                ve_panx,
+                panx_token,
+                panx_per_epoch,
+                gauges,
+                gauge_of_pair,
+                gauge_votes,
+                total_votes,
+                account_votes,
+                distributed,
+                epoch_distributed,
+            }
+        }
+
+        /// function for the admin to add the gauge of an AZERO trading pair created by PairCreator.
+        #[ink(message)]
+        pub fn add_gauge(&mut self, gauge: AccountId) -> Result<(), GaugeControllerErrors> {
+            if self.env().caller() != self.admin {
+                return Err(GaugeControllerErrors::CallerIsNotAdmin)
+            }
+
+            if self.gauges.len() >= MAX_GAUGES {
+                return Err(GaugeControllerErrors::TooManyGauges)
+            }
+
+            if self.gauges.contains(&gauge) {
+                return Err(GaugeControllerErrors::GaugeAlreadyAdded)
+            }
+
+            let gauge_ref: GaugeRef = FromAccountId::from_account_id(gauge);
+
+            if gauge_ref.get_controller() != self.env().account_id() {
+                return Err(GaugeControllerErrors::GaugeOfAnotherController)
+            }
+
+            let pair = gauge_ref.get_pair();
+
+            if self.gauge_of_pair.contains(pair) {
+                return Err(GaugeControllerErrors::GaugeAlreadyAdded)
+            }
+
+            // cross contract call to PairCreator to check it created the pair
+            let is_pair = build_call::<DefaultEnvironment>()
+                .call(self.pair_creator)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("is_pair")))
+                        .push_arg(pair),
+                )
+                .returns::<bool>()
+                .try_invoke();
+
+            if !matches!(is_pair, Ok(Ok(true))) {
+                return Err(GaugeControllerErrors::PairNotRegistered)
+            }
+
+            self.gauges.push(gauge);
+
+            self.gauge_of_pair.insert(pair, &gauge);
+
+            Self::env().emit_event(GaugeAdded { pair, gauge });
+
+            Ok(())
+        }
+
+        /// function for the admin to set the PANX split among the gauges every epoch.
+        #[ink(message)]
+        pub fn set_panx_per_epoch(
+            &mut self,
+            panx_per_epoch: Balance,
+        ) -> Result<(), GaugeControllerErrors> {
+            if self.env().caller() != self.admin {
+                return Err(GaugeControllerErrors::CallerIsNotAdmin)
+            }
+
+            self.panx_per_epoch = panx_per_epoch;
+
+            Ok(())
+        }
+
+        /// function to vote on how the PANX emissions of the next epoch are split among the gauges.
+        /// `weights` are in basis points of the caller's voting power, voting again in the same epoch replaces the vote.
+        /// the voting power is the caller's vePANX at the start of the epoch, so PANX locked or moved during the epoch can't vote twice.
+        #[ink(message)]
+        pub fn vote(&mut self, weights: Vec<(AccountId, u32)>) -> Result<(), GaugeControllerErrors> {
+            let caller = self.env().caller();
+
+            let voting_power = self.get_voting_power_of(caller, self.get_current_epoch());
+
+            if voting_power == 0 {
+                return Err(GaugeControllerErrors::NoVotingPower)
+            }
+
+            self.record_vote(caller, voting_power, weights)
+        }
+
+        /// function to send a gauge its share of the current epoch's PANX emissions, anyone can call it.
+        /// emissions of an epoch that were not distributed during it stay in the controller until the admin sweeps them.
+        #[ink(message)]
+        pub fn distribute(&mut self, gauge: AccountId) -> Result<Balance, GaugeControllerErrors> {
+            if !self.gauges.contains(&gauge) {
+                return Err(GaugeControllerErrors::UnknownGauge)
+            }
+
+            let epoch = self.get_current_epoch();
+
+            if self.distributed.contains((epoch, gauge)) {
+                return Err(GaugeControllerErrors::AlreadyDistributed)
+            }
+
+            let amount = self.get_gauge_emission(gauge, epoch);
+
+            if amount == 0 {
+                return Err(GaugeControllerErrors::NothingToDistribute)
+            }
+
+            self.distributed.insert((epoch, gauge), &amount);
+
+            match self.get_epoch_distributed(epoch).checked_add(amount) {
+                Some(result) => {
+                    self.epoch_distributed.insert(epoch, &result);
+                }
+                None => return Err(GaugeControllerErrors::Overflow),
+            };
+
+            // cross contract call to the PANX token to send the emission to the gauge
+            if let Err(error) = PSP22Ref::transfer(&self.panx_token, gauge, amount, vec![]) {
+                return Err(GaugeControllerErrors::PSP22TransferFailed(error))
+            }
+
+            let mut gauge_ref: GaugeRef = FromAccountId::from_account_id(gauge);
+
+            // cross contract call to the gauge to stream the emission to its stakers
+            match gauge_ref
+                .call_mut()
+                .notify_reward_amount(amount)
+                .try_invoke()
+            {
+                Ok(Ok(Ok(()))) => {}
+                Ok(Ok(Err(error))) => return Err(GaugeControllerErrors::Gauge(error)),
+                Ok(Err(error)) => return Err(GaugeControllerErrors::Lang(error)),
+                Err(_) => return Err(GaugeControllerErrors::GaugeCallFailed),
+            }
+
+            Self::env().emit_event(EmissionDistributed {
+                gauge,
+                epoch,
+                amount,
+            });
+
+            Ok(amount)
+        }
+
+        /// function for the admin to send `amount` PANX held by the controller to `to`, such as the emissions of past epochs
+        /// that were not distributed during them. the PANX the gauges can still get in the current epoch can't be swept.
+        #[ink(message)]
+        pub fn sweep_panx(
+            &mut self,
+            amount: Balance,
+            to: AccountId,
+        ) -> Result<(), GaugeControllerErrors> {
+            if self.env().caller() != self.admin {
+                return Err(GaugeControllerErrors::CallerIsNotAdmin)
+            }
+
+            let panx_balance = PSP22Ref::balance_of(&self.panx_token, self.env().account_id());
+
+            if amount
+                > panx_balance
+                    .saturating_sub(self.get_undistributed_emission(self.get_current_epoch()))
+            {
+                return Err(GaugeControllerErrors::SweepTooHigh)
+            }
+
+            // cross contract call to the PANX token to send the swept PANX
+            if let Err(error) = PSP22Ref::transfer(&self.panx_token, to, amount, vec![]) {
+                return Err(GaugeControllerErrors::PSP22TransferFailed(error))
+            }
+
+            Self::env().emit_event(PanxSwept { to, amount });
+
+            Ok(())
+        }
+
+        /// function to get the PANX emissions of an epoch not sent to the gauges yet, 0 if no one voted in the previous epoch.
+        #[ink(message)]
+        pub fn get_undistributed_emission(&self, epoch: u64) -> Balance {
+            let Some(vote_epoch) = epoch.checked_sub(1) else {
+                return 0
+            };
+
+            if self.get_total_votes(vote_epoch) == 0 {
+                return 0
+            }
+
+            // the emissions of the gauges are rounded down, they add up to at most panx_per_epoch
+            self.panx_per_epoch
+                .saturating_sub(self.get_epoch_distributed(epoch))
+        }
+
+        /// function to get the PANX emission of a gauge in an epoch, split by the votes of the previous epoch.
+        #[ink(message)]
+        pub fn get_gauge_emission(&self, gauge: AccountId, epoch: u64) -> Balance {
+            let Some(vote_epoch) = epoch.checked_sub(1) else {
+                return 0
+            };
+
+            let total_votes = self.get_total_votes(vote_epoch);
+
+            if total_votes == 0 {
+                return 0
+            }
+
+            // the gauge votes are part of the total votes, the emission is at most panx_per_epoch
+            let emission = U256::from(self.panx_per_epoch)
+                * U256::from(self.get_gauge_votes(gauge, vote_epoch))
+                / U256::from(total_votes);
+
+            emission.low_u128()
+        }
+
+        /// function to get the voting power of an account in an epoch, its vePANX at the start of the epoch, 0 if the call fails.
+        /// live balances are not used: a stake could vote, be moved to another account and vote again in the same epoch.
+        #[ink(message)]
+        pub fn get_voting_power_of(&self, account: AccountId, epoch: u64) -> Balance {
+            let Some(epoch_start) = epoch.checked_mul(EPOCH_DURATION) else {
+                return 0
+            };
+
+            // cross contract call to vePANX to get the checkpointed voting power of the account
+            let voting_power = build_call::<DefaultEnvironment>()
+                .call(self.ve_panx)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("balance_of_at")))
+                        .push_arg(account)
+                        .push_arg(epoch_start),
+                )
+                .returns::<Balance>()
+                .try_invoke();
+
+            match voting_power {
+                Ok(Ok(balance)) => balance,
+                _ => 0,
+            }
+        }
+
+        /// function to get the current epoch, the number of whole epochs since the unix epoch
+        #[ink(message)]
+        pub fn get_current_epoch(&self) -> u64 {
+            self.get_current_timestamp() / EPOCH_DURATION
+        }
+
+        /// function to get the votes of a gauge in an epoch
+        #[ink(message)]
+        pub fn get_gauge_votes(&self, gauge: AccountId, epoch: u64) -> Balance {
+            self.gauge_votes.get((epoch, gauge)).unwrap_or(0)
+        }
+
+        /// function to get the overall votes in an epoch
+        #[ink(message)]
+        pub fn get_total_votes(&self, epoch: u64) -> Balance {
+            self.total_votes.get(epoch).unwrap_or(0)
+        }
+
+        /// function to get the vote of an account in an epoch
+        #[ink(message)]
+        pub fn get_account_vote(&self, account: AccountId, epoch: u64) -> AccountVote {
+            self.account_votes.get((account, epoch)).unwrap_or_default()
+        }
+
+        /// function to get the PANX sent to a gauge in an epoch
+        #[ink(message)]
+        pub fn get_distributed(&self, gauge: AccountId, epoch: u64) -> Balance {
+            self.distributed.get((epoch, gauge)).unwrap_or(0)
+        }
+
+        /// function to get the PANX sent to all the gauges in an epoch
+        #[ink(message)]
+        pub fn get_epoch_distributed(&self, epoch: u64) -> Balance {
+            self.epoch_distributed.get(epoch).unwrap_or(0)
+        }
+
+        /// function to get the gauges that can be voted for
+        #[ink(message)]
+        pub fn get_gauges(&self) -> Vec<AccountId> {
+            self.gauges.clone()
+        }
+
+        /// function to get the gauge of an AZERO trading pair
+        #[ink(message)]
+        pub fn get_gauge_of_pair(&self, pair: AccountId) -> Option<AccountId> {
+            self.gauge_of_pair.get(pair)
+        }
+
+        /// function to get the PANX split among the gauges every epoch
+        #[ink(message)]
+        pub fn get_panx_per_epoch(&self) -> Balance {
+            self.panx_per_epoch
+        }
+
+        /// function to get the current timestamp in seconds
+        #[ink(message)]
+        pub fn get_current_timestamp(&self) -> u64 {
+            self.env().block_timestamp() / 1000
+        }
+
+        /// function to check the weights of a vote and record it in the current epoch, replacing the account's previous vote.
+        fn record_vote(
+            &mut self,
+            account: AccountId,
+            voting_power: Balance,
+            weights: Vec<(AccountId, u32)>,
+        ) -> Result<(), GaugeControllerErrors> {
+            if weights.len() > MAX_VOTES_PER_ACCOUNT {
+                return Err(GaugeControllerErrors::TooManyVotes)
+            }
+
+            let mut total_weight: u32 = 0;
+
+            for (index, (gauge, weight)) in weights.iter().enumerate() {
+                if !self.gauges.contains(gauge) {
+                    return Err(GaugeControllerErrors::UnknownGauge)
+                }
+
+                if weights[..index].iter().any(|(voted_gauge, _)| voted_gauge == gauge) {
+                    return Err(GaugeControllerErrors::DuplicateVote)
+                }
+
+                total_weight = total_weight.saturating_add(*weight);
+            }
+
+            if total_weight > VOTE_DENOMINATOR {
+                return Err(GaugeControllerErrors::InvalidVoteWeights)
+            }
+
+            let epoch = self.get_current_epoch();
+
+            let mut total_votes = self.get_total_votes(epoch);
+
+            // removing the previous vote of the account in this epoch
+            let previous_vote = self.get_account_vote(account, epoch);
+
+            for (gauge, weight) in previous_vote.weights.iter() {
+                let votes = get_vote_amount(previous_vote.voting_power, *weight);
+
+                self.gauge_votes.insert(
+                    (epoch, *gauge),
+                    &self.get_gauge_votes(*gauge, epoch).saturating_sub(votes),
+                );
+
+                total_votes = total_votes.saturating_sub(votes);
+            }
+
+            for (gauge, weight) in weights.iter() {
+                let votes = get_vote_amount(voting_power, *weight);
+
+                match self.get_gauge_votes(*gauge, epoch).checked_add(votes) {
+                    Some(result) => {
+                        self.gauge_votes.insert((epoch, *gauge), &result);
+                    }
+                    None => return Err(GaugeControllerErrors::Overflow),
+                };
+
+                match total_votes.checked_add(votes) {
+                    Some(result) => {
+                        total_votes = result;
+                    }
+                    None => return Err(GaugeControllerErrors::Overflow),
+                };
+            }
+
+            self.total_votes.insert(epoch, &total_votes);
+
+            self.account_votes.insert(
+                (account, epoch),
+                &AccountVote {
+                    voting_power,
+                    weights: weights.clone(),
+                },
+            );
+
+            Self::env().emit_event(Voted {
+                account,
+                epoch,
+                voting_power,
+                weights,
+            });
+
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[ink::test]
+        fn votes_of_an_epoch_split_the_next_epoch_emissions() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut gauge_controller = GaugeController::new(
+                AccountId::from([0x01; 32]),
+                AccountId::from([0x02; 32]),
+                AccountId::from([0x03; 32]),
+                1000,
+            );
+            let gauge_a = AccountId::from([0x0A; 32]);
+            let gauge_b = AccountId::from([0x0B; 32]);
+
+            gauge_controller.gauges = vec![gauge_a, gauge_b];
+
+            // second epoch
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(
+                EPOCH_DURATION * 1000,
+            );
+
+            assert_eq!(
+                gauge_controller.record_vote(accounts.alice, 100, vec![(gauge_a, 6000), (gauge_a, 4000)]),
+                Err(GaugeControllerErrors::DuplicateVote)
+            );
+            assert_eq!(
+                gauge_controller.record_vote(accounts.alice, 100, vec![(gauge_a, 6000), (gauge_b, 5000)]),
+                Err(GaugeControllerErrors::InvalidVoteWeights)
+            );
+            assert_eq!(
+                gauge_controller.record_vote(accounts.alice, 100, vec![(accounts.bob, 10000)]),
+                Err(GaugeControllerErrors::UnknownGauge)
+            );
+
+            // Alice votes twice, only the second vote counts
+            gauge_controller
+                .record_vote(accounts.alice, 300, vec![(gauge_b, 10000)])
+                .unwrap();
+            gauge_controller
+                .record_vote(accounts.alice, 300, vec![(gauge_a, 5000), (gauge_b, 5000)])
+                .unwrap();
+            gauge_controller
+                .record_vote(accounts.bob, 100, vec![(gauge_a, 10000)])
+                .unwrap();
+
+            assert_eq!(gauge_controller.get_gauge_votes(gauge_a, 1), 250);
+            assert_eq!(gauge_controller.get_gauge_votes(gauge_b, 1), 150);
+            assert_eq!(gauge_controller.get_total_votes(1), 400);
+
+            // the votes don't split the emissions of the epoch they were cast in
+            assert_eq!(gauge_controller.get_gauge_emission(gauge_a, 1), 0);
+            assert_eq!(gauge_controller.get_gauge_emission(gauge_a, 2), 625);
+            assert_eq!(gauge_controller.get_gauge_emission(gauge_b, 2), 375);
+
+            assert_eq!(
+                gauge_controller.distribute(accounts.bob),
+                Err(GaugeControllerErrors::UnknownGauge)
+            );
+            assert_eq!(
+                gauge_controller.distribute(gauge_a),
+                Err(GaugeControllerErrors::NothingToDistribute)
+            );
+        }
+
+        #[ink::test]
+        fn only_emissions_of_past_epochs_can_be_swept() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut gauge_controller = GaugeController::new(
+                AccountId::from([0x01; 32]),
+                AccountId::from([0x02; 32]),
+                AccountId::from([0x03; 32]),
+                1000,
+            );
+            let gauge_a = AccountId::from([0x0A; 32]);
+
+            // no one voted in the first epoch, the second epoch has no emissions
+            assert_eq!(gauge_controller.get_undistributed_emission(0), 0);
+            assert_eq!(gauge_controller.get_undistributed_emission(1), 0);
+
+            gauge_controller.total_votes.insert(2, &100);
+
+            // votes of the third epoch split the emissions of the fourth, 400 of them were sent
+            gauge_controller.distributed.insert((3, gauge_a), &400);
+            gauge_controller.epoch_distributed.insert(3, &400);
+
+            assert_eq!(gauge_controller.get_undistributed_emission(3), 600);
+            assert_eq!(gauge_controller.get_epoch_distributed(3), 400);
+            assert_eq!(gauge_controller.get_distributed(gauge_a, 3), 400);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            assert_eq!(
+                gauge_controller.sweep_panx(1, accounts.bob),
+                Err(GaugeControllerErrors::CallerIsNotAdmin)
+            );
+        }
+    }
+
+    /// ink! end-to-end (E2E) tests
+    ///
+    /// cargo test --features e2e-tests -- --nocapture
+    ///
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use contract_creator::PairCreatorRef;
+        use ink::primitives::AccountId;
+        use ink_e2e::build_message;
+        use my_psp22::my_psp22::MyPsp22Ref;
+        use openbrush::{
+            contracts::psp22::psp22_external::PSP22,
+            traits::String,
+        };
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        /// Helper to get Bob's account_id from `ink_e2e::bob()` PairSigner
+        fn get_bob_account_id() -> AccountId {
+            let bob = ink_e2e::bob::<ink_e2e::PolkadotConfig>();
+            let bob_account_id_32 = bob.account_id();
+            let bob_account_id = AccountId::try_from(bob_account_id_32.as_ref()).unwrap();
+
+            bob_account_id
+        }
+
+        /// Helper to get Charlie's account_id from `ink_e2e::charlie()` PairSigner
+        fn get_charlie_account_id() -> AccountId {
+            let charlie = ink_e2e::charlie::<ink_e2e::PolkadotConfig>();
+            let charlie_account_id_32 = charlie.account_id();
+            let charlie_account_id = AccountId::try_from(charlie_account_id_32.as_ref()).unwrap();
+
+            charlie_account_id
+        }
+
+        /// Tests included in "add_gauge_vote_and_sweep_work":
+        /// 1. add_gauge of a pair created by PairCreator
+        /// 2. add_gauge of a contract PairCreator did not create
+        /// 3. vote and distribute without voting power
+        /// 4. sweep_panx
+        #[ink_e2e::test(
+            additional_contracts = "../my_psp22/Cargo.toml ../trading_pair_azero/Cargo.toml ../contract_creator/Cargo.toml ../gauge/Cargo.toml"
+        )]
+        async fn add_gauge_vote_and_sweep_work(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // PSP22 token of the trading pair
+            let psp22_constructor = MyPsp22Ref::new(
+                10000000000000000,
+                Some(String::from("TOKEN").into()),
+                Some(String::from("TKN").into()),
+                12,
+            );
+
+            let psp22_acc_id = client
+                .instantiate("my_psp22", &ink_e2e::alice(), psp22_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // PANX token the gauges are rewarded in
+            let panx_constructor = MyPsp22Ref::new(
+                10000000000000000,
+                Some(String::from("PANX").into()),
+                Some(String::from("PANX").into()),
+                12,
+            );
+
+            let panx_acc_id = client
+                .instantiate("my_psp22", &ink_e2e::alice(), panx_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // PairCreator instantiating pairs from the uploaded trading_pair_azero code
+            let pair_code_hash = client
+                .upload("trading_pair_azero", &ink_e2e::alice(), None)
+                .await
+                .expect("upload failed")
+                .code_hash;
+
+            let pair_creator_acc_id = client
+                .instantiate(
+                    "contract_creator",
+                    &ink_e2e::alice(),
+                    PairCreatorRef::new(pair_code_hash),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            let create_pair = build_message::<PairCreatorRef>(pair_creator_acc_id.clone()).call(
+                |pair_creator| {
+                    pair_creator.create_azero_trading_pair(
+                        0,
+                        psp22_acc_id,
+                        10000,
+                        panx_acc_id,
+                        get_charlie_account_id(),
+                        0,
+                    )
+                },
+            );
+
+            let pair_acc_id = client
+                .call(&ink_e2e::alice(), create_pair, 0, None)
+                .await
+                .expect("calling `create_pair` failed")
+                .return_value()
+                .expect("pair creation failed");
+
+            // Charlie's account has no vePANX contract, every account gets 0 voting power from it
+            let gauge_controller_constructor = GaugeControllerRef::new(
+                pair_creator_acc_id,
+                get_charlie_account_id(),
+                panx_acc_id,
+                1000000000000,
+            );
+
+            let gauge_controller_acc_id = client
+                .instantiate(
+                    "gauge_controller",
+                    &ink_e2e::alice(),
+                    gauge_controller_constructor,
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            let gauge_acc_id = client
+                .instantiate(
+                    "gauge",
+                    &ink_e2e::alice(),
+                    GaugeRef::new(pair_acc_id, panx_acc_id, gauge_controller_acc_id),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            let add_gauge = build_message::<GaugeControllerRef>(gauge_controller_acc_id.clone())
+                .call(|gauge_controller| gauge_controller.add_gauge(gauge_acc_id));
+
+            let add_gauge_result = client
+                .call(&ink_e2e::alice(), add_gauge, 0, None)
+                .await
+                .expect("calling `add_gauge` failed")
+                .return_value();
+
+            assert_eq!(add_gauge_result, Ok(()));
+
+            let get_gauge_of_pair =
+                build_message::<GaugeControllerRef>(gauge_controller_acc_id.clone())
+                    .call(|gauge_controller| gauge_controller.get_gauge_of_pair(pair_acc_id));
+
+            let gauge_of_pair = client
+                .call_dry_run(&ink_e2e::alice(), &get_gauge_of_pair, 0, None)
+                .await
+                .return_value();
+
+            assert_eq!(gauge_of_pair, Some(gauge_acc_id));
+
+            // a gauge of a contract PairCreator did not create can't be added
+            let other_gauge_acc_id = client
+                .instantiate(
+                    "gauge",
+                    &ink_e2e::alice(),
+                    GaugeRef::new(psp22_acc_id, panx_acc_id, gauge_controller_acc_id),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            let add_other_gauge =
+                build_message::<GaugeControllerRef>(gauge_controller_acc_id.clone())
+                    .call(|gauge_controller| gauge_controller.add_gauge(other_gauge_acc_id));
+
+            let add_other_gauge_result = client
+                .call_dry_run(&ink_e2e::alice(), &add_other_gauge, 0, None)
+                .await
+                .return_value();
+
+            assert_eq!(
+                add_other_gauge_result,
+                Err(GaugeControllerErrors::PairNotRegistered)
+            );
+
+            // without vePANX no one can vote, so nothing is distributed.
+            // epochs are a week long, distributing voted emissions is covered by the unit tests.
+            let vote = build_message::<GaugeControllerRef>(gauge_controller_acc_id.clone())
+                .call(|gauge_controller| gauge_controller.vote(vec![(gauge_acc_id, 10000)]));
+
+            let vote_result = client
+                .call_dry_run(&ink_e2e::alice(), &vote, 0, None)
+                .await
+                .return_value();
+
+            assert_eq!(vote_result, Err(GaugeControllerErrors::NoVotingPower));
+
+            let distribute = build_message::<GaugeControllerRef>(gauge_controller_acc_id.clone())
+                .call(|gauge_controller| gauge_controller.distribute(gauge_acc_id));
+
+            let distribute_result = client
+                .call_dry_run(&ink_e2e::alice(), &distribute, 0, None)
+                .await
+                .return_value();
+
+            assert_eq!(
+                distribute_result,
+                Err(GaugeControllerErrors::NothingToDistribute)
+            );
+
+            // Alice funds the controller, none of it is owed to the gauges this epoch
+            let transfer_panx = build_message::<MyPsp22Ref>(panx_acc_id.clone()).call(|my_psp22| {
+                my_psp22.transfer(gauge_controller_acc_id, 5000000000000, vec![])
+            });
+
+            client
+                .call(&ink_e2e::alice(), transfer_panx, 0, None)
+                .await
+                .expect("calling `transfer_panx` failed");
+
+            let sweep_panx = build_message::<GaugeControllerRef>(gauge_controller_acc_id.clone())
+                .call(|gauge_controller| {
+                    gauge_controller.sweep_panx(5000000000000, get_bob_account_id())
+                });
+
+            let bob_sweep_result = client
+                .call_dry_run(&ink_e2e::bob(), &sweep_panx, 0, None)
+                .await
+                .return_value();
+
+            assert_eq!(bob_sweep_result, Err(GaugeControllerErrors::CallerIsNotAdmin));
+
+            let sweep_result = client
+                .call(&ink_e2e::alice(), sweep_panx, 0, None)
+                .await
+                .expect("calling `sweep_panx` failed")
+                .return_value();
+
+            assert_eq!(sweep_result, Ok(()));
+
+            let get_bob_panx_balance = build_message::<MyPsp22Ref>(panx_acc_id.clone())
+                .call(|my_psp22| my_psp22.balance_of(get_bob_account_id()));
+
+            let bob_panx_balance = client
+                .call_dry_run(&ink_e2e::alice(), &get_bob_panx_balance, 0, None)
+                .await
+                .return_value();
+
+            assert_eq!(bob_panx_balance, 5000000000000);
+
+            Ok(())
+        }
+    }
+}
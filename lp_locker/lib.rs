@@ -0,0 +1,735 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::lp_locker::LpLockerRef;
+
+#[ink::contract]
+pub mod lp_locker {
+
+    use ink::prelude::vec; // Importing vec from ink prelude
+    use ink::prelude::vec::Vec; // Importing Vec from ink prelude
+    use ink::storage::Mapping; // Importing Mapping from ink storage
+    use openbrush::contracts::traits::psp22::{
+        PSP22Error,
+        PSP22Ref,
+    };
+    use primitive_types::U256;
+
+    /// Most locks of a trading pair returned by one `get_pair_locks` query.
+    pub const MAX_PAIR_LOCKS_PAGE: u32 = 100;
+
+    /// LP tokens of a trading pair locked for a beneficiary.
+    /// `amount` unlocks linearly from `unlock_timestamp` to `end_timestamp`, all at once if both are equal.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Lock {
+        pub pair: AccountId,        // Trading pair whose LP tokens are locked
+        pub beneficiary: AccountId, // Account that manages the lock and receives the unlocked LP tokens
+        pub amount: Balance,        // LP tokens put in the lock, including the withdrawn ones
+        pub withdrawn: Balance,     // LP tokens already withdrawn from the lock
+        pub unlock_timestamp: u64,  // Timestamp (in seconds) the LP tokens start to unlock at
+        pub end_timestamp: u64,     // Timestamp (in seconds) all the LP tokens are unlocked at
+    }
+
+    /// function to get the amount of LP tokens of a lock unlocked by its schedule at `current_timestamp`, withdrawn ones included.
+    fn get_unlocked_amount(lock: &Lock, current_timestamp: u64) -> Option<Balance> {
+        if current_timestamp < lock.unlock_timestamp {
+            return Some(0)
+        }
+
+        if current_timestamp >= lock.end_timestamp {
+            return Some(lock.amount)
+        }
+
+        // unlock_timestamp <= current_timestamp < end_timestamp, the duration can't be 0
+        let unlocked = U256::from(lock.amount)
+            * U256::from(current_timestamp - lock.unlock_timestamp)
+            / U256::from(lock.end_timestamp - lock.unlock_timestamp);
+
+        Balance::try_from(unlocked).ok()
+    }
+
+    #[ink(storage)]
+    pub struct LpLocker {
+        // Id of the next lock
+        next_lock_id: u64,
+        // Locks by id
+        locks: Mapping<u64, Lock>,
+        // Number of locks of each trading pair
+        pair_lock_count: Mapping<AccountId, u32>,
+        // Ids of the locks of each trading pair by their index in the pair
+        pair_locks: Mapping<(AccountId, u32), u64>,
+        // LP tokens of each trading pair still held by the locker
+        pair_locked_amounts: Mapping<AccountId, Balance>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+    pub enum LpLockerErrors {
+        ZeroAmount,                        // Error code for locking, splitting or withdrawing 0 LP tokens
+        InvalidSchedule,                   // Error code for a lock that unlocks now, in the past or ends before it unlocks
+        InvalidExtension,                  // Error code for an extension that unlocks sooner than the current schedule
+        LockNotFound,                      // Error code for a lock id that does not exist
+        CallerIsNotBeneficiary,            // Error code for managing a lock of another beneficiary
+        InsufficientLockedAmount,          // Error code for splitting the whole lock or more
+        NothingToWithdraw,                 // Error code for withdrawing before any LP token unlocked
+        Overflow,                          // Error code for overflow
+        PSP22TransferFromFailed(PSP22Error), // Error code for a failed transfer of the LP tokens to the locker
+        PSP22TransferFailed(PSP22Error),   // Error code for a failed transfer of the unlocked LP tokens
+    }
+
+    #[ink(event)]
+    pub struct Locked {
+        #[ink(topic)]
+        lock_id: u64,           // Id of the new lock
+        #[ink(topic)]
+        pair: AccountId,        // Trading pair whose LP tokens are locked
+        #[ink(topic)]
+        beneficiary: AccountId, // Beneficiary of the lock
+        amount: Balance,        // Locked LP tokens
+        unlock_timestamp: u64,  // Timestamp (in seconds) the LP tokens start to unlock at
+        end_timestamp: u64,     // Timestamp (in seconds) all the LP tokens are unlocked at
+    }
+
+    #[ink(event)]
+    pub struct LockExtended {
+        #[ink(topic)]
+        lock_id: u64,          // Id of the extended lock
+        unlock_timestamp: u64, // New timestamp (in seconds) the LP tokens start to unlock at
+        end_timestamp: u64,    // New timestamp (in seconds) all the LP tokens are unlocked at
+    }
+
+    #[ink(event)]
+    pub struct LockSplit {
+        #[ink(topic)]
+        lock_id: u64,           // Id of the split lock
+        #[ink(topic)]
+        new_lock_id: u64,       // Id of the lock created by the split
+        #[ink(topic)]
+        beneficiary: AccountId, // Beneficiary of the new lock
+        amount: Balance,        // LP tokens moved to the new lock
+    }
+
+    #[ink(event)]
+    pub struct Unlocked {
+        #[ink(topic)]
+        lock_id: u64,           // Id of the lock
+        #[ink(topic)]
+        beneficiary: AccountId, // Beneficiary that received the LP tokens
+        amount: Balance,        // Withdrawn LP tokens
+    }
+
+    impl LpLocker {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            let next_lock_id: u64 = 0; // First lock id
+            let locks = Mapping::default(); // Mapping to store the locks by id
+            let pair_lock_count = Mapping::default(); // Mapping to store the number of locks of each pair
+            let pair_locks = Mapping::default(); // Mapping to store the lock ids of each pair by index
+            let pair_locked_amounts = Mapping::default(); // Mapping to store the LP tokens each pair has in the locker
+
+            Self {
+                next_lock_id,
+                locks,
+                pair_lock_count,
+                pair_locks,
+                pair_locked_amounts,
+            }
+        }
+
+        /// function to lock `amount` LP tokens of `pair` for `beneficiary`, unlocking linearly from `unlock_timestamp` to `end_timestamp`.
+        /// the caller has to approve the locker to spend the LP tokens first, returns the id of the new lock.
+        #[ink(message)]
+        pub fn lock(
+            &mut self,
+            pair: AccountId,
+            amount: Balance,
+            unlock_timestamp: u64,
+            end_timestamp: u64,
+            beneficiary: AccountId,
+        ) -> Result<u64, LpLockerErrors> {
+            let caller = self.env().caller();
+
+            if amount == 0 {
+                return Err(LpLockerErrors::ZeroAmount)
+            }
+
+            if unlock_timestamp <= self.get_current_timestamp() || end_timestamp < unlock_timestamp {
+                return Err(LpLockerErrors::InvalidSchedule)
+            }
+
+            let lock_id = self.add_lock(Lock {
+                pair,
+                beneficiary,
+                amount,
+                withdrawn: 0,
+                unlock_timestamp,
+                end_timestamp,
+            })?;
+
+            // cross contract call to the pair to transfer the LP tokens to the locker
+            if let Err(error) = PSP22Ref::transfer_from(
+                &pair,
+                caller,
+                self.env().account_id(),
+                amount,
+                vec![],
+            ) {
+                return Err(LpLockerErrors::PSP22TransferFromFailed(error))
+            }
+
+            Self::env().emit_event(Locked {
+                lock_id,
+                pair,
+                beneficiary,
+                amount,
+                unlock_timestamp,
+                end_timestamp,
+            });
+
+            Ok(lock_id)
+        }
+
+        /// function for the beneficiary to postpone the schedule of a lock, neither timestamp can move sooner.
+        #[ink(message)]
+        pub fn extend_lock(
+            &mut self,
+            lock_id: u64,
+            unlock_timestamp: u64,
+            end_timestamp: u64,
+        ) -> Result<(), LpLockerErrors> {
+            let mut lock = self.get_caller_lock(lock_id)?;
+
+            if unlock_timestamp < lock.unlock_timestamp
+                || end_timestamp < lock.end_timestamp
+                || end_timestamp < unlock_timestamp
+            {
+                return Err(LpLockerErrors::InvalidExtension)
+            }
+
+            lock.unlock_timestamp = unlock_timestamp;
+            lock.end_timestamp = end_timestamp;
+
+            self.locks.insert(lock_id, &lock);
+
+            Self::env().emit_event(LockExtended {
+                lock_id,
+                unlock_timestamp,
+                end_timestamp,
+            });
+
+            Ok(())
+        }
+
+        /// function for the beneficiary to move `amount` of a lock to a new lock of `beneficiary` with the same schedule.
+        /// the new lock takes the same share of the LP tokens already withdrawn, returns the id of the new lock.
+        #[ink(message)]
+        pub fn split_lock(
+            &mut self,
+            lock_id: u64,
+            amount: Balance,
+            beneficiary: AccountId,
+        ) -> Result<u64, LpLockerErrors> {
+            let mut lock = self.get_caller_lock(lock_id)?;
+
+            if amount == 0 {
+                return Err(LpLockerErrors::ZeroAmount)
+            }
+
+            // the split lock has to keep some LP tokens
+            if amount >= lock.amount {
+                return Err(LpLockerErrors::InsufficientLockedAmount)
+            }
+
+            let moved_withdrawn: Balance;
+
+            // share of the withdrawn LP tokens moved with the amount, rounded up in favour of the split lock
+            match Balance::try_from(
+                (U256::from(lock.withdrawn) * U256::from(amount) + U256::from(lock.amount - 1))
+                    / U256::from(lock.amount),
+            ) {
+                Ok(result) => {
+                    moved_withdrawn = result;
+                }
+                Err(_) => return Err(LpLockerErrors::Overflow),
+            };
+
+            let new_lock = Lock {
+                pair: lock.pair,
+                beneficiary,
+                amount,
+                withdrawn: moved_withdrawn,
+                unlock_timestamp: lock.unlock_timestamp,
+                end_timestamp: lock.end_timestamp,
+            };
+
+            // amount < lock.amount and moved_withdrawn <= lock.withdrawn
+            lock.amount -= amount;
+            lock.withdrawn -= moved_withdrawn;
+
+            self.locks.insert(lock_id, &lock);
+
+            // the LP tokens moved to the new lock are counted again when it is added
+            self.sub_pair_locked_amount(lock.pair, amount - moved_withdrawn);
+
+            let new_lock_id = self.add_lock(new_lock)?;
+
+            Self::env().emit_event(LockSplit {
+                lock_id,
+                new_lock_id,
+                beneficiary,
+                amount,
+            });
+
+            Ok(new_lock_id)
+        }
+
+        /// function for the beneficiary to withdraw the unlocked LP tokens of a lock, returns the withdrawn amount.
+        #[ink(message)]
+        pub fn withdraw(&mut self, lock_id: u64) -> Result<Balance, LpLockerErrors> {
+            let mut lock = self.get_caller_lock(lock_id)?;
+
+            let withdrawable_amount = self.get_withdrawable_amount(lock_id)?;
+
+            if withdrawable_amount == 0 {
+                return Err(LpLockerErrors::NothingToWithdraw)
+            }
+
+            // withdrawable_amount is at most amount - withdrawn
+            lock.withdrawn += withdrawable_amount;
+
+            self.locks.insert(lock_id, &lock);
+
+            self.sub_pair_locked_amount(lock.pair, withdrawable_amount);
+
+            // cross contract call to the pair to transfer the unlocked LP tokens to the beneficiary
+            if let Err(error) = PSP22Ref::transfer(
+                &lock.pair,
+                lock.beneficiary,
+                withdrawable_amount,
+                vec![],
+            ) {
+                return Err(LpLockerErrors::PSP22TransferFailed(error))
+            }
+
+            Self::env().emit_event(Unlocked {
+                lock_id,
+                beneficiary: lock.beneficiary,
+                amount: withdrawable_amount,
+            });
+
+            Ok(withdrawable_amount)
+        }
+
+        /// function to get a lock by its id
+        #[ink(message)]
+        pub fn get_lock(&self, lock_id: u64) -> Option<Lock> {
+            self.locks.get(lock_id)
+        }
+
+        /// function to get the LP tokens of a lock that can be withdrawn now
+        #[ink(message)]
+        pub fn get_withdrawable_amount(&self, lock_id: u64) -> Result<Balance, LpLockerErrors> {
+            let lock = self.locks.get(lock_id).ok_or(LpLockerErrors::LockNotFound)?;
+
+            match get_unlocked_amount(&lock, self.get_current_timestamp()) {
+                // a postponed schedule can unlock less than what was already withdrawn
+                Some(result) => Ok(result.saturating_sub(lock.withdrawn)),
+                None => Err(LpLockerErrors::Overflow),
+            }
+        }
+
+        /// function to get the number of locks of a trading pair, withdrawn locks included
+        #[ink(message)]
+        pub fn get_pair_lock_count(&self, pair: AccountId) -> u32 {
+            self.pair_lock_count.get(pair).unwrap_or(0)
+        }
+
+        /// function to get up to `limit` ids and locks of a trading pair from index `start`, in the order they were created.
+        /// withdrawn locks are included, a page holds at most MAX_PAIR_LOCKS_PAGE locks.
+        #[ink(message)]
+        pub fn get_pair_locks(
+            &self,
+            pair: AccountId,
+            start: u32,
+            limit: u32,
+        ) -> Vec<(u64, Lock)> {
+            let end = start
+                .saturating_add(limit.min(MAX_PAIR_LOCKS_PAGE))
+                .min(self.get_pair_lock_count(pair));
+
+            (start..end)
+                .filter_map(|index| self.pair_locks.get((pair, index)))
+                .filter_map(|lock_id| self.locks.get(lock_id).map(|lock| (lock_id, lock)))
+                .collect()
+        }
+
+        /// function to get the LP tokens of a trading pair that are still held by the locker
+        #[ink(message)]
+        pub fn get_pair_locked_amount(&self, pair: AccountId) -> Balance {
+            self.pair_locked_amounts.get(pair).unwrap_or(0)
+        }
+
+        /// function to get the current timestamp in seconds
+        #[ink(message)]
+        pub fn get_current_timestamp(&self) -> u64 {
+            self.env().block_timestamp() / 1000
+        }
+
+        /// function to get a lock of the caller, failing if it does not exist or belongs to another beneficiary.
+        fn get_caller_lock(&self, lock_id: u64) -> Result<Lock, LpLockerErrors> {
+            let lock = self.locks.get(lock_id).ok_or(LpLockerErrors::LockNotFound)?;
+
+            if lock.beneficiary != self.env().caller() {
+                return Err(LpLockerErrors::CallerIsNotBeneficiary)
+            }
+
+            Ok(lock)
+        }
+
+        /// function to store a new lock under the next id and list it under its pair.
+        fn add_lock(&mut self, lock: Lock) -> Result<u64, LpLockerErrors> {
+            let lock_id = self.next_lock_id;
+
+            match lock_id.checked_add(1) {
+                Some(result) => {
+                    self.next_lock_id = result;
+                }
+                None => return Err(LpLockerErrors::Overflow),
+            };
+
+            let pair_lock_index = self.get_pair_lock_count(lock.pair);
+
+            match pair_lock_index.checked_add(1) {
+                Some(result) => {
+                    self.pair_lock_count.insert(lock.pair, &result);
+                }
+                None => return Err(LpLockerErrors::Overflow),
+            };
+
+            self.pair_locks.insert((lock.pair, pair_lock_index), &lock_id);
+
+            // withdrawn <= amount
+            match self
+                .get_pair_locked_amount(lock.pair)
+                .checked_add(lock.amount - lock.withdrawn)
+            {
+                Some(result) => {
+                    self.pair_locked_amounts.insert(lock.pair, &result);
+                }
+                None => return Err(LpLockerErrors::Overflow),
+            };
+
+            self.locks.insert(lock_id, &lock);
+
+            Ok(lock_id)
+        }
+
+        /// function to take LP tokens that left a lock off the locked amount of its pair.
+        fn sub_pair_locked_amount(&mut self, pair: AccountId, amount: Balance) {
+            // the locked amount of a pair is the sum of the amounts left in its locks
+            let pair_locked_amount = self.get_pair_locked_amount(pair).saturating_sub(amount);
+
+            self.pair_locked_amounts.insert(pair, &pair_locked_amount);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn new_lock(amount: Balance, withdrawn: Balance) -> Lock {
+            Lock {
+                pair: AccountId::from([0x01; 32]),
+                beneficiary: ink::env::test::default_accounts::<ink::env::DefaultEnvironment>()
+                    .alice,
+                amount,
+                withdrawn,
+                unlock_timestamp: 1000,
+                end_timestamp: 2000,
+            }
+        }
+
+        #[ink::test]
+        fn unlocked_amount_follows_the_schedule() {
+            let lock = new_lock(1000, 0);
+
+            assert_eq!(get_unlocked_amount(&lock, 999), Some(0));
+            assert_eq!(get_unlocked_amount(&lock, 1000), Some(0));
+            assert_eq!(get_unlocked_amount(&lock, 1250), Some(250));
+            assert_eq!(get_unlocked_amount(&lock, 2000), Some(1000));
+            assert_eq!(get_unlocked_amount(&lock, 5000), Some(1000));
+
+            let cliff = Lock {
+                end_timestamp: 1000,
+                ..new_lock(1000, 0)
+            };
+
+            assert_eq!(get_unlocked_amount(&cliff, 999), Some(0));
+            assert_eq!(get_unlocked_amount(&cliff, 1000), Some(1000));
+        }
+
+        #[ink::test]
+        fn split_lock_keeps_the_schedule_and_the_withdrawn_share() {
+            let mut lp_locker = LpLocker::new();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            let lock_id = lp_locker.add_lock(new_lock(1000, 300)).unwrap();
+
+            assert_eq!(
+                lp_locker.split_lock(lock_id, 1000, accounts.bob),
+                Err(LpLockerErrors::InsufficientLockedAmount)
+            );
+
+            let new_lock_id = lp_locker.split_lock(lock_id, 250, accounts.bob).unwrap();
+
+            assert_eq!(lp_locker.get_lock(lock_id), Some(new_lock(750, 225)));
+            assert_eq!(
+                lp_locker.get_lock(new_lock_id),
+                Some(Lock {
+                    beneficiary: accounts.bob,
+                    ..new_lock(250, 75)
+                })
+            );
+            assert_eq!(
+                lp_locker.get_pair_locked_amount(AccountId::from([0x01; 32])),
+                700
+            );
+            assert_eq!(
+                lp_locker.extend_lock(new_lock_id, 2000, 3000),
+                Err(LpLockerErrors::CallerIsNotBeneficiary)
+            );
+            assert_eq!(
+                lp_locker.extend_lock(lock_id, 999, 3000),
+                Err(LpLockerErrors::InvalidExtension)
+            );
+        }
+
+        #[ink::test]
+        fn pair_locks_are_paginated() {
+            let mut lp_locker = LpLocker::new();
+            let pair = AccountId::from([0x01; 32]);
+
+            for amount in 1..=(MAX_PAIR_LOCKS_PAGE as Balance + 5) {
+                lp_locker.add_lock(new_lock(amount, 0)).unwrap();
+            }
+
+            assert_eq!(lp_locker.get_pair_lock_count(pair), MAX_PAIR_LOCKS_PAGE + 5);
+            assert_eq!(lp_locker.get_pair_lock_count(AccountId::from([0x02; 32])), 0);
+
+            // pages are capped to MAX_PAIR_LOCKS_PAGE locks
+            let first_page = lp_locker.get_pair_locks(pair, 0, u32::MAX);
+
+            assert_eq!(first_page.len(), MAX_PAIR_LOCKS_PAGE as usize);
+            assert_eq!(first_page[0], (0, new_lock(1, 0)));
+
+            // the last page stops at the last lock
+            assert_eq!(
+                lp_locker.get_pair_locks(pair, MAX_PAIR_LOCKS_PAGE + 3, 10),
+                vec![
+                    (103, new_lock(104, 0)),
+                    (104, new_lock(105, 0)),
+                ]
+            );
+            assert_eq!(lp_locker.get_pair_locks(pair, u32::MAX, u32::MAX), vec![]);
+        }
+    }
+
+    /// ink! end-to-end (E2E) tests
+    ///
+    /// cargo test --features e2e-tests -- --nocapture
+    ///
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink::primitives::AccountId;
+        use ink_e2e::build_message;
+        use my_psp22::my_psp22::MyPsp22Ref;
+        use openbrush::{
+            contracts::psp22::psp22_external::PSP22,
+            traits::String,
+        };
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        /// Helper to get Alice's account_id from `ink_e2e::alice()` PairSigner
+        fn get_alice_account_id() -> AccountId {
+            let alice = ink_e2e::alice::<ink_e2e::PolkadotConfig>();
+            let alice_account_id_32 = alice.account_id();
+            let alice_account_id = AccountId::try_from(alice_account_id_32.as_ref()).unwrap();
+
+            alice_account_id
+        }
+
+        /// Helper to get Bob's account_id from `ink_e2e::bob()` PairSigner
+        fn get_bob_account_id() -> AccountId {
+            let bob = ink_e2e::bob::<ink_e2e::PolkadotConfig>();
+            let bob_account_id_32 = bob.account_id();
+            let bob_account_id = AccountId::try_from(bob_account_id_32.as_ref()).unwrap();
+
+            bob_account_id
+        }
+
+        /// Tests included in "lock_extend_and_split_work":
+        /// 1. lock
+        /// 2. get_pair_locks and get_pair_locked_amount
+        /// 3. extend_lock
+        /// 4. split_lock
+        /// 5. withdraw before the unlock timestamp
+        #[ink_e2e::test(additional_contracts = "../my_psp22/Cargo.toml")]
+        async fn lock_extend_and_split_work(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // PSP22 token standing in for the LP token of a trading pair
+            let psp22_constructor = MyPsp22Ref::new(
+                10000000000000000,
+                Some(String::from("LP TOKEN").into()),
+                Some(String::from("LP").into()),
+                12,
+            );
+
+            // Instantiate new PSP22 token using the constructor
+            let pair_acc_id = client
+                .instantiate("my_psp22", &ink_e2e::alice(), psp22_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // Instantiate new LP locker
+            let locker_acc_id = client
+                .instantiate("lp_locker", &ink_e2e::alice(), LpLockerRef::new(), 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // Alice approves the locker for 1000 LP tokens
+            let approve_locker = build_message::<MyPsp22Ref>(pair_acc_id.clone())
+                .call(|my_psp22| my_psp22.approve(locker_acc_id, 1000000000000000));
+
+            client
+                .call(&ink_e2e::alice(), approve_locker, 0, None)
+                .await
+                .expect("calling `approve_locker` failed");
+
+            // Alice locks 1000 LP tokens for herself, unlocking linearly during 2100
+            let lock = build_message::<LpLockerRef>(locker_acc_id.clone()).call(|lp_locker| {
+                lp_locker.lock(
+                    pair_acc_id,
+                    1000000000000000,
+                    4102444800,
+                    4133980800,
+                    get_alice_account_id(),
+                )
+            });
+
+            let lock_id = client
+                .call(&ink_e2e::alice(), lock, 0, None)
+                .await
+                .expect("calling `lock` failed")
+                .return_value()
+                .expect("lock failed");
+
+            // The locker holds the LP tokens
+            let locker_balance = build_message::<MyPsp22Ref>(pair_acc_id.clone())
+                .call(|my_psp22| my_psp22.balance_of(locker_acc_id));
+
+            let locker_balance_res = client
+                .call(&ink_e2e::alice(), locker_balance, 0, None)
+                .await
+                .expect("locker_balance failed")
+                .return_value();
+
+            assert_eq!(locker_balance_res, 1000000000000000);
+
+            // Alice postpones the end of the lock by a year
+            let extend_lock = build_message::<LpLockerRef>(locker_acc_id.clone())
+                .call(|lp_locker| lp_locker.extend_lock(lock_id, 4102444800, 4165516800));
+
+            client
+                .call(&ink_e2e::alice(), extend_lock, 0, None)
+                .await
+                .expect("calling `extend_lock` failed");
+
+            // Alice moves 400 LP tokens of the lock to Bob
+            let split_lock = build_message::<LpLockerRef>(locker_acc_id.clone())
+                .call(|lp_locker| lp_locker.split_lock(lock_id, 400000000000000, get_bob_account_id()));
+
+            let new_lock_id = client
+                .call(&ink_e2e::alice(), split_lock, 0, None)
+                .await
+                .expect("calling `split_lock` failed")
+                .return_value()
+                .expect("split_lock failed");
+
+            // Both locks are listed under the pair with the extended schedule
+            let get_pair_locks = build_message::<LpLockerRef>(locker_acc_id.clone())
+                .call(|lp_locker| lp_locker.get_pair_locks(pair_acc_id, 0, 10));
+
+            let pair_locks = client
+                .call(&ink_e2e::alice(), get_pair_locks, 0, None)
+                .await
+                .expect("get_pair_locks failed")
+                .return_value();
+
+            assert_eq!(
+                pair_locks,
+                vec![
+                    (
+                        lock_id,
+                        Lock {
+                            pair: pair_acc_id,
+                            beneficiary: get_alice_account_id(),
+                            amount: 600000000000000,
+                            withdrawn: 0,
+                            unlock_timestamp: 4102444800,
+                            end_timestamp: 4165516800,
+                        }
+                    ),
+                    (
+                        new_lock_id,
+                        Lock {
+                            pair: pair_acc_id,
+                            beneficiary: get_bob_account_id(),
+                            amount: 400000000000000,
+                            withdrawn: 0,
+                            unlock_timestamp: 4102444800,
+                            end_timestamp: 4165516800,
+                        }
+                    ),
+                ]
+            );
+
+            let get_pair_locked_amount = build_message::<LpLockerRef>(locker_acc_id.clone())
+                .call(|lp_locker| lp_locker.get_pair_locked_amount(pair_acc_id));
+
+            let pair_locked_amount = client
+                .call(&ink_e2e::alice(), get_pair_locked_amount, 0, None)
+                .await
+                .expect("get_pair_locked_amount failed")
+                .return_value();
+
+            assert_eq!(pair_locked_amount, 1000000000000000);
+
+            // Nothing unlocked yet
+            let withdraw = build_message::<LpLockerRef>(locker_acc_id.clone())
+                .call(|lp_locker| lp_locker.withdraw(new_lock_id));
+
+            let withdraw_res = client
+                .call_dry_run(&ink_e2e::bob(), &withdraw, 0, None)
+                .await
+                .return_value();
+
+            assert_eq!(withdraw_res, Err(LpLockerErrors::NothingToWithdraw));
+
+            // Only the beneficiary manages a lock
+            let withdraw_res = client
+                .call_dry_run(&ink_e2e::alice(), &withdraw, 0, None)
+                .await
+                .return_value();
+
+            assert_eq!(withdraw_res, Err(LpLockerErrors::CallerIsNotBeneficiary));
+
+            Ok(())
+        }
+    }
+}
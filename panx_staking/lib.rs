@@ -0,0 +1,843 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::panx_staking::PanxStakingRef;
+
+#[ink::contract]
+pub mod panx_staking {
+
+    use ink::prelude::vec; // Importing vec from ink prelude
+    use ink::prelude::vec::Vec; // Importing Vec from ink prelude
+    use ink::storage::Mapping; // Importing Mapping from ink storage
+    use openbrush::contracts::traits::psp22::{
+        PSP22Error,
+        PSP22Ref,
+    };
+    use primitive_types::U256;
+
+    /// Scale of the reward per staked token accumulators.
+    pub const REWARD_PRECISION: Balance = 1_000_000_000_000;
+
+    /// Largest number of reward tokens, so that every stake update fits in a block.
+    pub const MAX_REWARD_TOKENS: usize = 8;
+
+    /// function to get `amount` times `price` divided by `denominator`, computed in U256.
+    fn mul_div(amount: Balance, price: Balance, denominator: Balance) -> Option<Balance> {
+        let result = (U256::from(amount) * U256::from(price)).checked_div(U256::from(denominator))?;
+
+        Balance::try_from(result).ok()
+    }
+
+    /// PANX of an account waiting for the unbonding period to end.
+    #[derive(Debug, Default, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Unbonding {
+        pub amount: Balance,        // Unstaked PANX
+        pub release_timestamp: u64, // Timestamp (in seconds) the PANX can be withdrawn at
+    }
+
+    #[ink(storage)]
+    pub struct PanxStaking {
+        // Account allowed to add reward tokens
+        admin: AccountId,
+        // PANX PSP22 token
+        panx_token: AccountId,
+        // Seconds unstaked PANX waits before it can be withdrawn
+        unbonding_period: u64,
+        // Staked PANX of each account
+        stakes: Mapping<AccountId, Balance>,
+        // Overall staked PANX
+        total_staked: Balance,
+        // Unstaked PANX of each account waiting for the unbonding period
+        unbondings: Mapping<AccountId, Unbonding>,
+        // Overall unstaked PANX waiting for the unbonding period
+        total_unbonding: Balance,
+        // Tokens the stakers are rewarded in, None standing for AZERO
+        reward_tokens: Vec<Option<AccountId>>,
+        // Rewards of each token per staked PANX since deployment, scaled by REWARD_PRECISION
+        reward_per_token: Mapping<Option<AccountId>, Balance>,
+        // Rewards of each token held for the stakers and not claimed yet
+        reward_reserves: Mapping<Option<AccountId>, Balance>,
+        // Reward per staked PANX of each account and token when its rewards were last updated
+        reward_per_token_paid: Mapping<(AccountId, Option<AccountId>), Balance>,
+        // Rewards of each account and token that can be claimed
+        pending_rewards: Mapping<(AccountId, Option<AccountId>), Balance>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+    pub enum PanxStakingErrors {
+        CallerIsNotAdmin,                    // Error code for an admin message called by another account
+        ZeroAmount,                          // Error code for staking or unstaking 0 PANX
+        InsufficientStake,                   // Error code for unstaking more than the staked PANX
+        NothingToWithdraw,                   // Error code for withdrawing with no unstaked PANX
+        StillUnbonding,                      // Error code for withdrawing before the unbonding period ended
+        NothingToClaim,                      // Error code for claiming with no pending rewards
+        NothingToCompound,                   // Error code for compounding with no pending PANX rewards
+        TooManyRewardTokens,                 // Error code for adding more than MAX_REWARD_TOKENS reward tokens
+        RewardTokenAlreadyAdded,             // Error code for adding a reward token twice
+        Overflow,                            // Error code for overflow
+        PSP22TransferFromFailed(PSP22Error), // Error code for a failed transfer of PANX to the staking contract
+        PSP22TransferFailed(PSP22Error),     // Error code for a failed transfer of PANX or PSP22 rewards
+        A0TransferFailed,                    // Error code for a failed transfer of AZERO rewards
+    }
+
+    #[ink(event)]
+    pub struct Staked {
+        #[ink(topic)]
+        account: AccountId, // Address of the staker
+        amount: Balance,    // Staked PANX
+    }
+
+    #[ink(event)]
+    pub struct Unstaked {
+        #[ink(topic)]
+        account: AccountId,     // Address of the staker
+        amount: Balance,        // Unstaked PANX
+        release_timestamp: u64, // Timestamp (in seconds) the unstaked PANX can be withdrawn at
+    }
+
+    #[ink(event)]
+    pub struct Withdrawn {
+        #[ink(topic)]
+        account: AccountId, // Address of the staker
+        amount: Balance,    // PANX sent back to the staker
+    }
+
+    #[ink(event)]
+    pub struct RewardAdded {
+        #[ink(topic)]
+        token: Option<AccountId>, // Address of the reward token, None for AZERO
+        amount: Balance,          // Rewards shared among the stakers
+    }
+
+    #[ink(event)]
+    pub struct RewardClaimed {
+        #[ink(topic)]
+        account: AccountId,       // Address of the staker
+        #[ink(topic)]
+        token: Option<AccountId>, // Address of the reward token, None for AZERO
+        amount: Balance,          // Claimed rewards
+    }
+
+    #[ink(event)]
+    pub struct Compounded {
+        #[ink(topic)]
+        account: AccountId, // Address of the staker
+        amount: Balance,    // PANX rewards added to the stake
+    }
+
+    impl PanxStaking {
+        #[ink(constructor)]
+        pub fn new(
+            panx_token: AccountId,  // Address of the PANX token contract
+            unbonding_period: u64,  // Seconds unstaked PANX waits before it can be withdrawn
+        ) -> Self {
+            let admin = Self::env().caller(); // The deployer adds reward tokens
+            let stakes = Mapping::default(); // Mapping to store the staked PANX of accounts
+            let total_staked: Balance = 0; // Overall staked PANX
+            let unbondings = Mapping::default(); // Mapping to store the unstaked PANX of accounts
+            let total_unbonding: Balance = 0; // Overall unstaked PANX
+            let reward_tokens = vec![None]; // Protocol fees in AZERO are always shared
+            let reward_per_token = Mapping::default(); // Mapping to store the accumulators of the reward tokens
+            let reward_reserves = Mapping::default(); // Mapping to store the unclaimed rewards of the reward tokens
+            let reward_per_token_paid = Mapping::default(); // Mapping to store the accumulators accounts were updated at
+            let pending_rewards = Mapping::default(); // Mapping to store the claimable rewards of accounts
+
+            Self {
+                admin,
+                panx_token,
+                unbonding_period,
+                stakes,
+                total_staked,
+                unbondings,
+                total_unbonding,
+                reward_tokens,
+                reward_per_token,
+                reward_reserves,
+                reward_per_token_paid,
+                pending_rewards,
+            }
+        }
+
+        /// function to stake `amount` PANX, the caller has to approve the staking contract first.
+        #[ink(message)]
+        pub fn stake(&mut self, amount: Balance) -> Result<(), PanxStakingErrors> {
+            let caller = self.env().caller();
+
+            if amount == 0 {
+                return Err(PanxStakingErrors::ZeroAmount)
+            }
+
+            // settling the rewards earned with the current stake
+            self.update_rewards_of(caller)?;
+
+            self.add_to_stake(caller, amount)?;
+
+            // cross contract call to the PANX token to transfer the staked PANX
+            if let Err(error) = PSP22Ref::transfer_from(
+                &self.panx_token,
+                caller,
+                self.env().account_id(),
+                amount,
+                vec![],
+            ) {
+                return Err(PanxStakingErrors::PSP22TransferFromFailed(error))
+            }
+
+            Self::env().emit_event(Staked {
+                account: caller,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        /// function to unstake `amount` PANX, it stops earning rewards and can be withdrawn after the unbonding period.
+        /// unstaking again restarts the unbonding period of all the unstaked PANX.
+        #[ink(message)]
+        pub fn unstake(&mut self, amount: Balance) -> Result<(), PanxStakingErrors> {
+            let caller = self.env().caller();
+
+            if amount == 0 {
+                return Err(PanxStakingErrors::ZeroAmount)
+            }
+
+            // settling the rewards earned with the current stake
+            self.update_rewards_of(caller)?;
+
+            match self.get_stake(caller).checked_sub(amount) {
+                Some(result) => {
+                    self.stakes.insert(caller, &result);
+                }
+                None => return Err(PanxStakingErrors::InsufficientStake),
+            };
+
+            // amount is at most the caller's stake, part of the total
+            self.total_staked -= amount;
+
+            let mut unbonding = self.get_unbonding(caller);
+
+            match unbonding.amount.checked_add(amount) {
+                Some(result) => {
+                    unbonding.amount = result;
+                }
+                None => return Err(PanxStakingErrors::Overflow),
+            };
+
+            unbonding.release_timestamp = self
+                .get_current_timestamp()
+                .saturating_add(self.unbonding_period);
+
+            match self.total_unbonding.checked_add(amount) {
+                Some(result) => {
+                    self.total_unbonding = result;
+                }
+                None => return Err(PanxStakingErrors::Overflow),
+            };
+
+            self.unbondings.insert(caller, &unbonding);
+
+            Self::env().emit_event(Unstaked {
+                account: caller,
+                amount,
+                release_timestamp: unbonding.release_timestamp,
+            });
+
+            Ok(())
+        }
+
+        /// function to withdraw the unstaked PANX once the unbonding period ended.
+        #[ink(message)]
+        pub fn withdraw_unbonded(&mut self) -> Result<(), PanxStakingErrors> {
+            let caller = self.env().caller();
+
+            let unbonding = self.get_unbonding(caller);
+
+            if unbonding.amount == 0 {
+                return Err(PanxStakingErrors::NothingToWithdraw)
+            }
+
+            if self.get_current_timestamp() < unbonding.release_timestamp {
+                return Err(PanxStakingErrors::StillUnbonding)
+            }
+
+            self.unbondings.remove(caller);
+
+            // the caller's unbonding amount is part of the total
+            self.total_unbonding -= unbonding.amount;
+
+            // cross contract call to the PANX token to send the unstaked PANX back
+            if let Err(error) =
+                PSP22Ref::transfer(&self.panx_token, caller, unbonding.amount, vec![])
+            {
+                return Err(PanxStakingErrors::PSP22TransferFailed(error))
+            }
+
+            Self::env().emit_event(Withdrawn {
+                account: caller,
+                amount: unbonding.amount,
+            });
+
+            Ok(())
+        }
+
+        /// function to claim the pending rewards of every reward token.
+        #[ink(message)]
+        pub fn claim_rewards(&mut self) -> Result<(), PanxStakingErrors> {
+            let caller = self.env().caller();
+
+            self.update_rewards_of(caller)?;
+
+            let mut claimed_any = false;
+
+            for token in self.reward_tokens.clone() {
+                let amount = self.take_pending_reward(caller, token)?;
+
+                if amount == 0 {
+                    continue
+                }
+
+                claimed_any = true;
+
+                match token {
+                    Some(token) => {
+                        // cross contract call to the reward token to transfer the rewards
+                        if let Err(error) = PSP22Ref::transfer(&token, caller, amount, vec![]) {
+                            return Err(PanxStakingErrors::PSP22TransferFailed(error))
+                        }
+                    }
+                    None => {
+                        if self.env().transfer(caller, amount).is_err() {
+                            return Err(PanxStakingErrors::A0TransferFailed)
+                        }
+                    }
+                }
+
+                Self::env().emit_event(RewardClaimed {
+                    account: caller,
+                    token,
+                    amount,
+                });
+            }
+
+            if !claimed_any {
+                return Err(PanxStakingErrors::NothingToClaim)
+            }
+
+            Ok(())
+        }
+
+        /// function to add the pending PANX rewards to the caller's stake instead of claiming them.
+        #[ink(message)]
+        pub fn compound(&mut self) -> Result<(), PanxStakingErrors> {
+            let caller = self.env().caller();
+
+            self.update_rewards_of(caller)?;
+
+            let amount = self.take_pending_reward(caller, Some(self.panx_token))?;
+
+            if amount == 0 {
+                return Err(PanxStakingErrors::NothingToCompound)
+            }
+
+            // the PANX rewards are already held by the staking contract
+            self.add_to_stake(caller, amount)?;
+
+            Self::env().emit_event(Compounded {
+                account: caller,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        /// function to share the rewards of a token received since the last update among the stakers, anyone can call it.
+        #[ink(message)]
+        pub fn update_reward(&mut self, token: Option<AccountId>) -> Result<(), PanxStakingErrors> {
+            // rewards received while nothing is staked wait for the first staker
+            if self.total_staked == 0 {
+                return Ok(())
+            }
+
+            let new_rewards = self
+                .get_reward_balance(token)
+                .saturating_sub(self.get_reward_reserve(token));
+
+            let reward_per_token_increase =
+                mul_div(new_rewards, REWARD_PRECISION, self.total_staked)
+                    .ok_or(PanxStakingErrors::Overflow)?;
+
+            if reward_per_token_increase == 0 {
+                return Ok(())
+            }
+
+            // rounding leftovers are shared with the next rewards
+            let shared_rewards = mul_div(
+                reward_per_token_increase,
+                self.total_staked,
+                REWARD_PRECISION,
+            )
+            .ok_or(PanxStakingErrors::Overflow)?;
+
+            match self
+                .get_reward_per_token(token)
+                .checked_add(reward_per_token_increase)
+            {
+                Some(result) => {
+                    self.reward_per_token.insert(token, &result);
+                }
+                None => return Err(PanxStakingErrors::Overflow),
+            };
+
+            // shared_rewards is at most new_rewards, which fits next to the reserve
+            self.reward_reserves
+                .insert(token, &(self.get_reward_reserve(token) + shared_rewards));
+
+            Self::env().emit_event(RewardAdded {
+                token,
+                amount: shared_rewards,
+            });
+
+            Ok(())
+        }
+
+        /// function for the admin to reward the stakers in a PSP22 token, PANX included.
+        #[ink(message)]
+        pub fn add_reward_token(&mut self, token: AccountId) -> Result<(), PanxStakingErrors> {
+            if self.env().caller() != self.admin {
+                return Err(PanxStakingErrors::CallerIsNotAdmin)
+            }
+
+            if self.reward_tokens.contains(&Some(token)) {
+                return Err(PanxStakingErrors::RewardTokenAlreadyAdded)
+            }
+
+            if self.reward_tokens.len() >= MAX_REWARD_TOKENS {
+                return Err(PanxStakingErrors::TooManyRewardTokens)
+            }
+
+            self.reward_tokens.push(Some(token));
+
+            Ok(())
+        }
+
+        /// function to get the staked PANX of an account
+        #[ink(message)]
+        pub fn get_stake(&self, account: AccountId) -> Balance {
+            self.stakes.get(account).unwrap_or(0)
+        }
+
+        /// function to get the PANX of an account the trading pairs base swap fee discounts on.
+        /// only staked PANX counts, so PANX borrowed for a single transaction can't reach a discount tier.
+        #[ink(message)]
+        pub fn get_discount_balance(&self, account: AccountId) -> Balance {
+            self.get_stake(account)
+        }
+
+        /// function to get the overall staked PANX
+        #[ink(message)]
+        pub fn get_total_staked(&self) -> Balance {
+            self.total_staked
+        }
+
+        /// function to get the unstaked PANX of an account waiting for the unbonding period
+        #[ink(message)]
+        pub fn get_unbonding(&self, account: AccountId) -> Unbonding {
+            self.unbondings.get(account).unwrap_or_default()
+        }
+
+        /// function to get the seconds unstaked PANX waits before it can be withdrawn
+        #[ink(message)]
+        pub fn get_unbonding_period(&self) -> u64 {
+            self.unbonding_period
+        }
+
+        /// function to get the reward tokens, None standing for AZERO
+        #[ink(message)]
+        pub fn get_reward_tokens(&self) -> Vec<Option<AccountId>> {
+            self.reward_tokens.clone()
+        }
+
+        /// function to get the rewards of a token per staked PANX since deployment, scaled by REWARD_PRECISION
+        #[ink(message)]
+        pub fn get_reward_per_token(&self, token: Option<AccountId>) -> Balance {
+            self.reward_per_token.get(token).unwrap_or(0)
+        }
+
+        /// function to get the rewards of a token an account can claim, as of the last reward update.
+        #[ink(message)]
+        pub fn get_pending_rewards(&self, account: AccountId, token: Option<AccountId>) -> Balance {
+            let earned_rewards = mul_div(
+                self.get_stake(account),
+                self.get_reward_per_token(token)
+                    .saturating_sub(self.reward_per_token_paid.get((account, token)).unwrap_or(0)),
+                REWARD_PRECISION,
+            )
+            .unwrap_or(0);
+
+            self.pending_rewards
+                .get((account, token))
+                .unwrap_or(0)
+                .saturating_add(earned_rewards)
+        }
+
+        /// function to get the current timestamp in seconds
+        #[ink(message)]
+        pub fn get_current_timestamp(&self) -> u64 {
+            self.env().block_timestamp() / 1000
+        }
+
+        /// function to get the rewards of a token held for the stakers and not claimed yet.
+        fn get_reward_reserve(&self, token: Option<AccountId>) -> Balance {
+            self.reward_reserves.get(token).unwrap_or(0)
+        }
+
+        /// function to get the balance of a reward token held by the staking contract, staked and unbonding PANX excluded.
+        fn get_reward_balance(&self, token: Option<AccountId>) -> Balance {
+            match token {
+                Some(token) => {
+                    let balance = PSP22Ref::balance_of(&token, self.env().account_id());
+
+                    if token == self.panx_token {
+                        balance
+                            .saturating_sub(self.total_staked)
+                            .saturating_sub(self.total_unbonding)
+                    } else {
+                        balance
+                    }
+                }
+                // the existential deposit of the staking contract is not a reward
+                None => self
+                    .env()
+                    .balance()
+                    .saturating_sub(self.env().minimum_balance()),
+            }
+        }
+
+        /// function to update the reward tokens, then move the rewards an account earned since its last update to its pending rewards.
+        fn update_rewards_of(&mut self, account: AccountId) -> Result<(), PanxStakingErrors> {
+            for token in self.reward_tokens.clone() {
+                self.update_reward(token)?;
+
+                let pending_rewards = self.get_pending_rewards(account, token);
+
+                self.pending_rewards
+                    .insert((account, token), &pending_rewards);
+
+                self.reward_per_token_paid
+                    .insert((account, token), &self.get_reward_per_token(token));
+            }
+
+            Ok(())
+        }
+
+        /// function to zero the pending rewards of an account in a token and release them from the reserve, returns the amount.
+        fn take_pending_reward(
+            &mut self,
+            account: AccountId,
+            token: Option<AccountId>,
+        ) -> Result<Balance, PanxStakingErrors> {
+            let amount = self.pending_rewards.get((account, token)).unwrap_or(0);
+
+            if amount == 0 {
+                return Ok(0)
+            }
+
+            self.pending_rewards.remove((account, token));
+
+            match self.get_reward_reserve(token).checked_sub(amount) {
+                Some(result) => {
+                    self.reward_reserves.insert(token, &result);
+                }
+                None => return Err(PanxStakingErrors::Overflow),
+            };
+
+            Ok(amount)
+        }
+
+        /// function to add PANX to the stake of an account, its rewards have to be updated first.
+        fn add_to_stake(&mut self, account: AccountId, amount: Balance) -> Result<(), PanxStakingErrors> {
+            match self.get_stake(account).checked_add(amount) {
+                Some(result) => {
+                    self.stakes.insert(account, &result);
+                }
+                None => return Err(PanxStakingErrors::Overflow),
+            };
+
+            match self.total_staked.checked_add(amount) {
+                Some(result) => {
+                    self.total_staked = result;
+                }
+                None => return Err(PanxStakingErrors::Overflow),
+            };
+
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[ink::test]
+        fn azero_rewards_are_shared_pro_rata() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
+            let mut panx_staking = PanxStaking::new(AccountId::from([0x01; 32]), 86400);
+
+            // Alice stakes 300 PANX and Bob 100 PANX
+            panx_staking.add_to_stake(accounts.alice, 300).unwrap();
+            panx_staking.add_to_stake(accounts.bob, 100).unwrap();
+
+            // 1000 AZERO of protocol fees reach the staking contract
+            let minimum_balance = panx_staking.env().minimum_balance();
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+                contract,
+                minimum_balance + 1000,
+            );
+
+            panx_staking.update_reward(None).unwrap();
+
+            assert_eq!(panx_staking.get_reward_per_token(None), 2_500_000_000_000);
+            assert_eq!(panx_staking.get_pending_rewards(accounts.alice, None), 750);
+            assert_eq!(panx_staking.get_pending_rewards(accounts.bob, None), 250);
+
+            let alice_balance =
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.alice)
+                    .unwrap();
+
+            panx_staking.claim_rewards().unwrap();
+
+            assert_eq!(
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.alice),
+                Ok(alice_balance + 750)
+            );
+            assert_eq!(panx_staking.get_pending_rewards(accounts.alice, None), 0);
+            assert_eq!(
+                panx_staking.claim_rewards(),
+                Err(PanxStakingErrors::NothingToClaim)
+            );
+
+            // Bob's rewards stay reserved for him
+            panx_staking.update_reward(None).unwrap();
+
+            assert_eq!(panx_staking.get_pending_rewards(accounts.bob, None), 250);
+        }
+
+        #[ink::test]
+        fn unstaked_panx_waits_for_the_unbonding_period() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut panx_staking = PanxStaking::new(AccountId::from([0x01; 32]), 86400);
+
+            panx_staking.add_to_stake(accounts.alice, 300).unwrap();
+
+            assert_eq!(
+                panx_staking.unstake(301),
+                Err(PanxStakingErrors::InsufficientStake)
+            );
+
+            panx_staking.unstake(100).unwrap();
+
+            assert_eq!(panx_staking.get_stake(accounts.alice), 200);
+            assert_eq!(panx_staking.get_total_staked(), 200);
+            assert_eq!(
+                panx_staking.get_unbonding(accounts.alice),
+                Unbonding {
+                    amount: 100,
+                    release_timestamp: 86400,
+                }
+            );
+            assert_eq!(
+                panx_staking.withdraw_unbonded(),
+                Err(PanxStakingErrors::StillUnbonding)
+            );
+            assert_eq!(
+                panx_staking.compound(),
+                Err(PanxStakingErrors::NothingToCompound)
+            );
+        }
+    }
+
+    /// ink! end-to-end (E2E) tests
+    ///
+    /// cargo test --features e2e-tests -- --nocapture
+    ///
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink::primitives::AccountId;
+        use ink_e2e::build_message;
+        use my_psp22::my_psp22::MyPsp22Ref;
+        use openbrush::{
+            contracts::psp22::psp22_external::PSP22,
+            traits::String,
+        };
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        /// Helper to get Bob's account_id from `ink_e2e::bob()` PairSigner
+        fn get_bob_account_id() -> AccountId {
+            let bob = ink_e2e::bob::<ink_e2e::PolkadotConfig>();
+            let bob_account_id_32 = bob.account_id();
+            let bob_account_id = AccountId::try_from(bob_account_id_32.as_ref()).unwrap();
+
+            bob_account_id
+        }
+
+        /// Tests included in "psp22_rewards_are_claimed_pro_rata":
+        /// 1. add_reward_token
+        /// 2. stake
+        /// 3. update_reward and get_pending_rewards
+        /// 4. claim_rewards
+        #[ink_e2e::test(additional_contracts = "../my_psp22/Cargo.toml")]
+        async fn psp22_rewards_are_claimed_pro_rata(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            // PANX token, minted to Alice
+            let panx_constructor = MyPsp22Ref::new(
+                10000000000000000,
+                Some(String::from("PANX").into()),
+                Some(String::from("PANX").into()),
+                12,
+            );
+
+            let panx_acc_id = client
+                .instantiate("my_psp22", &ink_e2e::alice(), panx_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // Reward token, minted to Alice
+            let reward_constructor = MyPsp22Ref::new(
+                10000000000000000,
+                Some(String::from("TOKEN").into()),
+                Some(String::from("TKN").into()),
+                12,
+            );
+
+            let reward_acc_id = client
+                .instantiate("my_psp22", &ink_e2e::alice(), reward_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // Staking contract with a one week unbonding period
+            let staking_acc_id = client
+                .instantiate(
+                    "panx_staking",
+                    &ink_e2e::alice(),
+                    PanxStakingRef::new(panx_acc_id, 604800),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            let add_reward_token = build_message::<PanxStakingRef>(staking_acc_id.clone())
+                .call(|panx_staking| panx_staking.add_reward_token(reward_acc_id));
+
+            client
+                .call(&ink_e2e::alice(), add_reward_token, 0, None)
+                .await
+                .expect("calling `add_reward_token` failed");
+
+            // Alice gives Bob 100 PANX
+            let transfer_to_bob = build_message::<MyPsp22Ref>(panx_acc_id.clone())
+                .call(|my_psp22| my_psp22.transfer(get_bob_account_id(), 100000000000000, vec![]));
+
+            client
+                .call(&ink_e2e::alice(), transfer_to_bob, 0, None)
+                .await
+                .expect("calling `transfer_to_bob` failed");
+
+            // Alice stakes 300 PANX and Bob 100 PANX
+            let approve_alice = build_message::<MyPsp22Ref>(panx_acc_id.clone())
+                .call(|my_psp22| my_psp22.approve(staking_acc_id, 300000000000000));
+
+            client
+                .call(&ink_e2e::alice(), approve_alice, 0, None)
+                .await
+                .expect("calling `approve_alice` failed");
+
+            let stake_alice = build_message::<PanxStakingRef>(staking_acc_id.clone())
+                .call(|panx_staking| panx_staking.stake(300000000000000));
+
+            client
+                .call(&ink_e2e::alice(), stake_alice, 0, None)
+                .await
+                .expect("calling `stake_alice` failed");
+
+            let approve_bob = build_message::<MyPsp22Ref>(panx_acc_id.clone())
+                .call(|my_psp22| my_psp22.approve(staking_acc_id, 100000000000000));
+
+            client
+                .call(&ink_e2e::bob(), approve_bob, 0, None)
+                .await
+                .expect("calling `approve_bob` failed");
+
+            let stake_bob = build_message::<PanxStakingRef>(staking_acc_id.clone())
+                .call(|panx_staking| panx_staking.stake(100000000000000));
+
+            client
+                .call(&ink_e2e::bob(), stake_bob, 0, None)
+                .await
+                .expect("calling `stake_bob` failed");
+
+            // 40 reward tokens of protocol fees reach the staking contract
+            let transfer_rewards = build_message::<MyPsp22Ref>(reward_acc_id.clone())
+                .call(|my_psp22| my_psp22.transfer(staking_acc_id, 40000000000000, vec![]));
+
+            client
+                .call(&ink_e2e::alice(), transfer_rewards, 0, None)
+                .await
+                .expect("calling `transfer_rewards` failed");
+
+            let update_reward = build_message::<PanxStakingRef>(staking_acc_id.clone())
+                .call(|panx_staking| panx_staking.update_reward(Some(reward_acc_id)));
+
+            client
+                .call(&ink_e2e::bob(), update_reward, 0, None)
+                .await
+                .expect("calling `update_reward` failed");
+
+            let get_pending_rewards = build_message::<PanxStakingRef>(staking_acc_id.clone())
+                .call(|panx_staking| {
+                    panx_staking.get_pending_rewards(get_bob_account_id(), Some(reward_acc_id))
+                });
+
+            let bob_pending_rewards = client
+                .call(&ink_e2e::bob(), get_pending_rewards, 0, None)
+                .await
+                .expect("get_pending_rewards failed")
+                .return_value();
+
+            assert_eq!(bob_pending_rewards, 10000000000000);
+
+            // Bob claims his quarter of the rewards
+            let claim_rewards = build_message::<PanxStakingRef>(staking_acc_id.clone())
+                .call(|panx_staking| panx_staking.claim_rewards());
+
+            client
+                .call(&ink_e2e::bob(), claim_rewards, 0, None)
+                .await
+                .expect("calling `claim_rewards` failed");
+
+            let bob_reward_balance = build_message::<MyPsp22Ref>(reward_acc_id.clone())
+                .call(|my_psp22| my_psp22.balance_of(get_bob_account_id()));
+
+            let bob_reward_balance_res = client
+                .call(&ink_e2e::bob(), bob_reward_balance, 0, None)
+                .await
+                .expect("bob_reward_balance failed")
+                .return_value();
+
+            assert_eq!(bob_reward_balance_res, 10000000000000);
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,163 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+//! PSP22 token used by the trading pair e2e tests. Every transfer made on behalf of
+//! the target trading pair calls back into the pair before moving the tokens, so the
+//! tests can check that the pair rejects re-entrant calls to the chosen message.
+
+pub use self::reentrant_psp22::ReentrantPsp22Ref;
+
+#[openbrush::implementation(PSP22)]
+#[openbrush::contract]
+pub mod reentrant_psp22 {
+
+    use ink::env::{
+        call::{
+            build_call,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+    };
+    use openbrush::{
+        contracts::psp22::*,
+        traits::Storage,
+    };
+
+    /// Message of the trading pair to call back into.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum ReentryCall {
+        #[default]
+        Approve,                // PSP22::approve of the LP tokens, the cheapest state-changing message
+        SwapPsp22,              // swap_psp22 of 1 PSP22 unit, with the tokens moving mid-operation
+        WithdrawSpecificAmount, // withdraw_specific_amount of 1 LP share held by the token
+    }
+
+    #[ink(storage)]
+    #[derive(Default, Storage)]
+    pub struct ReentrantPsp22 {
+        #[storage_field]
+        psp22: psp22::Data,
+        // Trading pair to call back into while it moves tokens
+        target: Option<AccountId>,
+        // Message of the trading pair to call back into
+        reentry_call: ReentryCall,
+        // Number of calls made back into the target
+        reentry_attempts: u32,
+        // Number of calls back into the target that succeeded
+        successful_reentries: u32,
+    }
+
+    #[overrider(psp22::Internal)]
+    fn _transfer_from_to(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        amount: Balance,
+        _data: Vec<u8>,
+    ) -> Result<(), PSP22Error> {
+        if let Some(target) = self.target {
+            // only transfers made by the target are used to re-enter it
+            if Self::env().caller() == target {
+                self.reentry_attempts += 1;
+
+                let reentered = match self.reentry_call {
+                    ReentryCall::Approve => {
+                        let reentry = build_call::<DefaultEnvironment>()
+                            .call(target)
+                            .exec_input(
+                                ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                                    "PSP22::approve"
+                                )))
+                                .push_arg(Self::env().account_id())
+                                .push_arg(Balance::MAX),
+                            )
+                            .returns::<Result<(), PSP22Error>>()
+                            .try_invoke();
+
+                        matches!(reentry, Ok(Ok(Ok(()))))
+                    }
+                    ReentryCall::SwapPsp22 => {
+                        // any slippage, the swap only has to go through
+                        let reentry = build_call::<DefaultEnvironment>()
+                            .call(target)
+                            .exec_input(
+                                ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                                    "swap_psp22"
+                                )))
+                                .push_arg(1 as Balance)
+                                .push_arg(0 as Balance)
+                                .push_arg(Balance::MAX),
+                            )
+                            .returns::<Result<(), ()>>()
+                            .try_invoke();
+
+                        matches!(reentry, Ok(Ok(Ok(()))))
+                    }
+                    ReentryCall::WithdrawSpecificAmount => {
+                        let reentry = build_call::<DefaultEnvironment>()
+                            .call(target)
+                            .exec_input(
+                                ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                                    "withdraw_specific_amount"
+                                )))
+                                .push_arg(1 as Balance),
+                            )
+                            .returns::<Result<(), ()>>()
+                            .try_invoke();
+
+                        matches!(reentry, Ok(Ok(Ok(()))))
+                    }
+                };
+
+                if reentered {
+                    self.successful_reentries += 1;
+                }
+            }
+        }
+
+        let from_balance = self._balance_of(&from);
+
+        if from_balance < amount {
+            return Err(PSP22Error::InsufficientBalance)
+        }
+
+        self.psp22.balances.insert(&from, &(from_balance - amount));
+        let to_balance = self._balance_of(&to);
+        self.psp22.balances.insert(&to, &(to_balance + amount));
+
+        Ok(())
+    }
+
+    impl ReentrantPsp22 {
+        #[ink(constructor)]
+        pub fn new(initial_supply: Balance) -> Self {
+            let mut _instance = Self::default();
+            psp22::Internal::_mint_to(&mut _instance, Self::env().caller(), initial_supply)
+                .expect("Should mint");
+            _instance
+        }
+
+        /// function to set the trading pair and the message of it to call back into.
+        #[ink(message)]
+        pub fn set_target(&mut self, target: AccountId, reentry_call: ReentryCall) {
+            self.target = Some(target);
+            self.reentry_call = reentry_call;
+        }
+
+        /// function to get the number of calls made back into the target.
+        #[ink(message)]
+        pub fn get_reentry_attempts(&self) -> u32 {
+            self.reentry_attempts
+        }
+
+        /// function to get the number of calls back into the target that succeeded.
+        #[ink(message)]
+        pub fn get_successful_reentries(&self) -> u32 {
+            self.successful_reentries
+        }
+    }
+}
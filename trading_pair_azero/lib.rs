@@ -4,15 +4,173 @@
 #[openbrush::contract]
 pub mod trading_pair_azero {
 
-    use ink::env::CallFlags; // Importing CallFlags from ink env
+    use ink::env::{
+        call::{
+            build_call,
+            ExecutionInput,
+            Selector,
+        },
+        CallFlags,
+        DefaultEnvironment,
+    }; // Importing call builders and CallFlags from ink env
+    use ink::LangError; // Importing LangError from ink
     use ink::prelude::vec; // Importing vec from ink prelude
+    use ink::storage::Lazy; // Importing Lazy from ink storage
     use ink::storage::Mapping; // Importing Mapping from ink storage
     use openbrush::{
         contracts::traits::psp22::PSP22Ref,
-        traits::Storage,
+        traits::{
+            Storage,
+            String,
+        },
     };
     use primitive_types::U256;
 
+    /// Denominator of the LP and traders fees, fees are expressed in parts per million (1% = 10000).
+    pub const FEE_DENOMINATOR: Balance = 1_000_000;
+
+    /// Fee charged to traders and accrued for the vault (0.25%).
+    pub const TRADERS_FEE: Balance = 2_500;
+
+    /// Highest LP fee of a pair, in parts per million (10%).
+    pub const MAX_FEE: Balance = 100_000;
+
+    /// Lock duration (one year, in seconds) that gets the highest incentive boost.
+    pub const MAX_LP_LOCK_BOOST_DURATION: u64 = 365 * 86400;
+
+    /// Highest incentive boost of locked LP shares, in basis points (+100%).
+    pub const MAX_LP_LOCK_BOOST_BPS: Balance = 10_000;
+
+    /// Highest share of the LP fee growth the protocol fee can take, in parts per million (50%).
+    pub const MAX_PROTOCOL_FEE_SHARE: Balance = 500_000;
+
+    /// Largest number of swap fee discount tiers.
+    pub const MAX_DISCOUNT_TIERS: usize = 5;
+
+    /// Highest LP fee discount of a tier, in basis points (the whole LP fee).
+    pub const MAX_DISCOUNT_BPS: Balance = 10_000;
+
+    /// Highest incentive boost of a discount tier, in basis points (+100%).
+    pub const MAX_DISCOUNT_INCENTIVE_BOOST_BPS: Balance = 10_000;
+
+    /// Largest number of streamed reward tokens, so that every LP share change fits in a block.
+    pub const MAX_REWARD_TOKENS: usize = 5;
+
+    /// Shortest (one day) and longest (one year) duration a reward is streamed over, in seconds.
+    pub const MIN_REWARD_DURATION: u64 = 86400;
+    pub const MAX_REWARD_DURATION: u64 = 365 * 86400;
+
+    /// Scale of the streamed reward per LP share accumulators.
+    pub const REWARD_PRECISION: Balance = 1_000_000_000_000;
+
+    /// Maps every failure of a cross-contract PSP22 `transfer_from` call to an error carrying its cause.
+    fn check_transfer_from_result(
+        result: Result<Result<Result<(), PSP22Error>, LangError>, ink::env::Error>,
+    ) -> Result<(), TradingPairErrors> {
+        match result {
+            Ok(Ok(Ok(()))) => Ok(()),
+            Ok(Ok(Err(error))) => Err(TradingPairErrors::PSP22TransferFromFailed(error)),
+            Ok(Err(error)) => Err(TradingPairErrors::Lang(error)),
+            Err(error) => Err(TradingPairErrors::Env(EnvErrorCode::from(error))),
+        }
+    }
+
+    /// Returns whether the product of the reserves (x * y = k) did not decrease.
+    fn is_constant_product_kept(
+        a0_reserve_before: Balance,
+        psp22_reserve_before: Balance,
+        a0_reserve_after: Balance,
+        psp22_reserve_after: Balance,
+    ) -> bool {
+        U256::from(a0_reserve_after) * U256::from(psp22_reserve_after)
+            >= U256::from(a0_reserve_before) * U256::from(psp22_reserve_before)
+    }
+
+    /// Returns whether the reserves backing each LP share did not decrease.
+    fn are_reserves_per_share_kept(
+        (a0_reserve_before, psp22_reserve_before, total_supply_before): (Balance, Balance, Balance),
+        (a0_reserve_after, psp22_reserve_after, total_supply_after): (Balance, Balance, Balance),
+    ) -> bool {
+        // nothing to compare against when the pool was or became empty
+        if total_supply_before == 0 || total_supply_after == 0 {
+            return true
+        }
+
+        U256::from(a0_reserve_after) * U256::from(total_supply_before)
+            >= U256::from(a0_reserve_before) * U256::from(total_supply_after)
+            && U256::from(psp22_reserve_after) * U256::from(total_supply_before)
+                >= U256::from(psp22_reserve_before) * U256::from(total_supply_after)
+    }
+
+    /// Returns whether the reserves backing each LP share decreased by no more than the PSP22 an incentive payout took from the pool.
+    fn is_incentive_payout_kept(
+        (a0_reserve_before, psp22_reserve_before, total_supply_before): (Balance, Balance, Balance),
+        reserves_after: (Balance, Balance, Balance),
+        psp22_incentive_paid: Balance,
+    ) -> bool {
+        // the incentive can't pay more than the pooled PSP22
+        let Some(psp22_reserve_without_incentive) =
+            psp22_reserve_before.checked_sub(psp22_incentive_paid)
+        else {
+            return false
+        };
+
+        are_reserves_per_share_kept(
+            (
+                a0_reserve_before,
+                psp22_reserve_without_incentive,
+                total_supply_before,
+            ),
+            reserves_after,
+        )
+    }
+
+    /// Returns the square root of the product of the reserves (sqrt(k)).
+    fn get_root_k(a0_reserve: Balance, psp22_reserve: Balance) -> Balance {
+        // the square root of a product of two u128 always fits in a u128
+        (U256::from(a0_reserve) * U256::from(psp22_reserve))
+            .integer_sqrt()
+            .low_u128()
+    }
+
+    /// Returns the LP shares to mint to the protocol so that it owns `protocol_fee_share` of the
+    /// growth of sqrt(k) since `root_k_last`, as in Uniswap v2's `_mintFee`.
+    fn get_protocol_fee_shares(
+        total_supply: Balance,
+        root_k: Balance,
+        root_k_last: Balance,
+        protocol_fee_share: Balance,
+    ) -> Option<Balance> {
+        // no growth to take a share of
+        if root_k <= root_k_last {
+            return Some(0)
+        }
+
+        // shares = total_supply * share * (root_k - root_k_last) / (root_k * (1 - share) + root_k_last * share)
+        let numerator = U256::from(total_supply)
+            * U256::from(protocol_fee_share)
+            * U256::from(root_k - root_k_last);
+
+        let denominator = U256::from(root_k)
+            * U256::from(FEE_DENOMINATOR.checked_sub(protocol_fee_share)?)
+            + U256::from(root_k_last) * U256::from(protocol_fee_share);
+
+        Balance::try_from(numerator.checked_div(denominator)?).ok()
+    }
+
+    /// Returns the increase of a price cumulative: the price (`reserve_out` per `reserve_in`, scaled by 10^12)
+    /// times the elapsed seconds. Cumulatives wrap around on overflow, so only their differences are meaningful.
+    fn get_price_cumulative_increase(
+        reserve_out: Balance,
+        reserve_in: Balance,
+        elapsed_seconds: u64,
+    ) -> Balance {
+        // reserve_in is never 0 when called, and the low 128 bits keep the wrapping arithmetic
+        (U256::from(reserve_out) * U256::from(10u128.pow(12)) / U256::from(reserve_in)
+            * U256::from(elapsed_seconds))
+        .low_u128()
+    }
+
     #[ink(storage)]
     #[derive(Storage)]
     pub struct TradingPairAzero {
@@ -54,6 +212,64 @@ pub mod trading_pair_azero {
         lp_lock_timestamp: u64,
         // Deployer account address
         deployer: AccountId,
+        // AZERO payouts that failed to transfer and can be collected with `claim_a0`
+        pending_a0_claims: Mapping<AccountId, Balance>,
+        // Sum of all pending AZERO claims, excluded from the pooled AZERO reserve
+        total_pending_a0_claims: Balance,
+        // Set while a state-changing message runs. Kept in its own storage cell so that
+        // a re-entrant call reads the written value instead of the one loaded at the
+        // start of the outer message.
+        reentrancy_lock: Lazy<bool>,
+        // Pooled AZERO as of the last reserve change
+        reserve_a0: Balance,
+        // Pooled PSP22 tokens as of the last reserve change
+        reserve_psp22: Balance,
+        // Swap statistics since the pair was deployed
+        swap_stats: SwapStats,
+        // Swap statistics of each day, by day number (timestamp in seconds / 86400)
+        daily_swap_stats: Mapping<u64, SwapStats>,
+        // Overall swap volume (AZERO, PSP22) of each account
+        account_swap_volume: Mapping<AccountId, (Balance, Balance)>,
+        // Locked LP shares of each account
+        lp_locks: Mapping<AccountId, LpLock>,
+        // Part of each account's daily PSP22 incentive that comes from its LP lock boost
+        psp22_boost_in_a_day: Mapping<AccountId, Balance>,
+        // Part of each account's daily PSP22 incentive that comes from its staked or locked PANX
+        psp22_commitment_boost_in_a_day: Mapping<AccountId, Balance>,
+        // Account allowed to rescue tokens sent to the pair by mistake
+        admin: AccountId,
+        // Treasury the protocol fee is minted to, None while the protocol fee is off
+        protocol_fee_to: Option<AccountId>,
+        // Share of the LP fee growth taken by the protocol fee, in parts per million
+        protocol_fee_share: Balance,
+        // sqrt(k) after the last liquidity change while the protocol fee was on, 0 otherwise
+        root_k_last: Balance,
+        // PSP22 traders fees accrued for the vault, excluded from the pooled PSP22
+        pending_vault_psp22: Balance,
+        // AZERO traders fees accrued for the vault, excluded from the pooled AZERO
+        pending_vault_a0: Balance,
+        // Time weighted sum of the PSP22 price in AZERO (scaled by 10^12), wrapping on overflow
+        psp22_price_cumulative: Balance,
+        // Time weighted sum of the AZERO price in PSP22 (scaled by 10^12), wrapping on overflow
+        a0_price_cumulative: Balance,
+        // Timestamp (in seconds) the price cumulatives were last updated at
+        price_cumulatives_timestamp: u64,
+        // Staking or locking contract reporting the PANX of accounts, no discount while None
+        discount_source: Option<AccountId>,
+        // LP fee discount tiers, sorted by ascending minimum PANX
+        discount_tiers: Vec<DiscountTier>,
+        // PSP22 tokens that can be streamed to the LPs
+        reward_tokens: Vec<AccountId>,
+        // Stream of each reward token
+        reward_streams: Mapping<AccountId, RewardStream>,
+        // Reward per LP share of each reward token and account when its rewards were last updated
+        reward_per_share_paid: Mapping<(AccountId, AccountId), Balance>,
+        // Streamed rewards of each reward token and account that can be claimed
+        pending_stream_rewards: Mapping<(AccountId, AccountId), Balance>,
+        // Account allowed to stream each reward token besides the admin
+        reward_distributors: Mapping<AccountId, AccountId>,
+        // Contracts holding LP shares that can't claim streamed rewards (LP locker, farm, gauges)
+        unclaimable_reward_holders: Mapping<AccountId, bool>,
     }
 
     #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
@@ -65,9 +281,9 @@ pub mod trading_pair_azero {
         Overflow,                       // Error code for overflow
         ZeroSharesGiven,                // Error code for zero shares given
         SlippageTolerance,              // Error code for slippage tolerance
-        PSP22TransferFromFailed,        // Error code for failed PSP22 transferFrom
-        PSP22TransferFailed,            // Error code for failed PSP22 transfer
-        A0TransferFailed,               // Error code for failed AZERO transfer
+        PSP22TransferFromFailed(PSP22Error), // Error code for failed PSP22 transferFrom, with the token's error
+        PSP22TransferFailed(PSP22Error), // Error code for failed PSP22 transfer, with the token's error
+        A0TransferFailed(EnvErrorCode), // Error code for failed AZERO transfer, with the environment error
         CallerInsufficientLPBalance,    // Error code for insufficient LP balance in caller
         ContractOutOfA0,                // Error code for contract out of pooled AZERO tokens
         ContractOutOfPSP22,             // Error code for contract out of pooled PSP22 tokens
@@ -79,10 +295,207 @@ pub mod trading_pair_azero {
         RemoveLpIncentiveProgramError, // Error code for remove LP incentive program error
         LpStillLocked,             // Error code for remove LP before the lock date
         ZeroRedeemableAmount,
+        ZeroClaimableAmount, // Error code for claiming AZERO with no pending claim
+        InvariantViolated,   // Error code for a pool operation that decreased the pool value
+        InvalidLockTimestamp, // Error code for a lock ending now, in the past or before the current lock
+        NoActiveLpLock,      // Error code for extending an LP lock that does not exist or expired
+        CallerIsNotAdmin,    // Error code for an admin message called by another account
+        CannotRescuePoolToken, // Error code for rescuing the pooled PSP22 token
+        InsufficientRescuableA0, // Error code for rescuing more AZERO than was sent outside the pool
+        InvalidProtocolFeeShare, // Error code for a protocol fee share above MAX_PROTOCOL_FEE_SHARE
+        InvalidFee,          // Error code for an LP fee above MAX_FEE
+        InvalidDiscountTiers, // Error code for too many, unsorted or above MAX_DISCOUNT_BPS discount tiers
+        TooManyRewardTokens, // Error code for adding more than MAX_REWARD_TOKENS reward tokens
+        RewardTokenAlreadyAdded, // Error code for adding a reward token twice
+        UnknownRewardToken, // Error code for streaming a token the admin did not add
+        CannotStreamPoolToken, // Error code for adding the pooled PSP22 token as a reward token
+        InvalidRewardDuration, // Error code for a reward duration outside MIN_REWARD_DURATION and MAX_REWARD_DURATION
+        ZeroRewardRate,      // Error code for a reward too small to stream anything every second
+        ZeroRewardsToClaim,  // Error code for claiming streamed rewards with none pending
+        CallerIsNotRewardDistributor, // Error code for streaming a reward token by neither its distributor nor the admin
+        NotAnUnclaimableRewardHolder, // Error code for sweeping the rewards of an account the admin did not mark as unclaimable
+        ZeroRewardsToSweep,  // Error code for sweeping streamed rewards with none unallocated or pending
+        CannotRescueRewardToken, // Error code for rescuing a streamed reward token
+        ZeroPendingVaultFees, // Error code for collecting the traders fees with none accrued
+        Reentrancy,          // Error code for a call made while another pair message is running
+        Underflow,           // Error code for underflow
+        DivisionByZero,      // Error code for division by zero (empty pool or zero amount)
+        Env(EnvErrorCode),   // Error code for a cross-contract call the environment failed
+        Lang(LangError),     // Error code for a cross-contract call the callee could not dispatch
+    }
+
+    /// Encodable mirror of `ink::env::Error`, which can't be returned from a message as is.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+    pub enum EnvErrorCode {
+        Decode,         // The call output could not be decoded
+        CalleeTrapped,  // The called contract trapped
+        CalleeReverted, // The called contract reverted
+        KeyNotFound,    // A storage key was not found
+        TransferFailed, // A balance transfer failed (e.g. below the existential deposit)
+        CodeNotFound,   // No code is stored under the given code hash
+        NotCallable,    // The account is not a contract
+        Unknown,        // Any other environment error
+    }
+
+    impl From<ink::env::Error> for EnvErrorCode {
+        fn from(error: ink::env::Error) -> Self {
+            match error {
+                ink::env::Error::Decode(_) => EnvErrorCode::Decode,
+                ink::env::Error::CalleeTrapped => EnvErrorCode::CalleeTrapped,
+                ink::env::Error::CalleeReverted => EnvErrorCode::CalleeReverted,
+                ink::env::Error::KeyNotFound => EnvErrorCode::KeyNotFound,
+                ink::env::Error::TransferFailed => EnvErrorCode::TransferFailed,
+                ink::env::Error::CodeNotFound => EnvErrorCode::CodeNotFound,
+                ink::env::Error::NotCallable => EnvErrorCode::NotCallable,
+                _ => EnvErrorCode::Unknown,
+            }
+        }
+    }
+
+    /// Snapshot of the pool returned by `get_pool_state`.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+    pub struct PoolState {
+        pub psp22_token: AccountId,     // Address of the pooled PSP22 token
+        pub a0_reserve: Balance,        // Pooled AZERO (pending AZERO claims excluded)
+        pub psp22_reserve: Balance,     // Pooled PSP22 tokens
+        pub total_supply: Balance,      // Total supply of LP shares
+        pub fee: Balance,               // LP fee in parts per million
+        pub psp22_lp_fee_vault: Balance, // PSP22 LP fees not yet withdrawn by LPs
+        pub azero_lp_fee_vault: Balance, // AZERO LP fees not yet withdrawn by LPs
+        pub overall_generated_psp22_fee: Balance, // Overall PSP22 LP fees generated by the pair
+        pub overall_generated_azero_fee: Balance, // Overall AZERO LP fees generated by the pair
+        pub price_for_one_psp22: Balance, // Mid-price: AZERO for one PSP22 token, before fees
+        pub transactions_num: i64,      // Total number of swaps
+        pub lp_lock_timestamp: u64,     // Deployer LP lock timestamp
+    }
+
+    /// LP position of an account returned by `get_position`.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+    pub struct Position {
+        pub account: AccountId,             // Address of the LP
+        pub shares: Balance,                // LP shares held by the account
+        pub a0_amount: Balance,             // Pooled AZERO owned by the shares, LP fees included
+        pub psp22_amount: Balance,          // Pooled PSP22 owned by the shares, LP fees included
+        pub pending_a0_lp_fee: Balance,     // AZERO LP fees earned by the shares
+        pub pending_psp22_lp_fee: Balance,  // PSP22 LP fees earned by the shares
+        pub pending_psp22_incentive: Balance, // Incentive program PSP22 redeemable right now
+        pub psp22_daily_incentive: Balance, // Incentive program PSP22 given each day
+        pub is_locked: bool,                // Whether some of the shares can't be withdrawn yet
+        pub locked_shares: Balance,         // Shares that can't be withdrawn or transferred yet
+        pub lock_until: u64,                // Timestamp the locked shares unlock at, 0 if not locked
+        pub lock_boost_bps: Balance,        // Incentive boost of the locked shares in basis points
+        pub pending_rewards: Vec<(AccountId, Balance)>, // Streamed rewards of each reward token
+    }
+
+    /// Stream of a PSP22 reward token to the LPs.
+    #[derive(Debug, Default, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct RewardStream {
+        pub rate: Balance,             // Reward tokens streamed every second
+        pub period_finish: u64,        // Timestamp (in seconds) the stream ends at
+        pub last_update_time: u64,     // Timestamp (in seconds) the reward per share was last updated at
+        pub reward_per_share: Balance, // Rewards per LP share as of the last update, scaled by REWARD_PRECISION
+        pub unallocated: Balance,      // Rewards streamed while there were no LP shares or left over by the rate, which the admin can sweep
+    }
+
+    /// Fee tier applied to a swap.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+    pub enum FeeTier {
+        Standard,       // Full LP fee
+        Discounted(u8), // LP fee discounted by the discount tier at this index
+    }
+
+    /// LP fee discount and incentive boost of accounts with enough staked or locked PANX (or vePANX).
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct DiscountTier {
+        pub min_panx: Balance,             // PANX reported by the discount source to reach the tier
+        pub discount_bps: Balance,         // Discount of the LP fee in basis points
+        pub incentive_boost_bps: Balance,  // Incentive program boost in basis points
+    }
+
+    /// Fee breakdown of a swap returned by the quote messages.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+    pub struct SwapQuote {
+        pub amount_in: Balance,        // Amount of tokens given by the trader
+        pub amount_out: Balance,       // Amount of tokens the trader receives after all fees
+        pub lp_fee: Balance,           // Amount of output tokens added to the LP fee vault
+        pub traders_fee: Balance,      // Amount of output tokens accrued for the vault
+        pub traders_fee_in: Balance,   // Amount of input tokens accrued for the vault
+        pub fee_tier: FeeTier,         // Fee tier the quote was calculated with
+        pub price_impact_bps: Balance, // Price impact of the trade in basis points, fees excluded
+    }
+
+    /// Swap statistics, kept for the whole life of the pair and for each day.
+    #[derive(Debug, Default, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct SwapStats {
+        pub a0_volume: Balance,         // AZERO side of the swaps: AZERO given or received by traders
+        pub psp22_volume: Balance,      // PSP22 side of the swaps: PSP22 given or received by traders
+        pub a0_traders_fee: Balance,    // AZERO traders fees accrued for the vault
+        pub psp22_traders_fee: Balance, // PSP22 traders fees accrued for the vault
+        pub a0_lp_fee: Balance,         // AZERO added to the LP fee vault
+        pub psp22_lp_fee: Balance,      // PSP22 added to the LP fee vault
+        pub a0_to_psp22_swaps: u64,     // Number of AZERO to PSP22 swaps
+        pub psp22_to_a0_swaps: u64,     // Number of PSP22 to AZERO swaps
+    }
+
+    impl SwapStats {
+        /// Returns the sum of both statistics, or `None` on overflow.
+        fn checked_add(&self, other: &SwapStats) -> Option<SwapStats> {
+            Some(SwapStats {
+                a0_volume: self.a0_volume.checked_add(other.a0_volume)?,
+                psp22_volume: self.psp22_volume.checked_add(other.psp22_volume)?,
+                a0_traders_fee: self.a0_traders_fee.checked_add(other.a0_traders_fee)?,
+                psp22_traders_fee: self.psp22_traders_fee.checked_add(other.psp22_traders_fee)?,
+                a0_lp_fee: self.a0_lp_fee.checked_add(other.a0_lp_fee)?,
+                psp22_lp_fee: self.psp22_lp_fee.checked_add(other.psp22_lp_fee)?,
+                a0_to_psp22_swaps: self.a0_to_psp22_swaps.checked_add(other.a0_to_psp22_swaps)?,
+                psp22_to_a0_swaps: self.psp22_to_a0_swaps.checked_add(other.psp22_to_a0_swaps)?,
+            })
+        }
+    }
+
+    /// LP shares of an account that can't be withdrawn or transferred before `unlock_timestamp`.
+    #[derive(Debug, Default, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct LpLock {
+        pub shares: Balance,        // Locked LP shares
+        pub unlock_timestamp: u64,  // Timestamp (in seconds) the shares unlock at
+        pub boost_bps: Balance,     // Incentive boost of the locked shares in basis points
+    }
+
+    /// Price impact of a swap returned by the price impact details messages.
+    /// All prices are the amount of output tokens for one input token (10^12 units).
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+    pub struct PriceImpact {
+        pub mid_price_before: Balance, // Pool mid-price before the swap
+        pub execution_price: Balance,  // Average price of the pooled amount in (traders fee in excluded) before fees
+        pub mid_price_after: Balance,  // Pool mid-price after the swap
+        pub price_impact_bps: Balance, // Difference between mid-price before and execution price in basis points
     }
 
     #[ink(event)]
     pub struct LiquidityPoolProvision {
+        #[ink(topic)]
         provider: AccountId, // Address of the provider who deposited the liquidity
         a0_deposited_amount: Balance, // Amount of AZERO tokens deposited by the provider
         psp22_deposited_amount: Balance, // Amount of PSP22 tokens deposited by the provider
@@ -91,7 +504,10 @@ pub mod trading_pair_azero {
 
     #[ink(event)]
     pub struct LiquidityPoolWithdrawal {
+        #[ink(topic)]
         caller: AccountId, // Address of the caller who initiated the liquidity withdrawal
+        #[ink(topic)]
+        recipient: AccountId, // Address that received the withdrawn AZERO and PSP22 tokens
         shares_given: Balance, // Amount of LP tokens (shares) being withdrawn
         a0_given_amount: Balance, /* Amount of AZERO tokens given to the caller as part of the withdrawal */
         psp22_given_amount: Balance, /* Amount of PSP22 tokens given to the caller as part of the withdrawal */
@@ -100,18 +516,115 @@ pub mod trading_pair_azero {
 
     #[ink(event)]
     pub struct A0Swap {
+        #[ink(topic)]
         caller: AccountId, // Address of the caller who initiated the A0 token swap
+        #[ink(topic)]
+        recipient: AccountId, // Address that received the PSP22 tokens of the swap
         a0_deposited_amount: Balance, // Amount of AZERO tokens deposited by the caller for the swap
         psp22_given_amount: Balance, /* Amount of PSP22 tokens given to the caller as part of the swap */
-        psp22_given_to_vault: Balance, /* Amount of PSP22 tokens sent to the vault as part of the swap */
+        psp22_given_to_vault: Balance, /* Amount of PSP22 tokens accrued for the vault as part of the swap */
     }
 
     #[ink(event)]
     pub struct PSP22Swap {
+        #[ink(topic)]
         caller: AccountId, // Address of the caller who initiated the PSP22 token swap
+        #[ink(topic)]
+        recipient: AccountId, // Address that received the AZERO of the swap
         psp22_deposited_amount: Balance, /* Amount of PSP22 tokens deposited by the caller for the swap */
         a0_given_amount: Balance, // Amount of AZERO tokens given to the caller as part of the swap
-        a0_given_to_vault: Balance, // Amount of AZERO tokens sent to the vault as part of the swap
+        a0_given_to_vault: Balance, // Amount of AZERO tokens accrued for the vault as part of the swap
+    }
+
+    /// Emitted with the pool reserves after every operation that changes them.
+    #[ink(event)]
+    pub struct Sync {
+        reserve_a0: Balance,    // Pooled AZERO after the operation (pending AZERO claims excluded)
+        reserve_psp22: Balance, // Pooled PSP22 tokens after the operation
+    }
+
+    #[ink(event)]
+    pub struct LpLocked {
+        #[ink(topic)]
+        owner: AccountId,      // Address of the LP whose shares are locked
+        shares: Balance,       // Overall locked shares of the LP after the lock
+        unlock_timestamp: u64, // Timestamp (in seconds) the shares unlock at
+        boost_bps: Balance,    // Incentive boost of the locked shares in basis points
+    }
+
+    #[ink(event)]
+    pub struct ProtocolFeeMinted {
+        #[ink(topic)]
+        to: AccountId,   // Treasury the protocol fee was minted to
+        shares: Balance, // LP shares minted to the treasury
+    }
+
+    #[ink(event)]
+    pub struct ProtocolFeeSet {
+        to: Option<AccountId>, // Treasury the protocol fee is minted to, None if turned off
+        share: Balance,        // Share of the LP fee growth taken, in parts per million
+    }
+
+    #[ink(event)]
+    pub struct RewardNotified {
+        #[ink(topic)]
+        token: AccountId,   // Address of the reward token
+        amount: Balance,    // Reward tokens added to the stream
+        rate: Balance,      // Reward tokens streamed every second from now on
+        period_finish: u64, // Timestamp (in seconds) the stream ends at
+    }
+
+    #[ink(event)]
+    pub struct RewardClaimed {
+        #[ink(topic)]
+        account: AccountId, // Address of the LP
+        #[ink(topic)]
+        token: AccountId,   // Address of the reward token
+        amount: Balance,    // Claimed reward tokens
+    }
+
+    #[ink(event)]
+    pub struct RewardsSwept {
+        #[ink(topic)]
+        token: AccountId,          // Address of the reward token
+        holder: Option<AccountId>, // Unclaimable holder whose rewards were swept, None for the unallocated rewards
+        to: AccountId,             // Address the rewards were sent to
+        amount: Balance,           // Swept reward tokens
+    }
+
+    #[ink(event)]
+    pub struct DiscountSet {
+        source: Option<AccountId>, // Contract reporting the PANX of accounts, None if turned off
+        tiers: Vec<DiscountTier>,  // LP fee discount tiers
+    }
+
+    #[ink(event)]
+    pub struct TokensRescued {
+        #[ink(topic)]
+        token: Option<AccountId>, // Address of the rescued PSP22 token, None for AZERO
+        #[ink(topic)]
+        to: AccountId,            // Address the rescued tokens were sent to
+        amount: Balance,          // Amount of rescued tokens
+    }
+
+    #[ink(event)]
+    pub struct A0ClaimRecorded {
+        recipient: AccountId, // Address of the account whose AZERO payout failed
+        amount: Balance, // Amount of AZERO added to the recipient's pending claim
+    }
+
+    #[ink(event)]
+    pub struct ProtocolFeesCollected {
+        #[ink(topic)]
+        vault: AccountId,      // Address of the vault the traders fees were sent to
+        psp22_amount: Balance, // Amount of PSP22 tokens sent to the vault
+        a0_amount: Balance,    // Amount of AZERO sent to the vault
+    }
+
+    #[ink(event)]
+    pub struct A0Claimed {
+        caller: AccountId, // Address of the caller who collected the pending AZERO
+        amount: Balance,   // Amount of AZERO transferred to the caller
     }
 
     #[overrider(PSP22)]
@@ -123,11 +636,18 @@ pub mod trading_pair_azero {
 
     #[overrider(PSP22)]
     fn approve(&mut self, spender: AccountId, value: Balance) -> Result<(), PSP22Error> {
+        // making sure that the pair is not re-entered while another message runs
+        if self.lock_reentrancy().is_err() {
+            return Err(PSP22Error::Custom(String::from("Reentrancy")))
+        }
+
         let caller = self.get_caller_id();
 
         self.lp_tokens_allowances
             .insert((caller, spender), &(value));
 
+        self.unlock_reentrancy();
+
         Ok(())
     }
 
@@ -138,24 +658,51 @@ pub mod trading_pair_azero {
         value: Balance,
         _data: Vec<u8>,
     ) -> Result<(), PSP22Error> {
+        // making sure that the pair is not re-entered while another message runs
+        if self.lock_reentrancy().is_err() {
+            return Err(PSP22Error::Custom(String::from("Reentrancy")))
+        }
+
         let caller = self.get_caller_id();
 
         let caller_shares: Balance = self.balances.get(&caller).unwrap_or(0);
 
-        let recipient_shares: Balance = self.balances.get(&to).unwrap_or(0);
-
         if caller_shares < value {
             return Err(PSP22Error::InsufficientBalance)
         }
 
-        let new_caller_lp_balance: Balance = caller_shares - value;
+        // locked LP shares can't be transferred
+        if self.get_unlocked_shares_of(caller) < value {
+            return Err(PSP22Error::Custom(String::from("LpStillLocked")))
+        }
+
+        // settling the streamed rewards earned with the current shares
+        if self.update_stream_rewards_of(caller).is_err()
+            || self.update_stream_rewards_of(to).is_err()
+        {
+            return Err(PSP22Error::Custom(String::from("Overflow")))
+        }
 
-        let new_recipient_lp_balance: Balance = recipient_shares + value;
+        let new_caller_lp_balance: Balance = caller_shares - value;
 
         self.balances.insert(caller, &(new_caller_lp_balance));
 
+        // the recipient shares are read after the debit, so a transfer to the sender keeps its balance
+        let recipient_shares: Balance = self.balances.get(&to).unwrap_or(0);
+
+        let new_recipient_lp_balance: Balance;
+
+        match recipient_shares.checked_add(value) {
+            Some(result) => {
+                new_recipient_lp_balance = result;
+            }
+            None => return Err(PSP22Error::Custom(String::from("Overflow"))),
+        };
+
         self.balances.insert(to, &(new_recipient_lp_balance));
 
+        self.unlock_reentrancy();
+
         Ok(())
     }
 
@@ -167,6 +714,11 @@ pub mod trading_pair_azero {
         value: Balance,
         _data: Vec<u8>,
     ) -> Result<(), PSP22Error> {
+        // making sure that the pair is not re-entered while another message runs
+        if self.lock_reentrancy().is_err() {
+            return Err(PSP22Error::Custom(String::from("Reentrancy")))
+        }
+
         let caller = self.get_caller_id();
 
         let allowance = psp22::PSP22::allowance(self, from, caller);
@@ -177,18 +729,38 @@ pub mod trading_pair_azero {
 
         let from_shares: Balance = self.balances.get(&from).unwrap_or(0);
 
-        let recipient_shares: Balance = self.balances.get(&to).unwrap_or(0);
-
         if from_shares < value {
             return Err(PSP22Error::InsufficientBalance)
         }
 
-        let new_from_lp_balance: Balance = from_shares - value;
+        // locked LP shares can't be transferred
+        if self.get_unlocked_shares_of(from) < value {
+            return Err(PSP22Error::Custom(String::from("LpStillLocked")))
+        }
+
+        // settling the streamed rewards earned with the current shares
+        if self.update_stream_rewards_of(from).is_err()
+            || self.update_stream_rewards_of(to).is_err()
+        {
+            return Err(PSP22Error::Custom(String::from("Overflow")))
+        }
 
-        let new_recipient_lp_balance: Balance = recipient_shares + value;
+        let new_from_lp_balance: Balance = from_shares - value;
 
         self.balances.insert(from, &(new_from_lp_balance));
 
+        // the recipient shares are read after the debit, so a transfer to the sender keeps its balance
+        let recipient_shares: Balance = self.balances.get(&to).unwrap_or(0);
+
+        let new_recipient_lp_balance: Balance;
+
+        match recipient_shares.checked_add(value) {
+            Some(result) => {
+                new_recipient_lp_balance = result;
+            }
+            None => return Err(PSP22Error::Custom(String::from("Overflow"))),
+        };
+
         self.balances.insert(to, &(new_recipient_lp_balance));
 
         let new_allowance = allowance - value;
@@ -196,6 +768,8 @@ pub mod trading_pair_azero {
         self.lp_tokens_allowances
             .insert((from, caller), &(new_allowance));
 
+        self.unlock_reentrancy();
+
         Ok(())
     }
 
@@ -213,12 +787,18 @@ pub mod trading_pair_azero {
         #[ink(constructor)]
         pub fn new(
             psp22_contract: AccountId, // Address of the PSP22 token contract
-            fee: Balance,              // Fee to be charged for LP providers
+            fee: Balance,              // Fee to be charged for LP providers, in parts per million
             panx_contract: AccountId,  // Address of the PANX token contract
             vault: AccountId,          // Address of the vault where traders fees are sent
             lp_lock_timestamp: u64,    // Lp lock timestamp
             deployer: AccountId,
-        ) -> Self {
+            admin: AccountId,          // Address allowed to rescue tokens sent to the pair by mistake
+        ) -> Result<Self, TradingPairErrors> {
+            // a fee of FEE_DENOMINATOR or more would make every swap fail, e.g. a fee still scaled by 10^12
+            if fee > MAX_FEE {
+                return Err(TradingPairErrors::InvalidFee)
+            }
+
             let psp22: psp22::Data = Default::default();
             let transasction_number: i64 = 0; // Number of transactions initiated
             let balances = Mapping::default(); // Mapping to store user balances
@@ -233,9 +813,42 @@ pub mod trading_pair_azero {
             let account_overall_staking_rewards = Mapping::default(); // Mapping to store overall staking rewards for accounts
             let account_overall_lp_fee_rewards = Mapping::default(); // Mapping to store overall LP fee rewards for accounts
             let last_redeemed = Mapping::default(); // Mapping to store last redeemed time for accounts
+            let pending_a0_claims = Mapping::default(); // Mapping to store failed AZERO payouts for accounts
+            let total_pending_a0_claims: Balance = 0; // Total AZERO owed to accounts through pending claims
+            let mut reentrancy_lock = Lazy::default(); // Lock held while a state-changing message runs
+            reentrancy_lock.set(&false);
+            let reserve_a0: Balance = 0; // Pooled AZERO as of the last reserve change
+            let reserve_psp22: Balance = 0; // Pooled PSP22 as of the last reserve change
+            let swap_stats = SwapStats::default(); // Swap statistics since deployment
+            let daily_swap_stats = Mapping::default(); // Mapping to store swap statistics of each day
+            let account_swap_volume = Mapping::default(); // Mapping to store overall swap volume of accounts
+            let lp_locks = Mapping::default(); // Mapping to store locked LP shares of accounts
+            let psp22_boost_in_a_day = Mapping::default(); // Mapping to store daily PSP22 lock boosts
+            let psp22_commitment_boost_in_a_day = Mapping::default(); // Mapping to store daily PSP22 PANX boosts
+            let protocol_fee_to: Option<AccountId> = None; // The protocol fee starts turned off
+            let protocol_fee_share: Balance = 0; // Share of the LP fee growth taken by the protocol
+            let root_k_last: Balance = 0; // sqrt(k) after the last liquidity change with the protocol fee on
+            let pending_vault_psp22: Balance = 0; // PSP22 traders fees accrued for the vault
+            let pending_vault_a0: Balance = 0; // AZERO traders fees accrued for the vault
+            let psp22_price_cumulative: Balance = 0; // Time weighted sum of the PSP22 price
+            let a0_price_cumulative: Balance = 0; // Time weighted sum of the AZERO price
+            let price_cumulatives_timestamp: u64 = 0; // Last update of the price cumulatives
+            let discount_source: Option<AccountId> = None; // No discount until a staking contract is set
+            // Half of the LP fee for accounts with 3500 staked or locked PANX or more
+            let discount_tiers = vec![DiscountTier {
+                min_panx: 3500u128 * 10u128.pow(12),
+                discount_bps: 5000,
+                incentive_boost_bps: 0,
+            }];
+            let reward_tokens = Vec::new(); // No streamed rewards until the admin adds a token
+            let reward_streams = Mapping::default(); // Mapping to store the streams of the reward tokens
+            let reward_per_share_paid = Mapping::default(); // Mapping to store the accumulators accounts were updated at
+            let pending_stream_rewards = Mapping::default(); // Mapping to store the claimable streamed rewards
+            let reward_distributors = Mapping::default(); // Mapping to store the distributor of each reward token
+            let unclaimable_reward_holders = Mapping::default(); // Mapping to store the LP holders that can't claim rewards
 
             // Return a new instance of TradingPairAzero with initialized variables
-            Self {
+            Ok(Self {
                 transasction_number,
                 psp22_token,
                 fee,
@@ -255,7 +868,35 @@ pub mod trading_pair_azero {
                 lp_lock_timestamp,
                 psp22,
                 deployer,
-            }
+                pending_a0_claims,
+                total_pending_a0_claims,
+                reentrancy_lock,
+                reserve_a0,
+                reserve_psp22,
+                swap_stats,
+                daily_swap_stats,
+                account_swap_volume,
+                lp_locks,
+                psp22_boost_in_a_day,
+                psp22_commitment_boost_in_a_day,
+                admin,
+                protocol_fee_to,
+                protocol_fee_share,
+                root_k_last,
+                pending_vault_psp22,
+                pending_vault_a0,
+                psp22_price_cumulative,
+                a0_price_cumulative,
+                price_cumulatives_timestamp,
+                discount_source,
+                discount_tiers,
+                reward_tokens,
+                reward_streams,
+                reward_per_share_paid,
+                pending_stream_rewards,
+                reward_distributors,
+                unclaimable_reward_holders,
+            })
         }
 
         /// function to provide liquidity to a PSP22/A0 trading pair contract.
@@ -267,6 +908,9 @@ pub mod trading_pair_azero {
             expected_lp_tokens: Balance,   // Expected amount of LP tokens to be received
             slippage: Balance,             // Slippage tolerance percentage
         ) -> Result<(), TradingPairErrors> {
+            // making sure that the pair is not re-entered while this message runs
+            self.lock_reentrancy()?;
+
             // Function returns a Result with an error type TradingPairErrors or a unit type ()
 
             let caller = self.env().caller(); // Get the address of the caller
@@ -294,45 +938,55 @@ pub mod trading_pair_azero {
                 return Err(TradingPairErrors::NotEnoughAllowance)
             }
 
-            let mut shares: U256 = U256::from(0); // Initialize shares variable to 0
+            let a0_reserve_before: Balance;
 
-            if self.total_supply == 0 {
-                shares =
-                    U256::from(self.env().transferred_value()) * U256::from(psp22_deposit_amount);
+            // calculating the A0 contract reserve before the transaction
+            match self
+                .get_a0_balance()
+                .checked_sub(self.env().transferred_value())
+            {
+                Some(result) => {
+                    a0_reserve_before = result;
+                }
+                None => return Err(TradingPairErrors::Overflow),
+            };
 
-                match shares.checked_div(U256::from(10u128.pow(12))) {
-                    Some(result) => {
-                        shares = result;
-                    }
-                    None => return Err(TradingPairErrors::Overflow),
-                };
-            }
+            // minting the protocol fee on the LP fee growth since the last liquidity change
+            self.mint_protocol_fee()?;
 
-            if self.total_supply > 0 {
-                let reserve_before_transaction =
-                    self.get_a0_balance() - a0_deposit_amount;
+            // pool reserves and LP supply before the deposit, for the invariant check
+            let reserves_before = (a0_reserve_before, self.get_psp22_balance(), self.total_supply);
 
-                let coin_product = (a0_deposit_amount * self.total_supply)
-                    / reserve_before_transaction;
+            // amount of LP shares to give to the caller for the deposited A0 and PSP22
+            let shares: Balance = self.get_shares_for_deposit(
+                self.env().transferred_value(),
+                psp22_deposit_amount,
+                a0_reserve_before,
+                self.get_psp22_balance(),
+                self.total_supply,
+            )?;
 
-                let psp22_product =
-                    (psp22_deposit_amount * self.total_supply) / self.get_psp22_balance();
+            if self.total_supply > 0 {
+                let reserve_before_transaction: Balance;
 
-                shares = U256::from(self._min(coin_product, psp22_product));
+                match self.get_a0_balance().checked_sub(a0_deposit_amount) {
+                    Some(result) => {
+                        reserve_before_transaction = result;
+                    }
+                    None => return Err(TradingPairErrors::Underflow),
+                };
 
                 let psp22_amount_needed_to_deposit =
-                    self.get_psp22_amount_for_lp(a0_deposit_amount, reserve_before_transaction);
+                    self.get_psp22_amount_for_lp(a0_deposit_amount, reserve_before_transaction)?;
 
                 let a0_amount_needed_to_deposit =
-                    self.get_a0_amount_for_lp(psp22_deposit_amount, reserve_before_transaction);
+                    self.get_a0_amount_for_lp(psp22_deposit_amount, reserve_before_transaction)?;
 
-                let psp22_deposit_percentage_diff = self
-                    .check_difference(psp22_deposit_amount, psp22_amount_needed_to_deposit)
-                    .unwrap();
+                let psp22_deposit_percentage_diff =
+                    self.check_difference(psp22_deposit_amount, psp22_amount_needed_to_deposit)?;
 
-                let a0_deposit_percentage_diff = self
-                    .check_difference(a0_amount_needed_to_deposit, a0_deposit_amount)
-                    .unwrap();
+                let a0_deposit_percentage_diff =
+                    self.check_difference(a0_amount_needed_to_deposit, a0_deposit_amount)?;
 
                 if psp22_deposit_percentage_diff > slippage && a0_deposit_percentage_diff > slippage
                 {
@@ -340,17 +994,16 @@ pub mod trading_pair_azero {
                 }
             }
 
-            if shares <= U256::from(0) {
+            if shares <= 0 {
                 // If shares is less than or equal to 0, return an error difference
                 return Err(TradingPairErrors::ZeroSharesGiven)
             }
 
-            let percentage_diff = self
-                .check_difference(expected_lp_tokens, shares.as_u128())
-                .unwrap(); // Calculate the percentage difference between expected LP tokens and calculated shares
+            // Calculate the percentage difference between expected LP tokens and calculated shares
+            let percentage_diff = self.check_difference(expected_lp_tokens, shares)?;
 
             // Validate slippage tolerance
-            if percentage_diff > slippage.try_into().unwrap() {
+            if percentage_diff > slippage {
                 // If percentage difference is greater than slippage tolerance, return an error
                 return Err(TradingPairErrors::SlippageTolerance)
             }
@@ -360,7 +1013,7 @@ pub mod trading_pair_azero {
             let new_caller_shares: Balance; // Initialize new caller shares variable
 
             // Calculate the new caller shares by adding current shares and calculated shares
-            match current_shares.checked_add(shares.as_u128()) {
+            match current_shares.checked_add(shares) {
                 Some(result) => {
                     new_caller_shares = result;
                 }
@@ -369,8 +1022,34 @@ pub mod trading_pair_azero {
                 }
             };
 
+            // settling the streamed rewards earned with the current shares
+            self.update_stream_rewards_of(caller)?;
+
+            // Increase the LP balance of `caller` (mint) by inserting `new_caller_shares` into `self.balances`
+            self.balances.insert(caller, &(new_caller_shares));
+
+            let new_total_supply: Balance;
+
+            // Add `shares` to the total supply of LP tokens (mint)
+            match self.total_supply.checked_add(shares) {
+                Some(result) => {
+                    new_total_supply = result;
+                }
+                None => return Err(TradingPairErrors::Overflow),
+            };
+
+            // self._mint_to(caller, shares);
+            psp22::Internal::_mint_to(self, caller, shares);
+
+            self.total_supply = new_total_supply;
+
+            // the deployer's liquidity is locked until the LP lock timestamp
+            if caller == self.deployer && self.get_current_timestamp() < self.lp_lock_timestamp {
+                self.add_to_lp_lock(caller, shares, self.lp_lock_timestamp, false)?;
+            }
+
             // Perform a cross-contract call to the PSP22 token contract to transfer `psp22_deposit_amount` tokens from `caller` to the current contract's account ID
-            if PSP22Ref::transfer_from_builder(
+            let transfer_from_result = PSP22Ref::transfer_from_builder(
                 &self.psp22_token,
                 caller,
                 Self::env().account_id(),
@@ -378,11 +1057,9 @@ pub mod trading_pair_azero {
                 vec![],
             )
             .call_flags(CallFlags::default().set_allow_reentry(true))
-            .try_invoke()
-            .is_err()
-            {
-                return Err(TradingPairErrors::PSP22TransferFromFailed)
-            }
+            .try_invoke();
+
+            check_transfer_from_result(transfer_from_result)?;
 
             // Get the balance of `caller` after the PSP22 token transfer
             let caller_balance_after_transfer: Balance =
@@ -394,30 +1071,36 @@ pub mod trading_pair_azero {
                 return Err(TradingPairErrors::CallerInsufficientPSP22Balance)
             }
 
-            // Increase the LP balance of `caller` (mint) by inserting `new_caller_shares` into `self.balances`
-            self.balances.insert(caller, &(new_caller_shares));
-
-            // self._mint_to(caller, shares.as_u128());
-            psp22::Internal::_mint_to(self, caller, shares.as_u128());
-
-            // Add `shares` to the total supply of LP tokens (mint)
-            self.total_supply += shares.as_u128();
+            // making sure that the deposit did not decrease the reserves backing each LP share
+            if !are_reserves_per_share_kept(
+                reserves_before,
+                (self.get_a0_balance(), self.get_psp22_balance(), self.total_supply),
+            ) {
+                return Err(TradingPairErrors::InvariantViolated)
+            }
 
             // Update the incentive program for `caller`, and if it fails, return an error
             if self.update_incentive_program(caller).is_err() {
                 return Err(TradingPairErrors::UpdateIncentiveProgramError)
             }
 
+            // storing the new reserves and emitting a Sync event
+            self.sync_reserves();
+
+            self.update_root_k_last();
+
             // Emit an event indicating the liquidity pool provision details
             Self::env().emit_event(LiquidityPoolProvision {
                 provider: caller,
                 a0_deposited_amount: self.env().transferred_value(),
                 psp22_deposited_amount: psp22_deposit_amount,
-                shares_given: shares.as_u128(),
+                shares_given: shares,
             });
 
             // Return a successful result
 
+            self.unlock_reentrancy();
+
             Ok(())
         }
 
@@ -427,13 +1110,12 @@ pub mod trading_pair_azero {
             &mut self,
             shares: Balance, // number of shares the caller wants to withdraw
         ) -> Result<(), TradingPairErrors> {
+            // making sure that the pair is not re-entered while this message runs
+            self.lock_reentrancy()?;
+
             // caller address
             let caller = self.env().caller();
 
-            if self.get_current_timestamp() < self.lp_lock_timestamp && caller == self.deployer {
-                return Err(TradingPairErrors::LpStillLocked)
-            }
-
             // throw error is the caller tries to withdraw 0 LP shares
             if shares <= 0 {
                 return Err(TradingPairErrors::ZeroSharesGiven)
@@ -447,17 +1129,28 @@ pub mod trading_pair_azero {
                 return Err(TradingPairErrors::CallerInsufficientLPBalance)
             }
 
+            // validating that the given shares are not locked
+            if self.get_unlocked_shares_of(caller) < shares {
+                return Err(TradingPairErrors::LpStillLocked)
+            }
+
+            // minting the protocol fee on the LP fee growth since the last liquidity change
+            self.mint_protocol_fee()?;
+
+            // pool reserves and LP supply before the withdrawal, for the invariant check
+            let reserves_before = self.get_reserves_and_supply();
+
             // amount of PSP22 tokens to give to the caller
-            let psp22_amount_to_give = self.get_psp22_withdraw_tokens_amount(shares).unwrap();
+            let psp22_amount_to_give = self.get_psp22_withdraw_tokens_amount(shares)?;
 
             // amount of A0 to give to the caller
-            let a0_amount_to_give = self.get_a0_withdraw_tokens_amount(shares).unwrap();
+            let a0_amount_to_give = self.get_a0_withdraw_tokens_amount(shares)?;
 
             // amount of PSP22 tokens the caller earned from the LP fee
-            let psp22_fee_amount_to_give = self.get_psp22_lp_fee_tokens(shares).unwrap();
+            let psp22_fee_amount_to_give = self.get_psp22_lp_fee_tokens(shares)?;
 
             // amount of AZERO tokens the caller earned from the LP fee
-            let a0_fee_amount_to_give = self.get_a0_lp_fee_tokens(shares).unwrap();
+            let a0_fee_amount_to_give = self.get_a0_lp_fee_tokens(shares)?;
 
             // Initialize new_caller_lp_shares variable to 0
             let new_caller_lp_shares: Balance;
@@ -467,213 +1160,233 @@ pub mod trading_pair_azero {
                 Some(result) => {
                     new_caller_lp_shares = result;
                 }
-                None => return Err(TradingPairErrors::Overflow),
+                None => return Err(TradingPairErrors::Underflow),
             };
 
-            // cross contract call to PSP22 contract to transfer PSP2 tokens to the caller
-            if PSP22Ref::transfer(&self.psp22_token, caller, psp22_amount_to_give, vec![]).is_err()
-            {
-                return Err(TradingPairErrors::PSP22TransferFailed)
-            }
-
-            // function to transfer A0 to the caller
-            if self.env().transfer(caller, a0_amount_to_give).is_err() {
-                return Err(TradingPairErrors::A0TransferFailed)
-            }
-
-            // reducing caller total LP share tokens balance
-            self.balances.insert(caller, &(new_caller_lp_shares));
-            // self._burn_from(caller, shares);
-            psp22::Internal::_burn_from(self, caller, shares);
-
-            // reducing overall LP token supply
-            self.total_supply -= shares;
-
-            if self.total_supply == 0 {
-                // cross contract call to PSP22 contract to transfer PSP2 tokens to the caller
-                if PSP22Ref::transfer(&self.psp22_token, caller, self.get_psp22_balance(), vec![])
-                    .is_err()
-                {
-                    return Err(TradingPairErrors::PSP22TransferFailed)
-                }
+            let new_total_supply: Balance;
 
-                // function to transfer A0 to the caller
-                if self.env().transfer(caller, self.get_a0_balance()).is_err() {
-                    return Err(TradingPairErrors::A0TransferFailed)
+            // calculation to determine the new overall LP token supply
+            match self.total_supply.checked_sub(shares) {
+                Some(result) => {
+                    new_total_supply = result;
                 }
-            }
-
-            // update caller's incentive program claim percentage according to the new LP share tokens
-            if self.remove_lp(new_caller_lp_shares).is_err() {
-                return Err(TradingPairErrors::RemoveLpIncentiveProgramError)
-            }
+                None => return Err(TradingPairErrors::Underflow),
+            };
 
             let (current_overall_psp22_lp_rewards, current_overall_azero_lp_rewards) = self
                 .account_overall_lp_fee_rewards
                 .get(&caller)
                 .unwrap_or((0u128, 0u128));
 
-            self.account_overall_lp_fee_rewards.insert(
-                &caller,
-                &(
-                    current_overall_psp22_lp_rewards + psp22_fee_amount_to_give,
-                    current_overall_azero_lp_rewards + a0_fee_amount_to_give,
-                ),
-            );
+            let new_overall_psp22_lp_rewards: Balance;
 
-            // reducing the given PSP22 tokens from LP fee from the total PSP22 LP vault
-            self.psp22_lp_fee_vault = self.psp22_lp_fee_vault - psp22_fee_amount_to_give;
+            match current_overall_psp22_lp_rewards.checked_add(psp22_fee_amount_to_give) {
+                Some(result) => {
+                    new_overall_psp22_lp_rewards = result;
+                }
+                None => return Err(TradingPairErrors::Overflow),
+            };
 
-            // reducing the given AZERO tokens from LP fee from the total AZERO LP vault
-            self.azero_lp_fee_vault = self.azero_lp_fee_vault - a0_fee_amount_to_give;
+            let new_overall_azero_lp_rewards: Balance;
 
-            // emit LP withdrawal event
-            Self::env().emit_event(LiquidityPoolWithdrawal {
-                caller,
-                shares_given: shares,
-                a0_given_amount: a0_amount_to_give,
-                psp22_given_amount: psp22_amount_to_give,
-                new_shares_balance: new_caller_lp_shares,
-            });
+            match current_overall_azero_lp_rewards.checked_add(a0_fee_amount_to_give) {
+                Some(result) => {
+                    new_overall_azero_lp_rewards = result;
+                }
+                None => return Err(TradingPairErrors::Overflow),
+            };
 
-            // Return a successful result
-            Ok(())
-        }
+            let new_psp22_lp_fee_vault: Balance;
 
-       
-        /// function to get the amount of withdrawable PSP22 and A0 by given number of LP shares without LP fees.
-        #[ink(message)]
-        pub fn get_withdraw_tokens_amount(
-            &mut self,
-            shares_amount: Balance,
-        ) -> Result<(Balance, Balance), TradingPairErrors> {
-            let mut amount_of_a0_to_give: Balance; // Amount of A0 tokens to give to the caller.
+            // reducing the given PSP22 tokens from LP fee from the total PSP22 LP vault
+            match self.psp22_lp_fee_vault.checked_sub(psp22_fee_amount_to_give) {
+                Some(result) => {
+                    new_psp22_lp_fee_vault = result;
+                }
+                None => return Err(TradingPairErrors::Underflow),
+            };
 
-            let actual_a0_balance = self.get_a0_balance(); // Get the actual balance of A0 tokens.
+            let new_azero_lp_fee_vault: Balance;
 
-            // Calculate the amount of A0 tokens to give to the caller.
-            match (shares_amount * actual_a0_balance).checked_div(self.total_supply) {
+            // reducing the given AZERO tokens from LP fee from the total AZERO LP vault
+            match self.azero_lp_fee_vault.checked_sub(a0_fee_amount_to_give) {
                 Some(result) => {
-                    amount_of_a0_to_give = result;
-                }
-                None => {
-                    return Err(TradingPairErrors::Overflow) // Return an error if overflow occurs.
+                    new_azero_lp_fee_vault = result;
                 }
+                None => return Err(TradingPairErrors::Underflow),
             };
 
-            let actual_psp22_balance = self.get_psp22_balance(); // Get the actual balance of PSP22 tokens.
+            // settling the streamed rewards earned with the current shares
+            self.update_stream_rewards_of(caller)?;
+
+            // reducing caller total LP share tokens balance
+            self.balances.insert(caller, &(new_caller_lp_shares));
+            // self._burn_from(caller, shares);
+            psp22::Internal::_burn_from(self, caller, shares);
+
+            // reducing overall LP token supply
+            self.total_supply = new_total_supply;
 
-            let mut amount_of_psp22_to_give: U256; // Amount of PSP22 tokens to give to the caller.
+            self.account_overall_lp_fee_rewards.insert(
+                &caller,
+                &(new_overall_psp22_lp_rewards, new_overall_azero_lp_rewards),
+            );
 
-            // Calculate the amount of PSP22 tokens to give to the caller.
-            match (U256::from(shares_amount) * U256::from(actual_psp22_balance))
-                .checked_div(U256::from((self.total_supply)))
+            self.psp22_lp_fee_vault = new_psp22_lp_fee_vault;
+
+            self.azero_lp_fee_vault = new_azero_lp_fee_vault;
+
+            // cross contract call to PSP22 contract to transfer PSP2 tokens to the caller
+            if let Err(error) =
+                PSP22Ref::transfer(&self.psp22_token, caller, psp22_amount_to_give, vec![])
             {
-                Some(result) => {
-                    amount_of_psp22_to_give = result;
+                return Err(TradingPairErrors::PSP22TransferFailed(error))
+            }
+
+            // function to transfer A0 to the caller, recording a claim if the transfer fails
+            self.transfer_a0_or_record_claim(caller, a0_amount_to_give)?;
+
+            if self.total_supply == 0 {
+                // cross contract call to PSP22 contract to transfer PSP2 tokens to the caller
+                if let Err(error) =
+                    PSP22Ref::transfer(&self.psp22_token, caller, self.get_psp22_balance(), vec![])
+                {
+                    return Err(TradingPairErrors::PSP22TransferFailed(error))
                 }
-                None => {
-                    return Err(TradingPairErrors::Overflow) // Return an error if overflow occurs.
+
+                // function to transfer A0 to the caller, recording a claim if the transfer fails
+                self.transfer_a0_or_record_claim(caller, self.get_a0_balance())?;
+            }
+
+            // making sure that the withdrawal did not decrease the reserves backing each remaining LP share
+            if !are_reserves_per_share_kept(reserves_before, self.get_reserves_and_supply()) {
+                return Err(TradingPairErrors::InvariantViolated)
+            }
+
+            // pool reserves and LP supply before the incentive payout, for the invariant check
+            let reserves_before_incentive = self.get_reserves_and_supply();
+
+            let psp22_incentive_paid: Balance;
+
+            // update caller's incentive program claim percentage according to the new LP share tokens
+            match self.remove_lp(new_caller_lp_shares) {
+                Ok(result) => {
+                    psp22_incentive_paid = result;
                 }
+                Err(_) => return Err(TradingPairErrors::RemoveLpIncentiveProgramError),
             };
 
-            // amount of PSP22 tokens the caller earned from the LP fee
-            let psp22_fee_amount_to_give = self.get_psp22_lp_fee_tokens(shares_amount).unwrap();
+            self.check_incentive_payout(reserves_before_incentive, psp22_incentive_paid)?;
 
-            // amount of AZERO tokens the caller earned from the LP fee
-            let a0_fee_amount_to_give = self.get_a0_lp_fee_tokens(shares_amount).unwrap();
+            // storing the new reserves and emitting a Sync event
+            self.sync_reserves();
+
+            self.update_root_k_last();
 
-            amount_of_psp22_to_give -= U256::from(psp22_fee_amount_to_give);
+            // emit LP withdrawal event
+            Self::env().emit_event(LiquidityPoolWithdrawal {
+                caller,
+                recipient: caller,
+                shares_given: shares,
+                a0_given_amount: a0_amount_to_give,
+                psp22_given_amount: psp22_amount_to_give,
+                new_shares_balance: new_caller_lp_shares,
+            });
 
-            amount_of_a0_to_give -= a0_fee_amount_to_give;
+            // Return a successful result
+            self.unlock_reentrancy();
 
-            Ok((amount_of_a0_to_give, amount_of_psp22_to_give.as_u128())) // Return the calculated amounts of A0 and PSP22 tokens to give to the caller.
+            Ok(())
         }
 
-        /// function to get the amount of withdrawable PSP22 and A0 by given number of LP shares with LP fees.
+        /// function to get the amount of withdrawable A0 and PSP22 (in that order) by given number of LP shares without LP fees.
+        /// deprecated, kept for existing front ends: use `get_position`, whose named fields do not depend on a tuple order.
         #[ink(message)]
-        pub fn get_withdraw_tokens_amount_with_lp(
+        pub fn get_withdraw_tokens_amount(
             &self,
             shares_amount: Balance,
         ) -> Result<(Balance, Balance), TradingPairErrors> {
-            let amount_of_a0_to_give: U256;
+            // Calculate the amount of A0 tokens to give to the caller.
+            let amount_of_a0_with_fees =
+                self.get_share_of_reserve(shares_amount, self.get_a0_balance(), self.total_supply)?;
 
-            let actual_a0_balance = self.get_a0_balance();
+            // Calculate the amount of PSP22 tokens to give to the caller.
+            let amount_of_psp22_with_fees = self.get_share_of_reserve(
+                shares_amount,
+                self.get_psp22_balance(),
+                self.total_supply,
+            )?;
 
-            // calculating the amount of A0 to give to the caller.
-            match (U256::from(shares_amount) * U256::from(actual_a0_balance)).checked_div(U256::from(self.total_supply)) {
+            // amount of PSP22 tokens the caller earned from the LP fee
+            let psp22_fee_amount_to_give = self.get_psp22_lp_fee_tokens(shares_amount)?;
+
+            // amount of AZERO tokens the caller earned from the LP fee
+            let a0_fee_amount_to_give = self.get_a0_lp_fee_tokens(shares_amount)?;
+
+            let amount_of_a0_to_give: Balance; // Amount of A0 tokens to give to the caller.
+
+            match amount_of_a0_with_fees.checked_sub(a0_fee_amount_to_give) {
                 Some(result) => {
                     amount_of_a0_to_give = result;
                 }
-                None => return Err(TradingPairErrors::Overflow),
+                None => return Err(TradingPairErrors::Underflow),
             };
 
-            let actual_psp22_balance = self.get_psp22_balance();
+            let amount_of_psp22_to_give: Balance; // Amount of PSP22 tokens to give to the caller.
 
-            let amount_of_psp22_to_give: U256;
-
-            // calculating the amount of PSP22 to give to the caller.
-            match (U256::from(shares_amount) * U256::from(actual_psp22_balance)).checked_div(U256::from(self.total_supply)) {
+            match amount_of_psp22_with_fees.checked_sub(psp22_fee_amount_to_give) {
                 Some(result) => {
                     amount_of_psp22_to_give = result;
                 }
-                None => return Err(TradingPairErrors::Overflow),
+                None => return Err(TradingPairErrors::Underflow),
             };
 
-            Ok((amount_of_a0_to_give.as_u128(), amount_of_psp22_to_give.as_u128()))
+            Ok((amount_of_a0_to_give, amount_of_psp22_to_give)) // Return the calculated amounts of A0 and PSP22 tokens to give to the caller.
         }
 
-        /// function to get the amount of withdrawable pooled PSP22 tokens by given number of LP shares without LP fees.
+        /// function to get the amount of withdrawable PSP22 and A0 by given number of LP shares with LP fees.
         #[ink(message)]
-        pub fn get_psp22_withdraw_tokens_amount(
+        pub fn get_withdraw_tokens_amount_with_lp(
             &self,
             shares_amount: Balance,
-        ) -> Result<Balance, TradingPairErrors> {
-            let amount_of_psp22_to_give: U256;
-
-            let actual_psp22_balance = self.get_psp22_balance();
+        ) -> Result<(Balance, Balance), TradingPairErrors> {
+            // calculating the amount of A0 to give to the caller.
+            let amount_of_a0_to_give =
+                self.get_share_of_reserve(shares_amount, self.get_a0_balance(), self.total_supply)?;
 
             // calculating the amount of PSP22 to give to the caller.
-            match (U256::from(shares_amount) * U256::from(actual_psp22_balance))
-                .checked_div(U256::from(self.total_supply))
-            {
-                Some(result) => {
-                    amount_of_psp22_to_give = result;
-                }
-                None => return Err(TradingPairErrors::Overflow),
-            };
+            let amount_of_psp22_to_give = self.get_share_of_reserve(
+                shares_amount,
+                self.get_psp22_balance(),
+                self.total_supply,
+            )?;
 
-            let amount_of_psp22_to_give_u128 = amount_of_psp22_to_give.as_u128();
+            Ok((amount_of_a0_to_give, amount_of_psp22_to_give))
+        }
 
-            Ok(amount_of_psp22_to_give_u128)
+        /// function to get the amount of withdrawable pooled PSP22 tokens by given number of LP shares without LP fees.
+        #[ink(message)]
+        pub fn get_psp22_withdraw_tokens_amount(
+            &self,
+            shares_amount: Balance,
+        ) -> Result<Balance, TradingPairErrors> {
+            // calculating the amount of PSP22 to give to the caller.
+            self.get_share_of_reserve(shares_amount, self.get_psp22_balance(), self.total_supply)
         }
 
         /// function to get the amount of PSP22 LP fee tokens by number of shares
         #[ink(message)]
         pub fn get_psp22_lp_fee_tokens(
-            &mut self,
+            &self,
             shares_amount: Balance,
         ) -> Result<Balance, TradingPairErrors> {
-            let amount_of_psp22_fees_to_give: U256;
-
-            // calculating the amount of PSP22 to give to the caller.
-            match (U256::from(shares_amount) * U256::from(self.psp22_lp_fee_vault))
-                .checked_div(U256::from(self.total_supply))
-            {
-                Some(result) => {
-                    amount_of_psp22_fees_to_give = result;
-                }
-                None => return Err(TradingPairErrors::Overflow),
-            };
-
-            let amount_of_psp22_to_give_u128 = amount_of_psp22_fees_to_give.as_u128();
-
-            Ok(amount_of_psp22_to_give_u128)
+            // calculating the amount of LP fee PSP22 to give to the caller.
+            self.get_share_of_reserve(shares_amount, self.psp22_lp_fee_vault, self.total_supply)
         }
 
         /// function to get the percentage difference between the PSP22 pooled tokens without LP fee and with LP fees
         #[ink(message)]
-        pub fn get_psp22_difference_by_percentage(&mut self) -> Result<Balance, TradingPairErrors> {
+        pub fn get_psp22_difference_by_percentage(&self) -> Result<Balance, TradingPairErrors> {
             // caller address
             let caller = self.env().caller();
 
@@ -685,7 +1398,14 @@ pub mod trading_pair_azero {
             // amount of PSP22 to give to the caller
             let psp22_amount_without_fees = self.get_psp22_withdraw_tokens_amount(caller_shares)?;
 
-            let psp22_amount_with_fees = psp22_amount_without_fees + amount_of_psp22_fees;
+            let psp22_amount_with_fees: Balance;
+
+            match psp22_amount_without_fees.checked_add(amount_of_psp22_fees) {
+                Some(result) => {
+                    psp22_amount_with_fees = result;
+                }
+                None => return Err(TradingPairErrors::Overflow),
+            };
 
             let percentage_diff: Balance =
                 self.check_difference(psp22_amount_without_fees, psp22_amount_with_fees)?;
@@ -699,25 +1419,8 @@ pub mod trading_pair_azero {
             &self,
             shares_amount: Balance,
         ) -> Result<Balance, TradingPairErrors> {
-            let amount_of_a0_to_give: U256;
-
-            let actual_a0_balance = self.get_a0_balance();
-
-            
-
             // calculating the amount of A0 to give to the caller.
-            match (U256::from(shares_amount) * U256::from(actual_a0_balance))
-                .checked_div(U256::from(self.total_supply))
-            {
-                Some(result) => {
-                    amount_of_a0_to_give = result;
-                }
-                None => return Err(TradingPairErrors::Overflow),
-            };
-
-            let amount_of_a0_to_give_u128 = amount_of_a0_to_give.as_u128();
-
-            Ok(amount_of_a0_to_give_u128)
+            self.get_share_of_reserve(shares_amount, self.get_a0_balance(), self.total_supply)
         }
 
         /// function to get the amount of A0 LP fee tokens by number of shares
@@ -726,43 +1429,41 @@ pub mod trading_pair_azero {
             &self,
             shares_amount: Balance,
         ) -> Result<Balance, TradingPairErrors> {
-            let amount_of_a0_fees_to_give: Balance;
-
             // calculating the amount of LP fee A0 to give to the caller.
-            match (shares_amount * self.azero_lp_fee_vault).checked_div(self.total_supply) {
-                Some(result) => {
-                    amount_of_a0_fees_to_give = result;
-                }
-                None => return Err(TradingPairErrors::Overflow),
-            };
-
-            Ok(amount_of_a0_fees_to_give)
+            self.get_share_of_reserve(shares_amount, self.azero_lp_fee_vault, self.total_supply)
         }
 
         /// function to get the percentage difference between the AZERO pooled coins without LP fee and with LP fees
         #[ink(message)]
-        pub fn get_a0_difference_by_percentage(&mut self) -> Result<Balance, TradingPairErrors> {
+        pub fn get_a0_difference_by_percentage(&self) -> Result<Balance, TradingPairErrors> {
             // caller address
             let caller = self.env().caller();
 
             // caller total LP shares
             let caller_shares: Balance = self.balances.get(&caller).unwrap_or(0);
 
-            let amount_of_a0_fees: Balance = self.get_a0_lp_fee_tokens(caller_shares).unwrap();
+            let amount_of_a0_fees: Balance = self.get_a0_lp_fee_tokens(caller_shares)?;
 
             // amount of PSP22 to give to the caller
-            let a0_amount_without_fees = self.get_a0_withdraw_tokens_amount(caller_shares).unwrap();
+            let a0_amount_without_fees = self.get_a0_withdraw_tokens_amount(caller_shares)?;
 
-            let a0_amount_with_fees = a0_amount_without_fees + amount_of_a0_fees;
+            let a0_amount_with_fees: Balance;
 
-            let percentage_diff: Balance = self
-                .check_difference(a0_amount_without_fees, a0_amount_with_fees)
-                .unwrap();
+            match a0_amount_without_fees.checked_add(amount_of_a0_fees) {
+                Some(result) => {
+                    a0_amount_with_fees = result;
+                }
+                None => return Err(TradingPairErrors::Overflow),
+            };
+
+            let percentage_diff: Balance =
+                self.check_difference(a0_amount_without_fees, a0_amount_with_fees)?;
 
             Ok(percentage_diff)
         }
 
-        /// function to get the callers pooled PSP22 and A0.
+        /// function to get the callers pooled PSP22 and A0 (in that order, unlike `get_withdraw_tokens_amount`).
+        /// deprecated, kept for existing front ends: use `get_position`, whose named fields do not depend on a tuple order.
         #[ink(message)]
         pub fn get_account_locked_tokens(
             &self,
@@ -773,40 +1474,20 @@ pub mod trading_pair_azero {
             // get caller LP tokens
             let caller_shares: Balance = self.balances.get(&caller).unwrap_or(0);
 
-            let mut amount_of_a0_to_give: Balance = 0;
-
-            let mut amount_of_psp22_to_give: Balance = 0;
-
             if caller_shares <= 0 {
-                return Ok((amount_of_psp22_to_give, amount_of_a0_to_give))
+                return Ok((0, 0))
             }
 
-            let actual_a0_balance = self.get_a0_balance();
-
             // calculating the amount of A0 to give to the caller.
-            match (caller_shares * actual_a0_balance).checked_div(self.total_supply) {
-                Some(result) => {
-                    amount_of_a0_to_give = result;
-                }
-                None => return Err(TradingPairErrors::Overflow),
-            };
-
-            let mut actual_psp22_balance = self.get_psp22_balance();
+            let amount_of_a0_to_give =
+                self.get_share_of_reserve(caller_shares, self.get_a0_balance(), self.total_supply)?;
 
-            match actual_psp22_balance.checked_div(10u128.pow(12)) {
-                Some(result) => {
-                    actual_psp22_balance = result;
-                }
-                None => return Err(TradingPairErrors::Overflow),
-            };
+            // pooled PSP22 in whole tokens
+            let actual_psp22_balance = self.get_psp22_balance() / 10u128.pow(12);
 
             // calculating the amount of PSP22 to give to the caller.
-            match (caller_shares * actual_psp22_balance).checked_div(self.total_supply) {
-                Some(result) => {
-                    amount_of_psp22_to_give = result;
-                }
-                None => return Err(TradingPairErrors::Overflow),
-            };
+            let amount_of_psp22_to_give =
+                self.get_share_of_reserve(caller_shares, actual_psp22_balance, self.total_supply)?;
 
             let actual_amount_of_psp22_to_give: Balance;
 
@@ -827,45 +1508,71 @@ pub mod trading_pair_azero {
             a0_deposit_amount: Balance,
             psp22_deposit_amount: Balance,
         ) -> Result<Balance, TradingPairErrors> {
+            self.get_shares_for_deposit(
+                a0_deposit_amount,
+                psp22_deposit_amount,
+                self.get_a0_balance(),
+                self.get_psp22_balance(),
+                self.total_supply,
+            )
+        }
 
-            let mut shares: U256 = U256::from(0) ;
-
+        /// function to get the amount of LP shares to give for a deposit against the given reserves.
+        fn get_shares_for_deposit(
+            &self,
+            a0_deposit_amount: Balance,
+            psp22_deposit_amount: Balance,
+            a0_reserve: Balance,
+            psp22_reserve: Balance,
+            total_supply: Balance,
+        ) -> Result<Balance, TradingPairErrors> {
             // if its the trading pair first deposit
-            if self.total_supply == 0 {
-
-                shares = U256::from(a0_deposit_amount) * U256::from(psp22_deposit_amount);
-
-                match shares.checked_div(U256::from(10u128.pow(12))) {
-                    Some(result) => {
-                        shares = result;
-                    }
+            if total_supply == 0 {
+                match (U256::from(a0_deposit_amount) * U256::from(psp22_deposit_amount))
+                    .checked_div(U256::from(10u128.pow(12)))
+                    .and_then(|result| Balance::try_from(result).ok())
+                {
+                    Some(result) => return Ok(result),
                     None => return Err(TradingPairErrors::Overflow),
                 };
-
             }
 
-            // if its not the first LP deposit
-            if self.total_supply > 0 {
+            // LP shares are given by the smaller of the two deposits relative to its reserve
+            let coin_product =
+                self.get_share_of_reserve(a0_deposit_amount, total_supply, a0_reserve)?;
 
-                let coin_product = (a0_deposit_amount * self.total_supply)
-                    / self.get_a0_balance();
+            let psp22_product =
+                self.get_share_of_reserve(psp22_deposit_amount, total_supply, psp22_reserve)?;
 
-                let psp22_product =
-                    (psp22_deposit_amount * self.total_supply) / self.get_psp22_balance();
+            Ok(self._min(coin_product, psp22_product))
+        }
 
-                shares = U256::from(self._min(coin_product, psp22_product));
+        /// function to calculate `shares * reserve / total_supply`, the part of a reserve owned by LP shares.
+        fn get_share_of_reserve(
+            &self,
+            shares: Balance,
+            reserve: Balance,
+            total_supply: Balance,
+        ) -> Result<Balance, TradingPairErrors> {
+            let share_of_reserve: U256;
 
-            }
+            match (U256::from(shares) * U256::from(reserve)).checked_div(U256::from(total_supply)) {
+                Some(result) => {
+                    share_of_reserve = result;
+                }
+                None => return Err(TradingPairErrors::DivisionByZero),
+            };
 
-            Ok(shares.as_u128())
+            match Balance::try_from(share_of_reserve) {
+                Ok(result) => Ok(result),
+                Err(_) => Err(TradingPairErrors::Overflow),
+            }
         }
 
         /// function to get the amount of A0 the caller will get for 1 PSP22 token.
         #[ink(message)]
         pub fn get_price_for_one_psp22(&self) -> Result<Balance, TradingPairErrors> {
-            let amount_out = self
-                .get_est_price_psp22_to_a0(1u128 * (10u128.pow(12)))
-                .unwrap();
+            let amount_out = self.get_est_price_psp22_to_a0(1u128 * (10u128.pow(12)))?;
 
             Ok(amount_out)
         }
@@ -876,86 +1583,15 @@ pub mod trading_pair_azero {
             &self,
             psp22_amount_in: Balance,
         ) -> Result<Balance, TradingPairErrors> {
-            let caller = self.env().caller();
-
-            // fetching caller current PSP22 balance
-            let caller_current_balance: Balance = PSP22Ref::balance_of(&self.panx_contract, caller);
-
-            let mut psp22_amount_in_with_lp_fees: U256;
-            psp22_amount_in_with_lp_fees = U256::from(0);
-
-            // reducting the LP fee from the PSP22 amount in
-            match U256::from(psp22_amount_in)
-                .checked_mul(U256::from(100u128 * 10u128.pow(12)) - (self.fee))
-            {
-                Some(result) => {
-                    psp22_amount_in_with_lp_fees = result;
-                }
-                None => return Err(TradingPairErrors::Overflow),
-            };
-
-            const TOKENS_TO_VALIDATE: Balance = 3500u128 * 10u128.pow(12);
-
-            // validating if caller has more than 3500 PANX to verify if the caller is eligible for the incentive program
-            if caller_current_balance >= TOKENS_TO_VALIDATE {
-                if self.fee <= 1400000000000u128 {
-                    // reducting HALF of the LP fee from PSP22 amount in, if the caller has more than 3500 PANX and the LP fee is less than 1.4%
-                    match U256::from(psp22_amount_in)
-                        .checked_mul(U256::from(100u128 * 10u128.pow(12)) - (self.fee / 2u128))
-                    {
-                        Some(result) => {
-                            psp22_amount_in_with_lp_fees = result;
-                        }
-                        None => return Err(TradingPairErrors::Overflow),
-                    };
-                }
-
-                if self.fee > 1400000000000u128 {
-                    // reducting (LP fee - 1) of the LP fee from PSP22 amount in, if the caller has more than 3500 PANX and the LP fee is more than 1.4%
-                    match U256::from(psp22_amount_in).checked_mul(
-                        U256::from(100u128 * 10u128.pow(12))
-                            - (self.fee - (1u128 * 10u128.pow(12))),
-                    ) {
-                        Some(result) => {
-                            psp22_amount_in_with_lp_fees = result;
-                        }
-                        None => return Err(TradingPairErrors::Overflow),
-                    };
-                }
-            }
-
-            psp22_amount_in_with_lp_fees = psp22_amount_in_with_lp_fees / 10u128.pow(12);
-
-            let mut numerator: U256 = U256::from(0);
-            let mut denominator: U256 = U256::from(0);
-            let a0_amount_out: Balance;
-
-            match U256::from(psp22_amount_in_with_lp_fees)
-                .checked_mul(U256::from(self.get_a0_balance()))
-            {
-                Some(result) => {
-                    numerator = result;
-                }
-                None => return Err(TradingPairErrors::Overflow),
-            };
-
-            match (U256::from(self.get_psp22_balance()) * U256::from(100))
-                .checked_add(U256::from(psp22_amount_in_with_lp_fees))
-            {
-                Some(result) => {
-                    denominator = result;
-                }
-                None => return Err(TradingPairErrors::Overflow),
-            };
-
-            match numerator.checked_div(denominator) {
-                Some(result) => {
-                    a0_amount_out = result.as_u128();
-                }
-                None => return Err(TradingPairErrors::Overflow),
-            };
-
-            Ok(a0_amount_out)
+            // amount out after all fees of the caller's fee tier
+            let quote = self.get_swap_quote(
+                psp22_amount_in,
+                self.get_psp22_balance(),
+                self.get_a0_balance(),
+                self.get_fee_tier_of(Some(self.env().caller())),
+            )?;
+
+            Ok(quote.amount_out)
         }
 
         /// function to get the amount of PSP22 the caller will get for given A0 amount (swap use)
@@ -964,8 +1600,6 @@ pub mod trading_pair_azero {
             &self,
             a0_amout_in: Balance,
         ) -> Result<Balance, TradingPairErrors> {
-            let caller = self.env().caller();
-
             let a0_reserve_before: Balance;
 
             // calculating the A0 contract reserve before the transaction
@@ -976,155 +1610,265 @@ pub mod trading_pair_azero {
                 None => return Err(TradingPairErrors::Overflow),
             };
 
-            let caller_current_balance: Balance = PSP22Ref::balance_of(&self.panx_contract, caller);
-
-            let mut a0_amount_in_with_lp_fees: U256 = U256::from(0);
-
-            // reducting the LP fee from the A0 amount in
-            match U256::from(a0_amout_in)
-                .checked_mul(U256::from(100u128 * 10u128.pow(12)) - self.fee)
-            {
-                Some(result) => {
-                    a0_amount_in_with_lp_fees = result;
-                }
-                None => return Err(TradingPairErrors::Overflow),
-            };
+            // amount out after all fees of the caller's fee tier
+            let quote = self.get_swap_quote(
+                a0_amout_in,
+                a0_reserve_before,
+                self.get_psp22_balance(),
+                self.get_fee_tier_of(Some(self.env().caller())),
+            )?;
 
-            const TOKENS_TO_VALIDATE: Balance = 3500u128 * 10u128.pow(12);
-
-            // validating if the caller has more than 3500 PANX
-            if caller_current_balance >= TOKENS_TO_VALIDATE {
-                if self.fee <= 1400000000000 {
-                    // reducting HALF of the LP fee from the A0 amount in, if the caller has more than 3500 PANX and the LP fee is less than 1.4%
-                    match U256::from(a0_amout_in)
-                        .checked_mul(U256::from(100u128 * 10u128.pow(12)) - (self.fee / 2u128))
-                    {
-                        Some(result) => {
-                            a0_amount_in_with_lp_fees = result;
-                        }
-                        None => return Err(TradingPairErrors::Overflow),
-                    };
-                }
+            Ok(quote.amount_out)
+        }
 
-                if self.fee > 1400000000000 {
-                    // reducting (LP fee - 1) of the LP fee from the A0 amount in, if the caller has more than 3500 PANX and the LP fee is more than 1.4%
-                    match U256::from(a0_amout_in).checked_mul(
-                        U256::from(100u128 * 10u128.pow(12))
-                            - (self.fee - (1u128 * 10u128.pow(12))),
-                    ) {
-                        Some(result) => {
-                            a0_amount_in_with_lp_fees = result;
-                        }
-                        None => return Err(TradingPairErrors::Overflow),
-                    };
-                }
-            }
+        /// function to get the amount of PSP22 the caller will get for given A0 amount (front-end use)
+        #[ink(message)]
+        pub fn get_est_price_a0_to_psp22(
+            &self,
+            a0_amount_in: Balance,
+        ) -> Result<Balance, TradingPairErrors> {
+            // amount out after all fees of the caller's fee tier
+            let quote = self.get_swap_quote(
+                a0_amount_in,
+                self.get_a0_balance(),
+                self.get_psp22_balance(),
+                self.get_fee_tier_of(Some(self.env().caller())),
+            )?;
+
+            Ok(quote.amount_out)
+        }
 
-            a0_amount_in_with_lp_fees = a0_amount_in_with_lp_fees / 10u128.pow(12);
+        /// function to quote a PSP22 to A0 swap for an account, or for no account (standard fee tier).
+        #[ink(message)]
+        pub fn quote_psp22_to_a0(
+            &self,
+            psp22_amount_in: Balance,
+            account: Option<AccountId>,
+        ) -> Result<SwapQuote, TradingPairErrors> {
+            self.quote_psp22_to_a0_with_tier(psp22_amount_in, self.get_fee_tier_of(account))
+        }
 
-            let mut numerator: U256 = U256::from(0);
-            let mut denominator: U256 = U256::from(0);
-            let a0_amount_out: Balance;
+        /// function to quote a PSP22 to A0 swap with an explicit fee tier.
+        #[ink(message)]
+        pub fn quote_psp22_to_a0_with_tier(
+            &self,
+            psp22_amount_in: Balance,
+            fee_tier: FeeTier,
+        ) -> Result<SwapQuote, TradingPairErrors> {
+            self.get_swap_quote(
+                psp22_amount_in,
+                self.get_psp22_balance(),
+                self.get_a0_balance(),
+                fee_tier,
+            )
+        }
 
-            match U256::from(a0_amount_in_with_lp_fees)
-                .checked_mul(U256::from(self.get_psp22_balance()))
-            {
-                Some(result) => {
-                    numerator = result;
-                }
-                None => return Err(TradingPairErrors::Overflow),
+        /// function to quote an A0 to PSP22 swap for an account, or for no account (standard fee tier).
+        #[ink(message)]
+        pub fn quote_a0_to_psp22(
+            &self,
+            a0_amount_in: Balance,
+            account: Option<AccountId>,
+        ) -> Result<SwapQuote, TradingPairErrors> {
+            self.quote_a0_to_psp22_with_tier(a0_amount_in, self.get_fee_tier_of(account))
+        }
+
+        /// function to quote an A0 to PSP22 swap with an explicit fee tier.
+        #[ink(message)]
+        pub fn quote_a0_to_psp22_with_tier(
+            &self,
+            a0_amount_in: Balance,
+            fee_tier: FeeTier,
+        ) -> Result<SwapQuote, TradingPairErrors> {
+            self.get_swap_quote(
+                a0_amount_in,
+                self.get_a0_balance(),
+                self.get_psp22_balance(),
+                fee_tier,
+            )
+        }
+
+        /// function to get the fee tier of an account, the highest discount tier its staked or locked PANX reaches.
+        /// the PANX is reported by the discount source, so PANX borrowed for a single transaction gets no discount.
+        #[ink(message)]
+        pub fn get_fee_tier_of(&self, account: Option<AccountId>) -> FeeTier {
+            let Some(account) = account else {
+                return FeeTier::Standard
             };
 
-            match (U256::from(a0_reserve_before) * U256::from(100))
-                .checked_add(U256::from(a0_amount_in_with_lp_fees))
+            // no tiers, no discount: the PANX of the account is not needed
+            if self.discount_tiers.is_empty() {
+                return FeeTier::Standard
+            }
+
+            let discount_balance = self.get_discount_balance_of(account);
+
+            // tiers are sorted by ascending minimum PANX
+            match self
+                .discount_tiers
+                .iter()
+                .rposition(|tier| tier.min_panx <= discount_balance)
             {
-                Some(result) => {
-                    denominator = result;
-                }
-                None => return Err(TradingPairErrors::Overflow),
-            };
+                Some(index) => FeeTier::Discounted(index as u8),
+                None => FeeTier::Standard,
+            }
+        }
 
-            match numerator.checked_div(denominator) {
-                Some(result) => {
-                    a0_amount_out = result.as_u128();
-                }
-                None => return Err(TradingPairErrors::Overflow),
+        /// function to get the staked or locked PANX of an account reported by the discount source, 0 if none is set or the call fails.
+        /// PANX held in a wallet is never used: it can be borrowed for the length of a single swap.
+        #[ink(message)]
+        pub fn get_discount_balance_of(&self, account: AccountId) -> Balance {
+            let Some(discount_source) = self.discount_source else {
+                return 0
             };
 
-            Ok(a0_amount_out)
+            // cross contract call to the discount source to get the PANX of the account
+            let discount_balance = build_call::<DefaultEnvironment>()
+                .call(discount_source)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                        "get_discount_balance"
+                    )))
+                    .push_arg(account),
+                )
+                .returns::<Balance>()
+                .try_invoke();
+
+            match discount_balance {
+                Ok(Ok(balance)) => balance,
+                _ => 0,
+            }
         }
 
-        /// function to get the amount of PSP22 the caller will get for given A0 amount (front-end use)
+        /// function to get the LP fee charged to a fee tier.
+        /// a discount tier takes `discount_bps` of the LP fee off, whatever the LP fee (the default tier halves it).
         #[ink(message)]
-        pub fn get_est_price_a0_to_psp22(
-            &self,
-            a0_amount_in: Balance,
-        ) -> Result<Balance, TradingPairErrors> {
-            let caller = self.env().caller();
-
-            let caller_current_balance: Balance = PSP22Ref::balance_of(&self.panx_contract, caller);
+        pub fn get_lp_fee_for_tier(&self, fee_tier: FeeTier) -> Result<Balance, TradingPairErrors> {
+            let tier = match fee_tier {
+                FeeTier::Standard => return Ok(self.fee),
+                FeeTier::Discounted(index) => match self.discount_tiers.get(index as usize) {
+                    Some(tier) => tier,
+                    None => return Ok(self.fee),
+                },
+            };
 
-            let mut a0_amount_in_with_lp_fees: U256 = U256::from(0);
+            let discount: Balance;
 
-            // reducting the LP fee from the A0 amount in
-            match U256::from(a0_amount_in)
-                .checked_mul(U256::from(100u128 * 10u128.pow(12)) - self.fee)
+            // part of the LP fee taken off by the tier
+            match (U256::from(self.fee) * U256::from(tier.discount_bps))
+                .checked_div(U256::from(MAX_DISCOUNT_BPS))
+                .and_then(|result| Balance::try_from(result).ok())
             {
                 Some(result) => {
-                    a0_amount_in_with_lp_fees = result;
+                    discount = result;
                 }
                 None => return Err(TradingPairErrors::Overflow),
             };
 
-            const TOKENS_TO_VALIDATE: Balance = 3500u128 * 10u128.pow(12);
-
-            // validating if the caller has more than 3500 PANX
-            if caller_current_balance >= TOKENS_TO_VALIDATE {
-                if self.fee <= 1400000000000 {
-                    // reducting HALF of the LP fee from the A0 amount in, if the caller has more than 3500 PANX and the LP fee is less than 1.4%
-                    match U256::from(a0_amount_in)
-                        .checked_mul(U256::from(100u128 * 10u128.pow(12)) - (self.fee / 2u128))
-                    {
-                        Some(result) => {
-                            a0_amount_in_with_lp_fees = result;
-                        }
-                        None => return Err(TradingPairErrors::Overflow),
-                    };
-                }
+            match self.fee.checked_sub(discount) {
+                Some(result) => Ok(result),
+                None => Err(TradingPairErrors::Overflow),
+            }
+        }
 
-                if self.fee > 1400000000000 {
-                    // reducting (LP fee - 1) of the LP fee from the A0 amount in, if the caller has more than 3500 PANX and the LP fee is more than 1.4%
-                    match U256::from(a0_amount_in).checked_mul(
-                        U256::from(100u128 * 10u128.pow(12))
-                            - (self.fee - (1u128 * 10u128.pow(12))),
-                    ) {
-                        Some(result) => {
-                            a0_amount_in_with_lp_fees = result;
-                        }
-                        None => return Err(TradingPairErrors::Overflow),
-                    };
-                }
+        /// function to get the incentive program boost of an account in basis points, from the discount tier it reaches.
+        #[ink(message)]
+        pub fn get_incentive_boost_bps_of(&self, account: AccountId) -> Balance {
+            match self.get_fee_tier_of(Some(account)) {
+                FeeTier::Discounted(index) => self
+                    .discount_tiers
+                    .get(index as usize)
+                    .map_or(0, |tier| tier.incentive_boost_bps),
+                FeeTier::Standard => 0,
+            }
+        }
+
+        /// function for the admin to set the contract reporting the staked or locked PANX of accounts and the discount tiers.
+        /// the source can be any contract with a `get_discount_balance(account)` message, e.g. panx_staking or ve_panx.
+        /// a discount source of None or empty tiers turn the discount off.
+        #[ink(message)]
+        pub fn set_discount(
+            &mut self,
+            source: Option<AccountId>,
+            tiers: Vec<DiscountTier>,
+        ) -> Result<(), TradingPairErrors> {
+            // making sure that the pair is not re-entered while this message runs
+            self.lock_reentrancy()?;
+
+            if self.env().caller() != self.admin {
+                return Err(TradingPairErrors::CallerIsNotAdmin)
+            }
+
+            if tiers.len() > MAX_DISCOUNT_TIERS
+                || tiers.iter().any(|tier| {
+                    tier.discount_bps > MAX_DISCOUNT_BPS
+                        || tier.incentive_boost_bps > MAX_DISCOUNT_INCENTIVE_BOOST_BPS
+                })
+            {
+                return Err(TradingPairErrors::InvalidDiscountTiers)
+            }
+
+            // a tier needs more PANX than the previous one and can't discount or boost less
+            if tiers.windows(2).any(|pair| {
+                pair[1].min_panx <= pair[0].min_panx
+                    || pair[1].discount_bps < pair[0].discount_bps
+                    || pair[1].incentive_boost_bps < pair[0].incentive_boost_bps
+            }) {
+                return Err(TradingPairErrors::InvalidDiscountTiers)
             }
 
-            a0_amount_in_with_lp_fees = a0_amount_in_with_lp_fees / 10u128.pow(12);
+            self.discount_source = source;
 
-            let mut numerator: U256 = U256::from(0);
-            let mut denominator: U256 = U256::from(0);
+            self.discount_tiers = tiers.clone();
 
-            let a0_amount_out: Balance;
+            Self::env().emit_event(DiscountSet { source, tiers });
 
-            match U256::from(a0_amount_in_with_lp_fees)
-                .checked_mul(U256::from(self.get_psp22_balance()))
+            self.unlock_reentrancy();
+
+            Ok(())
+        }
+
+        /// function to get the contract reporting the staked or locked PANX of accounts (None while off) and the discount tiers
+        #[ink(message)]
+        pub fn get_discount(&self) -> (Option<AccountId>, Vec<DiscountTier>) {
+            (self.discount_source, self.discount_tiers.clone())
+        }
+
+        /// function to get the amount out of a swap against the given reserves after reducting the LP fee from the amount in.
+        fn get_amount_out(
+            &self,
+            amount_in: Balance,
+            reserve_in: Balance,
+            reserve_out: Balance,
+            lp_fee: Balance,
+        ) -> Result<Balance, TradingPairErrors> {
+            let amount_in_with_lp_fees: U256;
+
+            // reducting the LP fee from the amount in
+            match FEE_DENOMINATOR
+                .checked_sub(lp_fee)
+                .and_then(|fee_multiplier| {
+                    U256::from(amount_in).checked_mul(U256::from(fee_multiplier))
+                })
             {
+                Some(result) => {
+                    amount_in_with_lp_fees = result / FEE_DENOMINATOR;
+                }
+                None => return Err(TradingPairErrors::Overflow),
+            };
+
+            let numerator: U256;
+            let denominator: U256;
+            let amount_out: U256;
+
+            match amount_in_with_lp_fees.checked_mul(U256::from(reserve_out)) {
                 Some(result) => {
                     numerator = result;
                 }
                 None => return Err(TradingPairErrors::Overflow),
             };
 
-            match (U256::from(self.get_a0_balance()) * U256::from(100))
-                .checked_add(U256::from(a0_amount_in_with_lp_fees))
-            {
+            match U256::from(reserve_in).checked_add(amount_in_with_lp_fees) {
                 Some(result) => {
                     denominator = result;
                 }
@@ -1133,124 +1877,339 @@ pub mod trading_pair_azero {
 
             match numerator.checked_div(denominator) {
                 Some(result) => {
-                    a0_amount_out = result.as_u128();
+                    amount_out = result;
                 }
-                None => return Err(TradingPairErrors::Overflow),
+                None => return Err(TradingPairErrors::DivisionByZero),
             };
 
-            Ok(a0_amount_out)
+            match Balance::try_from(amount_out) {
+                Ok(result) => Ok(result),
+                Err(_) => Err(TradingPairErrors::Overflow),
+            }
         }
 
-        /// function to get the estimated price impact for given psp22 token amount
-        #[ink(message)]
-        pub fn get_price_impact_psp22_to_a0(
+        /// function to build the full fee breakdown of a swap against the given reserves.
+        /// the swap messages execute exactly these amounts.
+        fn get_swap_quote(
             &self,
-            psp22_amount_in: Balance,
-        ) -> Result<Balance, TradingPairErrors> {
-            // fetching the amount of A0 the caller WOULD get if he would swap
-            let current_amount_out = self.get_est_price_psp22_to_a0(psp22_amount_in).unwrap();
+            amount_in: Balance,
+            reserve_in: Balance,
+            reserve_out: Balance,
+            fee_tier: FeeTier,
+        ) -> Result<SwapQuote, TradingPairErrors> {
+            let traders_fee_in: Balance;
+
+            // calculating the amount in to allocate to the vault account
+            match amount_in.checked_mul(TRADERS_FEE) {
+                Some(result) => {
+                    traders_fee_in = result / FEE_DENOMINATOR;
+                }
+                None => return Err(TradingPairErrors::Overflow),
+            };
 
-            let mut psp22_amount_in_with_lp_fees: U256 = U256::from(0);
+            let pooled_amount_in: Balance;
 
-            // reducting the LP fee from the PSP22 amount in
-            match U256::from(psp22_amount_in)
-                .checked_mul(U256::from(100u128 * 10u128.pow(12)) - (self.fee))
-            {
+            // the amount in that stays in the pool
+            match amount_in.checked_sub(traders_fee_in) {
                 Some(result) => {
-                    psp22_amount_in_with_lp_fees = result;
+                    pooled_amount_in = result;
                 }
-                None => return Err(TradingPairErrors::Overflow),
+                None => return Err(TradingPairErrors::Underflow),
             };
 
-            psp22_amount_in_with_lp_fees = psp22_amount_in_with_lp_fees / 10u128.pow(12);
+            // amount out without LP fee, the LP fee is the part of it that stays in the pool
+            let amount_out_without_lp_fee =
+                self.get_amount_out(pooled_amount_in, reserve_in, reserve_out, 0)?;
 
-            let mut numerator: U256 = U256::from(0);
+            let amount_out_before_traders_fee = self.get_amount_out(
+                pooled_amount_in,
+                reserve_in,
+                reserve_out,
+                self.get_lp_fee_for_tier(fee_tier)?,
+            )?;
 
-            let mut denominator: U256 = U256::from(0);
-            let future_a0_amount_out: Balance;
+            let lp_fee: Balance;
 
-            match U256::from(psp22_amount_in_with_lp_fees)
-                .checked_mul(U256::from(self.get_a0_balance() - current_amount_out))
-            {
+            // calculating the amount out to allocate to the LP vault
+            match amount_out_without_lp_fee.checked_sub(amount_out_before_traders_fee) {
                 Some(result) => {
-                    numerator = result;
+                    lp_fee = result;
+                }
+                None => return Err(TradingPairErrors::Underflow),
+            };
+
+            let traders_fee: Balance;
+
+            // calculating the amount out to allocate to the vault account
+            match amount_out_before_traders_fee.checked_mul(TRADERS_FEE) {
+                Some(result) => {
+                    traders_fee = result / FEE_DENOMINATOR;
                 }
                 None => return Err(TradingPairErrors::Overflow),
             };
 
-            match (U256::from(self.get_psp22_balance() + psp22_amount_in) * U256::from(100))
-                .checked_add(U256::from(psp22_amount_in_with_lp_fees))
+            let amount_out: Balance;
+
+            // calculating the final amount out to give to the trader after reducing traders fee
+            match amount_out_before_traders_fee.checked_sub(traders_fee) {
+                Some(result) => {
+                    amount_out = result;
+                }
+                None => return Err(TradingPairErrors::Underflow),
+            };
+
+            // price impact of the pooled amount in, fees excluded
+            let price_impact_bps = self.get_price_impact_bps(
+                self.get_mid_price(reserve_in, reserve_out)?,
+                self.get_execution_price(
+                    pooled_amount_in,
+                    amount_out_without_lp_fee,
+                    reserve_in,
+                    reserve_out,
+                )?,
+            )?;
+
+            Ok(SwapQuote {
+                amount_in,
+                amount_out,
+                lp_fee,
+                traders_fee,
+                traders_fee_in,
+                fee_tier,
+                price_impact_bps,
+            })
+        }
+
+        /// function to get the price impact in basis points, the difference between the mid-price before a swap and its execution price.
+        fn get_price_impact_bps(
+            &self,
+            mid_price_before: Balance,
+            execution_price: Balance,
+        ) -> Result<Balance, TradingPairErrors> {
+            // an empty pool has no mid-price to move
+            if mid_price_before == 0 {
+                return Ok(0)
+            }
+
+            // for x * y = k the execution price is never above the mid-price before the swap
+            match (U256::from(mid_price_before.saturating_sub(execution_price))
+                * U256::from(10000u128))
+            .checked_div(U256::from(mid_price_before))
+            .and_then(|result| Balance::try_from(result).ok())
             {
+                Some(result) => Ok(result),
+                None => Err(TradingPairErrors::Overflow),
+            }
+        }
+
+        /// function to get the mid-price of the given reserves, 0 for an empty pool.
+        fn get_mid_price(
+            &self,
+            reserve_in: Balance,
+            reserve_out: Balance,
+        ) -> Result<Balance, TradingPairErrors> {
+            if reserve_in == 0 {
+                return Ok(0)
+            }
+
+            self.get_scaled_price(reserve_out, reserve_in)
+        }
+
+        /// function to get the execution price of trading the pooled `amount_in` for `amount_out` before fees.
+        /// a trade of 0 tokens executes at the mid-price.
+        fn get_execution_price(
+            &self,
+            amount_in: Balance,
+            amount_out: Balance,
+            reserve_in: Balance,
+            reserve_out: Balance,
+        ) -> Result<Balance, TradingPairErrors> {
+            if amount_in == 0 {
+                return self.get_mid_price(reserve_in, reserve_out)
+            }
+
+            self.get_scaled_price(amount_out, amount_in)
+        }
+
+        /// function to get the amount of A0 the same PSP22 amount would get right after this swap (front-end use).
+        /// see `get_price_impact_details_psp22_to_a0` for the price impact of the swap.
+        #[ink(message)]
+        pub fn get_price_impact_psp22_to_a0(
+            &self,
+            psp22_amount_in: Balance,
+        ) -> Result<Balance, TradingPairErrors> {
+            // fetching the amount of A0 the caller WOULD get if he would swap
+            let current_amount_out = self.get_est_price_psp22_to_a0(psp22_amount_in)?;
+
+            let a0_reserve_after: Balance;
+
+            match self.get_a0_balance().checked_sub(current_amount_out) {
                 Some(result) => {
-                    denominator = result;
+                    a0_reserve_after = result;
                 }
-                None => return Err(TradingPairErrors::Overflow),
+                None => return Err(TradingPairErrors::Underflow),
             };
 
-            match numerator.checked_div(denominator) {
+            let psp22_reserve_after: Balance;
+
+            match self.get_psp22_balance().checked_add(psp22_amount_in) {
                 Some(result) => {
-                    future_a0_amount_out = result.as_u128();
+                    psp22_reserve_after = result;
                 }
                 None => return Err(TradingPairErrors::Overflow),
             };
 
-            Ok(future_a0_amount_out)
+            self.get_amount_out(
+                psp22_amount_in,
+                psp22_reserve_after,
+                a0_reserve_after,
+                self.fee,
+            )
         }
 
-        /// function to get the estimated price impact for given A0 amount
+        /// function to get the amount of PSP22 the same A0 amount would get right after this swap (front-end use).
+        /// see `get_price_impact_details_a0_to_psp22` for the price impact of the swap.
         #[ink(message)]
         pub fn get_price_impact_a0_to_psp22(
-            &mut self,
+            &self,
             a0_amount_in: Balance,
         ) -> Result<Balance, TradingPairErrors> {
-            let current_amount_out = self.get_est_price_a0_to_psp22(a0_amount_in).unwrap();
+            let current_amount_out = self.get_est_price_a0_to_psp22(a0_amount_in)?;
 
-            let mut a0_amount_in_with_lp_fees: U256 = U256::from(0);
+            let psp22_reserve_after: Balance;
 
-            // reducting the LP fee from the A0 amount in
-            match U256::from(a0_amount_in)
-                .checked_mul(U256::from(100u128 * 10u128.pow(12)) - self.fee)
-            {
+            match self.get_psp22_balance().checked_sub(current_amount_out) {
                 Some(result) => {
-                    a0_amount_in_with_lp_fees = result;
+                    psp22_reserve_after = result;
                 }
-                None => return Err(TradingPairErrors::Overflow),
+                None => return Err(TradingPairErrors::Underflow),
             };
 
-            a0_amount_in_with_lp_fees = a0_amount_in_with_lp_fees / 10u128.pow(12);
+            let a0_reserve_after: Balance;
+
+            match self.get_a0_balance().checked_add(a0_amount_in) {
+                Some(result) => {
+                    a0_reserve_after = result;
+                }
+                None => return Err(TradingPairErrors::Overflow),
+            };
 
-            let mut numerator: U256 = U256::from(0);
+            self.get_amount_out(
+                a0_amount_in,
+                a0_reserve_after,
+                psp22_reserve_after,
+                self.fee,
+            )
+        }
 
-            let mut denominator: U256 = U256::from(0);
+        /// function to get the mid-price before, execution price, mid-price after and price impact of a PSP22 to A0 swap.
+        #[ink(message)]
+        pub fn get_price_impact_details_psp22_to_a0(
+            &self,
+            psp22_amount_in: Balance,
+            fee_tier: FeeTier,
+        ) -> Result<PriceImpact, TradingPairErrors> {
+            self.get_price_impact(
+                psp22_amount_in,
+                self.get_psp22_balance(),
+                self.get_a0_balance(),
+                fee_tier,
+            )
+        }
 
-            let future_psp22_amount_out: Balance;
+        /// function to get the mid-price before, execution price, mid-price after and price impact of an A0 to PSP22 swap.
+        #[ink(message)]
+        pub fn get_price_impact_details_a0_to_psp22(
+            &self,
+            a0_amount_in: Balance,
+            fee_tier: FeeTier,
+        ) -> Result<PriceImpact, TradingPairErrors> {
+            self.get_price_impact(
+                a0_amount_in,
+                self.get_a0_balance(),
+                self.get_psp22_balance(),
+                fee_tier,
+            )
+        }
 
-            match U256::from(a0_amount_in_with_lp_fees)
-                .checked_mul(U256::from(self.get_psp22_balance() - current_amount_out))
+        /// function to calculate the price impact of trading `amount_in` against the given reserves.
+        fn get_price_impact(
+            &self,
+            amount_in: Balance,
+            reserve_in: Balance,
+            reserve_out: Balance,
+            fee_tier: FeeTier,
+        ) -> Result<PriceImpact, TradingPairErrors> {
+            let quote = self.get_swap_quote(amount_in, reserve_in, reserve_out, fee_tier)?;
+
+            // the amount in that stays in the pool, the traders fee in is sent to the vault
+            let pooled_amount_in: Balance = amount_in - quote.traders_fee_in;
+
+            // amount out of the pooled amount in before any fee, the same the quote's price impact is computed from
+            let amount_out_without_fees =
+                self.get_amount_out(pooled_amount_in, reserve_in, reserve_out, 0)?;
+
+            let reserve_in_after: Balance;
+
+            // the input reserve grows by the amount in, minus the traders fee sent to the vault
+            match reserve_in
+                .checked_add(amount_in)
+                .and_then(|result| result.checked_sub(quote.traders_fee_in))
             {
                 Some(result) => {
-                    numerator = result;
+                    reserve_in_after = result;
                 }
                 None => return Err(TradingPairErrors::Overflow),
             };
 
-            match (U256::from(self.get_a0_balance() + a0_amount_in) * U256::from(100))
-                .checked_add(U256::from(a0_amount_in_with_lp_fees))
+            let reserve_out_after: Balance;
+
+            // the output reserve shrinks by what the trader and the vault receive, the LP fee stays in the pool
+            match quote
+                .amount_out
+                .checked_add(quote.traders_fee)
+                .and_then(|result| reserve_out.checked_sub(result))
             {
                 Some(result) => {
-                    denominator = result;
+                    reserve_out_after = result;
                 }
                 None => return Err(TradingPairErrors::Overflow),
             };
 
-            match numerator.checked_div(denominator) {
+            Ok(PriceImpact {
+                mid_price_before: self.get_mid_price(reserve_in, reserve_out)?,
+                execution_price: self.get_execution_price(
+                    pooled_amount_in,
+                    amount_out_without_fees,
+                    reserve_in,
+                    reserve_out,
+                )?,
+                mid_price_after: self.get_mid_price(reserve_in_after, reserve_out_after)?,
+                price_impact_bps: quote.price_impact_bps,
+            })
+        }
+
+        /// function to get the amount of output tokens for one input token (10^12 units).
+        fn get_scaled_price(
+            &self,
+            amount_out: Balance,
+            amount_in: Balance,
+        ) -> Result<Balance, TradingPairErrors> {
+            let price: U256;
+
+            match (U256::from(amount_out) * U256::from(10u128.pow(12)))
+                .checked_div(U256::from(amount_in))
+            {
                 Some(result) => {
-                    future_psp22_amount_out = result.as_u128();
+                    price = result;
                 }
-                None => return Err(TradingPairErrors::Overflow),
+                None => return Err(TradingPairErrors::DivisionByZero),
             };
 
-            Ok(future_psp22_amount_out)
+            match Balance::try_from(price) {
+                Ok(result) => Ok(result),
+                Err(_) => Err(TradingPairErrors::Overflow),
+            }
         }
 
         /// function to swap PSP22 to A0
@@ -1261,12 +2220,13 @@ pub mod trading_pair_azero {
             a0_amount_to_validate: Balance,
             slippage: Balance,
         ) -> Result<(), TradingPairErrors> {
+            // making sure that the pair is not re-entered while this message runs
+            self.lock_reentrancy()?;
+
             let caller = self.env().caller();
 
             let contract_a0_current_balance = self.get_a0_balance();
 
-            const TRADERS_FEE: Balance = 2500000000000 / 10u128.pow(12); // Fee to be charged to traders
-
             // making sure that the contract has more than 0 A0 coins.
             if contract_a0_current_balance <= 0 {
                 return Err(TradingPairErrors::ContractOutOfA0)
@@ -1294,65 +2254,26 @@ pub mod trading_pair_azero {
                 return Err(TradingPairErrors::NotEnoughAllowance)
             }
 
-            // the amount of A0 to give to the caller before traders fee.
-            let a0_amount_out_for_caller_before_traders_fee: Balance = self
-                .get_est_price_psp22_to_a0(psp22_amount_to_transfer)
-                .unwrap();
+            // the amounts of the swap, same as `get_est_price_psp22_to_a0` and `quote_psp22_to_a0`
+            let quote = self.get_swap_quote(
+                psp22_amount_to_transfer,
+                contract_psp22_current_balance,
+                contract_a0_current_balance,
+                self.get_fee_tier_of(Some(caller)),
+            )?;
 
             // percentage dif between given A0 amount (from front-end) and acutal final AO amount
-            let percentage_diff: Balance = self
-                .check_difference(
-                    a0_amount_to_validate,
-                    a0_amount_out_for_caller_before_traders_fee,
-                )
-                .unwrap();
+            let percentage_diff: Balance =
+                self.check_difference(a0_amount_to_validate, quote.amount_out)?;
 
             // validating slippage
-            if percentage_diff > slippage.try_into().unwrap() {
+            if percentage_diff > slippage {
                 return Err(TradingPairErrors::SlippageTolerance)
             }
 
-            let actual_a0_amount_out_for_caller: Balance;
-
-            let a0_amount_out_for_vault: Balance;
-
-            // calculating the amount of A0 coins to allocate to the vault account
-            match (a0_amount_out_for_caller_before_traders_fee * TRADERS_FEE).checked_div(1000u128)
-            {
-                Some(result) => {
-                    a0_amount_out_for_vault = result;
-                }
-                None => return Err(TradingPairErrors::Overflow),
-            };
-
-            let actual_lp_fee: Balance;
-
-            // calculating the actual LP fee
-            match (self.fee / (10u128.pow(12))).checked_mul(10) {
-                Some(result) => {
-                    actual_lp_fee = result;
-                }
-                None => return Err(TradingPairErrors::Overflow),
-            };
-
-            let a0_amount_out_for_lp_vault: Balance;
-
-            // calculating the amount of A0 coins to allocate to the lp vault
-            match (a0_amount_out_for_caller_before_traders_fee * actual_lp_fee)
-                .checked_div(1000u128)
-            {
-                Some(result) => {
-                    a0_amount_out_for_lp_vault = result;
-                }
-                None => return Err(TradingPairErrors::Overflow),
-            };
-
             let new_azero_lp_fee_vault: Balance;
 
-            match self
-                .azero_lp_fee_vault
-                .checked_add(a0_amount_out_for_lp_vault)
-            {
+            match self.azero_lp_fee_vault.checked_add(quote.lp_fee) {
                 Some(result) => {
                     new_azero_lp_fee_vault = result;
                 }
@@ -1365,7 +2286,7 @@ pub mod trading_pair_azero {
 
             match self
                 .contract_overall_generated_azero_fee
-                .checked_add(a0_amount_out_for_lp_vault)
+                .checked_add(quote.lp_fee)
             {
                 Some(result) => {
                     new_contract_overall_generated_azero_fee = result;
@@ -1375,28 +2296,33 @@ pub mod trading_pair_azero {
 
             self.contract_overall_generated_azero_fee = new_contract_overall_generated_azero_fee;
 
-            // calculating the final amount of A0 coins to give to the caller after reducing traders fee
-            match a0_amount_out_for_caller_before_traders_fee
-                .checked_sub(a0_amount_out_for_vault + a0_amount_out_for_lp_vault)
-            {
+            // increase num of trans
+            match self.transasction_number.checked_add(1) {
                 Some(result) => {
-                    actual_a0_amount_out_for_caller = result;
+                    self.transasction_number = result;
                 }
                 None => return Err(TradingPairErrors::Overflow),
             };
 
-            let psp22_amount_out_for_vault: Balance;
+            // adding the swap to the pair, daily and caller statistics
+            self.record_swap(
+                caller,
+                &SwapStats {
+                    a0_volume: quote.amount_out,
+                    psp22_volume: psp22_amount_to_transfer,
+                    a0_traders_fee: quote.traders_fee,
+                    psp22_traders_fee: quote.traders_fee_in,
+                    a0_lp_fee: quote.lp_fee,
+                    psp22_to_a0_swaps: 1,
+                    ..Default::default()
+                },
+            )?;
 
-            // calculating the amount of PSP22 tokens to allocate to the vault account
-            match (psp22_amount_to_transfer * TRADERS_FEE).checked_div(1000u128) {
-                Some(result) => {
-                    psp22_amount_out_for_vault = result;
-                }
-                None => return Err(TradingPairErrors::Overflow),
-            };
+            // accruing the traders fees for the vault, collected with `collect_protocol_fees`
+            self.accrue_vault_fees(quote.traders_fee_in, quote.traders_fee)?;
 
             // cross contract call to psp22 contract to transfer psp22 token to the Pair contract
-            if PSP22Ref::transfer_from_builder(
+            let transfer_from_result = PSP22Ref::transfer_from_builder(
                 &self.psp22_token,
                 caller,
                 Self::env().account_id(),
@@ -1404,11 +2330,9 @@ pub mod trading_pair_azero {
                 vec![],
             )
             .call_flags(CallFlags::default().set_allow_reentry(true))
-            .try_invoke()
-            .is_err()
-            {
-                return Err(TradingPairErrors::PSP22TransferFromFailed)
-            }
+            .try_invoke();
+
+            check_transfer_from_result(transfer_from_result)?;
 
             let caller_balance_after_transfer: Balance =
                 PSP22Ref::balance_of(&self.psp22_token, caller);
@@ -1417,45 +2341,32 @@ pub mod trading_pair_azero {
                 return Err(TradingPairErrors::CallerInsufficientPSP22Balance)
             }
 
-            // cross contract call to PSP22 contract to transfer PSP22 to the vault
-            if PSP22Ref::transfer(
-                &self.psp22_token,
-                self.vault,
-                psp22_amount_out_for_vault,
-                vec![],
-            )
-            .is_err()
-            {
-                return Err(TradingPairErrors::PSP22TransferFailed)
-            }
-
-            // function to transfer A0 to the caller.
-            if self
-                .env()
-                .transfer(caller, actual_a0_amount_out_for_caller)
-                .is_err()
-            {
-                return Err(TradingPairErrors::A0TransferFailed)
+            // function to transfer A0 to the caller, recording a claim if the transfer fails.
+            self.transfer_a0_or_record_claim(caller, quote.amount_out)?;
+
+            // making sure that the swap did not decrease the constant product of the reserves
+            if !is_constant_product_kept(
+                contract_a0_current_balance,
+                contract_psp22_current_balance,
+                self.get_a0_balance(),
+                self.get_psp22_balance(),
+            ) {
+                return Err(TradingPairErrors::InvariantViolated)
             }
 
-            // function to transfer A0 to the vault.
-            if self
-                .env()
-                .transfer(self.vault, a0_amount_out_for_vault)
-                .is_err()
-            {
-                return Err(TradingPairErrors::A0TransferFailed)
-            }
+            // storing the new reserves and emitting a Sync event
+            self.sync_reserves();
 
-            // increase num of trans
-            self.transasction_number = self.transasction_number + 1;
             Self::env().emit_event(PSP22Swap {
                 caller,
+                recipient: caller,
                 psp22_deposited_amount: psp22_amount_to_transfer,
-                a0_given_amount: actual_a0_amount_out_for_caller,
-                a0_given_to_vault: a0_amount_out_for_vault,
+                a0_given_amount: quote.amount_out,
+                a0_given_to_vault: quote.traders_fee,
             });
 
+            self.unlock_reentrancy();
+
             Ok(())
         }
 
@@ -1466,12 +2377,13 @@ pub mod trading_pair_azero {
             psp22_amount_to_validate: Balance,
             slippage: Balance,
         ) -> Result<(), TradingPairErrors> {
+            // making sure that the pair is not re-entered while this message runs
+            self.lock_reentrancy()?;
+
             let caller = self.env().caller();
 
             let contract_a0_current_balance = self.get_a0_balance();
 
-            const TRADERS_FEE: Balance = 2500000000000 / 10u128.pow(12); // Fee to be charged to traders
-
             // making sure that the contract has more than 0 A0 coins.
             if contract_a0_current_balance <= 0 {
                 return Err(TradingPairErrors::ContractOutOfA0)
@@ -1484,518 +2396,2893 @@ pub mod trading_pair_azero {
                 return Err(TradingPairErrors::ContractOutOfPSP22)
             }
 
-            // amount of PSP22 tokens to give to caller before traders fee.
-            let psp22_amount_out_for_caller_before_traders_fee: Balance = self
-                .get_est_price_a0_to_psp22_for_swap(self.env().transferred_value())
-                .unwrap();
+            let a0_reserve_before: Balance;
+
+            // calculating the A0 contract reserve before the transaction
+            match contract_a0_current_balance.checked_sub(self.env().transferred_value()) {
+                Some(result) => {
+                    a0_reserve_before = result;
+                }
+                None => return Err(TradingPairErrors::Overflow),
+            };
+
+            // the amounts of the swap, same as `get_est_price_a0_to_psp22_for_swap` and `quote_a0_to_psp22`
+            let quote = self.get_swap_quote(
+                self.env().transferred_value(),
+                a0_reserve_before,
+                contract_psp22_current_balance,
+                self.get_fee_tier_of(Some(caller)),
+            )?;
 
             // percentage dif between given PSP22 amount (from front-end) and the acutal final PSP22 amount.
-            let percentage_diff: Balance = self
-                .check_difference(
-                    psp22_amount_to_validate,
-                    psp22_amount_out_for_caller_before_traders_fee,
-                )
-                .unwrap();
+            let percentage_diff: Balance =
+                self.check_difference(psp22_amount_to_validate, quote.amount_out)?;
 
             // validating slippage
-            if percentage_diff > slippage.try_into().unwrap() {
+            if percentage_diff > slippage {
                 return Err(TradingPairErrors::SlippageTolerance)
             }
 
-            let psp22_amount_out_for_vault: Balance;
-
-            let actual_psp22_amount_out_for_caller: Balance;
+            let new_psp22_lp_fee_vault: Balance;
 
-            // calculating the amount of PSP22 tokens to allocate to the vault account
-            match (psp22_amount_out_for_caller_before_traders_fee * TRADERS_FEE)
-                .checked_div(1000u128)
-            {
+            match self.psp22_lp_fee_vault.checked_add(quote.lp_fee) {
                 Some(result) => {
-                    psp22_amount_out_for_vault = result;
+                    new_psp22_lp_fee_vault = result;
                 }
                 None => return Err(TradingPairErrors::Overflow),
             };
 
-            let actual_lp_fee: Balance;
+            self.psp22_lp_fee_vault = new_psp22_lp_fee_vault;
+
+            let new_contract_overall_generated_psp22_fee: Balance;
 
-            // calculating the actual LP fee
-            match (self.fee / (10u128.pow(12))).checked_mul(10) {
+            match self
+                .contract_overall_generated_psp22_fee
+                .checked_add(quote.lp_fee)
+            {
                 Some(result) => {
-                    actual_lp_fee = result;
+                    new_contract_overall_generated_psp22_fee = result;
                 }
                 None => return Err(TradingPairErrors::Overflow),
             };
 
-            let psp22_amount_out_for_lp_vault: Balance;
+            self.contract_overall_generated_psp22_fee = new_contract_overall_generated_psp22_fee;
 
-            // calculating the amount of PSP22 tokens to allocate to the lp vault
-            match (psp22_amount_out_for_caller_before_traders_fee * actual_lp_fee)
-                .checked_div(1000u128)
-            {
+            // increase num of trans
+            match self.transasction_number.checked_add(1) {
                 Some(result) => {
-                    psp22_amount_out_for_lp_vault = result;
+                    self.transasction_number = result;
                 }
                 None => return Err(TradingPairErrors::Overflow),
             };
 
-            let new_psp22_lp_fee_vault: Balance;
+            // adding the swap to the pair, daily and caller statistics
+            self.record_swap(
+                caller,
+                &SwapStats {
+                    a0_volume: self.env().transferred_value(),
+                    psp22_volume: quote.amount_out,
+                    a0_traders_fee: quote.traders_fee_in,
+                    psp22_traders_fee: quote.traders_fee,
+                    psp22_lp_fee: quote.lp_fee,
+                    a0_to_psp22_swaps: 1,
+                    ..Default::default()
+                },
+            )?;
 
-            match self
-                .psp22_lp_fee_vault
-                .checked_add(psp22_amount_out_for_lp_vault)
+            // accruing the traders fees for the vault, collected with `collect_protocol_fees`
+            self.accrue_vault_fees(quote.traders_fee, quote.traders_fee_in)?;
+
+            // cross contract call to PSP22 contract to transfer PSP22 to the caller
+            if let Err(error) =
+                PSP22Ref::transfer(&self.psp22_token, caller, quote.amount_out, vec![])
             {
+                return Err(TradingPairErrors::PSP22TransferFailed(error))
+            }
+
+            // making sure that the swap did not decrease the constant product of the reserves
+            if !is_constant_product_kept(
+                a0_reserve_before,
+                contract_psp22_current_balance,
+                self.get_a0_balance(),
+                self.get_psp22_balance(),
+            ) {
+                return Err(TradingPairErrors::InvariantViolated)
+            }
+
+            // storing the new reserves and emitting a Sync event
+            self.sync_reserves();
+
+            Self::env().emit_event(A0Swap {
+                caller,
+                recipient: caller,
+                a0_deposited_amount: self.env().transferred_value(),
+                psp22_given_amount: quote.amount_out,
+                psp22_given_to_vault: quote.traders_fee,
+            });
+
+            self.unlock_reentrancy();
+
+            Ok(())
+        }
+
+        /// function to add traders fees to the amounts accrued for the vault.
+        fn accrue_vault_fees(
+            &mut self,
+            psp22_amount: Balance,
+            a0_amount: Balance,
+        ) -> Result<(), TradingPairErrors> {
+            match self.pending_vault_psp22.checked_add(psp22_amount) {
                 Some(result) => {
-                    new_psp22_lp_fee_vault = result;
+                    self.pending_vault_psp22 = result;
                 }
                 None => return Err(TradingPairErrors::Overflow),
             };
 
-            self.psp22_lp_fee_vault = new_psp22_lp_fee_vault;
+            match self.pending_vault_a0.checked_add(a0_amount) {
+                Some(result) => {
+                    self.pending_vault_a0 = result;
+                }
+                None => return Err(TradingPairErrors::Overflow),
+            };
 
-            let new_contract_overall_generated_psp22_fee: Balance;
+            Ok(())
+        }
 
-            match self
-                .contract_overall_generated_psp22_fee
-                .checked_add(psp22_amount_out_for_lp_vault)
-            {
+        /// function to send the traders fees accrued since the last collection to the vault, anyone can call it.
+        #[ink(message)]
+        pub fn collect_protocol_fees(&mut self) -> Result<(), TradingPairErrors> {
+            // making sure that the pair is not re-entered while this message runs
+            self.lock_reentrancy()?;
+
+            let psp22_amount = self.pending_vault_psp22;
+
+            let a0_amount = self.pending_vault_a0;
+
+            if psp22_amount == 0 && a0_amount == 0 {
+                return Err(TradingPairErrors::ZeroPendingVaultFees)
+            }
+
+            self.pending_vault_psp22 = 0;
+
+            self.pending_vault_a0 = 0;
+
+            if psp22_amount > 0 {
+                // cross contract call to PSP22 contract to transfer PSP22 to the vault
+                if let Err(error) =
+                    PSP22Ref::transfer(&self.psp22_token, self.vault, psp22_amount, vec![])
+                {
+                    return Err(TradingPairErrors::PSP22TransferFailed(error))
+                }
+            }
+
+            // function to transfer A0 to the vault, recording a claim if the transfer fails.
+            self.transfer_a0_or_record_claim(self.vault, a0_amount)?;
+
+            Self::env().emit_event(ProtocolFeesCollected {
+                vault: self.vault,
+                psp22_amount,
+                a0_amount,
+            });
+
+            self.unlock_reentrancy();
+
+            Ok(())
+        }
+
+        /// function to get the traders fees accrued for the vault (AZERO, PSP22)
+        #[ink(message)]
+        pub fn get_pending_vault_fees(&self) -> (Balance, Balance) {
+            (self.pending_vault_a0, self.pending_vault_psp22)
+        }
+
+        /// function to add a swap to the pair statistics, today's statistics and the trader's volume.
+        fn record_swap(
+            &mut self,
+            trader: AccountId,
+            swap: &SwapStats,
+        ) -> Result<(), TradingPairErrors> {
+            let day = self.get_current_day();
+
+            let new_swap_stats: SwapStats;
+
+            match self.swap_stats.checked_add(swap) {
                 Some(result) => {
-                    new_contract_overall_generated_psp22_fee = result;
+                    new_swap_stats = result;
                 }
                 None => return Err(TradingPairErrors::Overflow),
             };
 
-            self.contract_overall_generated_psp22_fee = new_contract_overall_generated_psp22_fee;
+            let new_daily_swap_stats: SwapStats;
 
-            // calculating the final amount of PSP22 tokens to give to the caller after reducing traders fee
-            match psp22_amount_out_for_caller_before_traders_fee
-                .checked_sub(psp22_amount_out_for_vault + psp22_amount_out_for_lp_vault)
+            match self
+                .daily_swap_stats
+                .get(day)
+                .unwrap_or_default()
+                .checked_add(swap)
             {
                 Some(result) => {
-                    actual_psp22_amount_out_for_caller = result;
+                    new_daily_swap_stats = result;
                 }
                 None => return Err(TradingPairErrors::Overflow),
             };
 
-            let a0_amount_out_for_vault: Balance;
+            let (trader_a0_volume, trader_psp22_volume) =
+                self.account_swap_volume.get(trader).unwrap_or((0, 0));
+
+            let new_trader_volume: (Balance, Balance);
 
-            // calculating the amount of A0 coins to allocate to the vault account
-            match (self.env().transferred_value() * TRADERS_FEE).checked_div(1000u128) {
+            match trader_a0_volume
+                .checked_add(swap.a0_volume)
+                .zip(trader_psp22_volume.checked_add(swap.psp22_volume))
+            {
                 Some(result) => {
-                    a0_amount_out_for_vault = result;
+                    new_trader_volume = result;
                 }
                 None => return Err(TradingPairErrors::Overflow),
             };
 
-            // cross contract call to PSP22 contract to transfer PSP22 to the caller
-            if PSP22Ref::transfer(
-                &self.psp22_token,
-                caller,
-                actual_psp22_amount_out_for_caller,
-                vec![],
-            )
-            .is_err()
-            {
-                return Err(TradingPairErrors::PSP22TransferFailed)
-            }
+            self.swap_stats = new_swap_stats;
 
-            // cross contract call to PSP22 contract to transfer PSP22 to the vault
-            if PSP22Ref::transfer(
-                &self.psp22_token,
-                self.vault,
-                psp22_amount_out_for_vault,
-                vec![],
-            )
-            .is_err()
-            {
-                return Err(TradingPairErrors::PSP22TransferFailed)
-            }
+            self.daily_swap_stats.insert(day, &new_daily_swap_stats);
 
-            // function to transfer A0 to the vault.
-            if self
-                .env()
-                .transfer(self.vault, a0_amount_out_for_vault)
-                .is_err()
-            {
-                return Err(TradingPairErrors::A0TransferFailed)
-            }
+            self.account_swap_volume.insert(trader, &new_trader_volume);
 
-            // increase num of trans
-            self.transasction_number = self.transasction_number + 1;
+            Ok(())
+        }
 
-            Self::env().emit_event(A0Swap {
-                caller,
-                a0_deposited_amount: self.env().transferred_value(),
-                psp22_given_amount: actual_psp22_amount_out_for_caller,
-                psp22_given_to_vault: psp22_amount_out_for_vault,
-            });
+        /// function to get the swap statistics since the pair was deployed.
+        #[ink(message)]
+        pub fn get_swap_stats(&self) -> SwapStats {
+            self.swap_stats.clone()
+        }
 
-            Ok(())
+        /// function to get the swap statistics of a day, by day number (see `get_current_day`).
+        #[ink(message)]
+        pub fn get_daily_swap_stats(&self, day: u64) -> SwapStats {
+            self.daily_swap_stats.get(day).unwrap_or_default()
         }
 
-        /// function to add caller to the LP incentive program
-        fn update_incentive_program(&mut self, caller: AccountId) -> Result<(), TradingPairErrors> {
-            const STAKING_PERCENTAGE: Balance = 2; // Percentage of fees to be distributed as staking rewards
+        /// function to get the current day number (timestamp in seconds / 86400) used by the daily statistics.
+        #[ink(message)]
+        pub fn get_current_day(&self) -> u64 {
+            self.get_current_timestamp() / 86400
+        }
 
-            let account_shares_balance: Balance = self.balances.get(&caller).unwrap_or(0);
+        /// function to get the overall swap volume (AZERO, PSP22) of an account.
+        #[ink(message)]
+        pub fn get_account_swap_volume(&self, account: AccountId) -> (Balance, Balance) {
+            self.account_swap_volume.get(account).unwrap_or((0, 0))
+        }
 
-            // amount of PSP22 to give to the caller without LP fee
-            let caller_locked_psp22_balance = self
-                .get_psp22_withdraw_tokens_amount(account_shares_balance)
-                .unwrap();
+        /// function to transfer AZERO to `recipient`, recording a pending claim instead of failing if the transfer is rejected.
+        fn transfer_a0_or_record_claim(
+            &mut self,
+            recipient: AccountId,
+            amount: Balance,
+        ) -> Result<(), TradingPairErrors> {
+            if amount == 0 {
+                return Ok(())
+            }
 
-            // calc how many tokens to give in a day
-            let psp22_amount_to_give_each_day: Balance;
+            if self.env().transfer(recipient, amount).is_ok() {
+                return Ok(())
+            }
 
-            // calculating the amount of daily PSP22 to give to the user
-            match ((caller_locked_psp22_balance * STAKING_PERCENTAGE) / 100u128).checked_div(365) {
+            let new_recipient_claim: Balance;
+
+            // adding the failed payout to the recipient's pending claim
+            match self
+                .pending_a0_claims
+                .get(&recipient)
+                .unwrap_or(0)
+                .checked_add(amount)
+            {
                 Some(result) => {
-                    psp22_amount_to_give_each_day = result;
+                    new_recipient_claim = result;
                 }
                 None => return Err(TradingPairErrors::Overflow),
             };
 
-            // insert the daily amount of PSP22 tokens and AZERO to give to the caller
-            self.psp22_to_give_in_a_day
-                .insert(caller, &psp22_amount_to_give_each_day);
+            let new_total_pending_a0_claims: Balance;
 
-            self.last_redeemed
-                .insert(caller, &self.get_current_timestamp());
+            match self.total_pending_a0_claims.checked_add(amount) {
+                Some(result) => {
+                    new_total_pending_a0_claims = result;
+                }
+                None => return Err(TradingPairErrors::Overflow),
+            };
+
+            self.pending_a0_claims
+                .insert(recipient, &new_recipient_claim);
+
+            self.total_pending_a0_claims = new_total_pending_a0_claims;
+
+            Self::env().emit_event(A0ClaimRecorded { recipient, amount });
 
             Ok(())
         }
 
-        /// function to get caller redeemable amount of pooled PSP22
-        #[ink(message)]
-        pub fn get_psp22_redeemable_amount(&mut self) -> Result<Balance, TradingPairErrors> {
-            // call address
-            let caller = self.env().caller();
-            // current timestamp
-            let current_tsp = self.get_current_timestamp();
+        /// function to store the current pool reserves and emit them in a Sync event.
+        fn sync_reserves(&mut self) {
+            // the prices of the old reserves held until now
+            let (psp22_price_cumulative, a0_price_cumulative, timestamp) =
+                self.get_price_cumulatives();
 
-            let account_shares_balance: Balance = self.balances.get(&caller).unwrap_or(0);
+            self.psp22_price_cumulative = psp22_price_cumulative;
+            self.a0_price_cumulative = a0_price_cumulative;
+            self.price_cumulatives_timestamp = timestamp;
 
-            // amount of PSP22 to give to the caller without LP fee
-            let caller_locked_psp22_balance = self
-                .get_psp22_withdraw_tokens_amount(account_shares_balance)
-                .unwrap_or(0);
+            self.reserve_a0 = self.get_a0_balance();
 
-            // last time caller redeemed tokens
-            let last_redeemed: u64 = self.last_redeemed.get(caller).unwrap_or(0);
+            self.reserve_psp22 = self.get_psp22_balance();
 
-            // the amount of daily PSP22 tokens to give ot the caller
-            let psp22_to_give_each_day: Balance =
-                self.psp22_to_give_in_a_day.get(caller).unwrap_or(0);
+            Self::env().emit_event(Sync {
+                reserve_a0: self.reserve_a0,
+                reserve_psp22: self.reserve_psp22,
+            });
+        }
 
-            // Declare a variable to hold the difference in days between current timestamp and last redeemed timestamp
-            let days_difference: u64;
+        /// function to mint the protocol fee share of the sqrt(k) growth since the last liquidity change to the treasury.
+        fn mint_protocol_fee(&mut self) -> Result<(), TradingPairErrors> {
+            let treasury = match self.protocol_fee_to {
+                Some(treasury) => treasury,
+                None => return Ok(()),
+            };
 
-            // Calculate the difference in days by dividing the difference between current timestamp and last redeemed timestamp by 86400 (number of seconds in a day)
-            match (current_tsp - last_redeemed).checked_div(86400) {
+            // the growth is measured from the first liquidity change after the protocol fee was turned on
+            if self.root_k_last == 0 {
+                return Ok(())
+            }
+
+            let shares: Balance;
+
+            match get_protocol_fee_shares(
+                self.total_supply,
+                get_root_k(self.reserve_a0, self.reserve_psp22),
+                self.root_k_last,
+                self.protocol_fee_share,
+            ) {
                 Some(result) => {
-                    days_difference = result;
-                }
-                None => {
-                    // If the division results in overflow, return an error of StakingErrors::Overflow
-                    return Err(TradingPairErrors::Overflow)
+                    shares = result;
                 }
+                None => return Err(TradingPairErrors::Overflow),
             };
 
-            // making sure that caller has more then 0 pooled PSP22 tokens
-            if caller_locked_psp22_balance <= 0 {
-                return Err(TradingPairErrors::CallerInsufficientPSP22Balance)
-            }
-
-            // making sure that caller has more than 0 daily PSP22 tokens to claim
-            if psp22_to_give_each_day <= 0 {
-                return Err(TradingPairErrors::ZeroDailyPSP22)
+            if shares == 0 {
+                return Ok(())
             }
 
-            // The amount of PSP22 tokens and AZERO to give to the caller
-            let psp22_redeemable_amount: Balance = psp22_to_give_each_day * days_difference as u128;
+            let new_treasury_shares: Balance;
 
-            Ok(psp22_redeemable_amount)
-        }
+            match self.get_lp_token_of(treasury).checked_add(shares) {
+                Some(result) => {
+                    new_treasury_shares = result;
+                }
+                None => return Err(TradingPairErrors::Overflow),
+            };
 
-        /// function for caller to redeem LP incentive tokens.
-        #[ink(message)]
-        pub fn redeem_redeemable_amount(&mut self) -> Result<(), TradingPairErrors> {
-            // caller address
-            let caller = self.env().caller();
-            // caller timestamp
-            let current_tsp = self.get_current_timestamp();
+            let new_total_supply: Balance;
 
-            let psp22_redeemable_amount = self.get_psp22_redeemable_amount().unwrap_or(0);
+            match self.total_supply.checked_add(shares) {
+                Some(result) => {
+                    new_total_supply = result;
+                }
+                None => return Err(TradingPairErrors::Overflow),
+            };
 
-            //if psp22_redeemable_amount == 0 {
-            //    return Err(TradingPairErrors::ZeroRedeemableAmount)
-            //}
+            // settling the streamed rewards earned with the current shares
+            self.update_stream_rewards_of(treasury)?;
 
-            // cross contract call to PSP22 contract to transfer PSP22 to caller
-            if PSP22Ref::transfer(&self.psp22_token, caller, psp22_redeemable_amount, vec![])
-                .is_err()
-            {
-                return Err(TradingPairErrors::PSP22TransferFailed)
-            }
+            // Increase the LP balance of the treasury (mint)
+            self.balances.insert(treasury, &new_treasury_shares);
 
-            let current_account_overall_psp22_staking_rewards = self
-                .account_overall_staking_rewards
-                .get(&caller)
-                .unwrap_or(0);
+            psp22::Internal::_mint_to(self, treasury, shares);
 
-            self.account_overall_staking_rewards.insert(
-                &caller,
-                &(current_account_overall_psp22_staking_rewards + psp22_redeemable_amount),
-            );
+            self.total_supply = new_total_supply;
 
-            // Making sure to set his last redeem to current timestamp
-            self.last_redeemed.insert(caller, &current_tsp);
+            Self::env().emit_event(ProtocolFeeMinted {
+                to: treasury,
+                shares,
+            });
 
             Ok(())
         }
 
-        /// function to reduce the incentive program rewards allocation after LP removal.
-        fn remove_lp(&mut self, new_shares: Balance) -> Result<(), TradingPairErrors> {
-            // caller address
-            let caller = self.env().caller();
+        /// function to store sqrt(k) after a liquidity change, the protocol fee growth is measured from it.
+        fn update_root_k_last(&mut self) {
+            self.root_k_last = match self.protocol_fee_to {
+                Some(_) => get_root_k(self.reserve_a0, self.reserve_psp22),
+                None => 0,
+            };
+        }
 
-            const STAKING_PERCENTAGE: Balance = 2; // Percentage of fees to be distributed as staking rewards
+        /// function for the admin to turn the protocol fee on with a treasury and share (parts per million), or off with None.
+        #[ink(message)]
+        pub fn set_protocol_fee(
+            &mut self,
+            to: Option<AccountId>,
+            share: Balance,
+        ) -> Result<(), TradingPairErrors> {
+            // making sure that the pair is not re-entered while this message runs
+            self.lock_reentrancy()?;
 
-            if new_shares == 0 {
-                if self.redeem_redeemable_amount().is_err() {
-                    return Err(TradingPairErrors::RemoveLpIncentiveProgramError)
-                }
+            if self.env().caller() != self.admin {
+                return Err(TradingPairErrors::CallerIsNotAdmin)
+            }
 
-                // insert the daily amount of PSP22 and AZERO tokens to give to the caller
-                self.psp22_to_give_in_a_day.insert(caller, &0);
+            if share > MAX_PROTOCOL_FEE_SHARE {
+                return Err(TradingPairErrors::InvalidProtocolFeeShare)
             }
 
-            if new_shares > 0 {
-                if self.redeem_redeemable_amount().is_err() {
-                    return Err(TradingPairErrors::RemoveLpIncentiveProgramError)
-                }
+            // a protocol fee with no treasury or no share is off
+            let (to, share) = match to {
+                Some(treasury) if share > 0 => (Some(treasury), share),
+                _ => (None, 0),
+            };
+
+            // growth accrued while the protocol fee was off is left to the LPs
+            if to.is_none() {
+                self.root_k_last = 0;
+            }
 
-                // amount of pooled PSP22 tokens by number of LP shares with LP fee
-                let caller_locked_psp22_balance =
-                    self.get_psp22_withdraw_tokens_amount(new_shares).unwrap();
+            self.protocol_fee_to = to;
 
-                let new_psp22_amount_to_give_each_day: Balance;
+            self.protocol_fee_share = share;
 
-                // calculating the amount of daily PSP22 to give to the user
-                match ((caller_locked_psp22_balance * STAKING_PERCENTAGE) / 100u128)
-                    .checked_div(365)
-                {
-                    Some(result) => {
-                        new_psp22_amount_to_give_each_day = result;
-                    }
-                    None => return Err(TradingPairErrors::Overflow),
-                };
+            Self::env().emit_event(ProtocolFeeSet { to, share });
 
-                // insert the daily amount of PSP22 and AZERO tokens to give to the caller
-                self.psp22_to_give_in_a_day
-                    .insert(caller, &new_psp22_amount_to_give_each_day);
-            }
+            self.unlock_reentrancy();
 
             Ok(())
         }
 
-        /// function to get the amount of tokens to give to caller each day.
+        /// function to get the protocol fee treasury (None while off) and its share of the LP fee growth in parts per million
         #[ink(message)]
-        pub fn get_amount_to_give_each_day_to_caller(&mut self, caller: AccountId) -> Balance {
-            let psp22_daily_amount: Balance = self.psp22_to_give_in_a_day.get(&caller).unwrap_or(0);
-
-            psp22_daily_amount
+        pub fn get_protocol_fee(&self) -> (Option<AccountId>, Balance) {
+            (self.protocol_fee_to, self.protocol_fee_share)
         }
 
+        /// function to get sqrt(k) after the last liquidity change while the protocol fee was on
         #[ink(message)]
-        pub fn get_generated_lp_fees(&mut self) -> (Balance, Balance) {
-            let psp22_lp_fees: Balance = self.psp22_lp_fee_vault;
-            let azero_lp_fees: Balance = self.azero_lp_fee_vault;
-
-            (psp22_lp_fees, azero_lp_fees)
+        pub fn get_root_k_last(&self) -> Balance {
+            self.root_k_last
         }
 
+        /// function to get the price cumulatives (PSP22 price in AZERO, AZERO price in PSP22) as of now and the current timestamp.
+        /// a time weighted average price is the difference of two readings divided by the seconds between them.
         #[ink(message)]
-        pub fn get_account_overall_staking_rewards(&self, owner: AccountId) -> Balance {
-            let psp22_overall_amount = self
-                .account_overall_staking_rewards
-                .get(&owner)
-                .unwrap_or(0);
+        pub fn get_price_cumulatives(&self) -> (Balance, Balance, u64) {
+            let current_timestamp = self.get_current_timestamp();
 
-            psp22_overall_amount
-        }
+            let elapsed_seconds = current_timestamp.saturating_sub(self.price_cumulatives_timestamp);
 
-        #[ink(message)]
-        pub fn get_account_overall_lp_fee_rewards(&self, owner: AccountId) -> (Balance, Balance) {
-            let (psp22_overall_amount, azero_overall_amount) = self
-                .account_overall_lp_fee_rewards
-                .get(&owner)
-                .unwrap_or((0, 0));
+            // no price to accumulate while the pool is empty
+            if elapsed_seconds == 0 || self.reserve_a0 == 0 || self.reserve_psp22 == 0 {
+                return (
+                    self.psp22_price_cumulative,
+                    self.a0_price_cumulative,
+                    current_timestamp,
+                )
+            }
 
-            (psp22_overall_amount, azero_overall_amount)
+            (
+                self.psp22_price_cumulative
+                    .wrapping_add(get_price_cumulative_increase(
+                        self.reserve_a0,
+                        self.reserve_psp22,
+                        elapsed_seconds,
+                    )),
+                self.a0_price_cumulative
+                    .wrapping_add(get_price_cumulative_increase(
+                        self.reserve_psp22,
+                        self.reserve_a0,
+                        elapsed_seconds,
+                    )),
+                current_timestamp,
+            )
         }
 
-        // function to get the contract's overall generated LP fees
+        /// function to get the pool reserves (AZERO, PSP22) stored by the last operation that changed them.
         #[ink(message)]
-        pub fn get_contract_overall_generated_fee(&mut self) -> (Balance, Balance) {
-            let psp22_lp_fees: Balance = self.contract_overall_generated_psp22_fee;
-            let azero_lp_fees: Balance = self.contract_overall_generated_azero_fee;
+        pub fn get_reserves(&self) -> (Balance, Balance) {
+            (self.reserve_a0, self.reserve_psp22)
+        }
 
-            (psp22_lp_fees, azero_lp_fees)
+        /// function to take the reentrancy lock at the start of a state-changing message.
+        fn lock_reentrancy(&mut self) -> Result<(), TradingPairErrors> {
+            // a message of the pair is already running further up the call stack
+            if self.reentrancy_lock.get().unwrap_or(false) {
+                return Err(TradingPairErrors::Reentrancy)
+            }
+
+            self.reentrancy_lock.set(&true);
+
+            Ok(())
         }
 
-        /// function to get AzeroTradingPair contract address (self)
-        #[ink(message)]
-        pub fn get_account_id(&self) -> AccountId {
-            Self::env().account_id()
+        /// function to release the reentrancy lock before a state-changing message returns successfully.
+        /// Failed messages revert their storage changes, the lock included.
+        fn unlock_reentrancy(&mut self) {
+            self.reentrancy_lock.set(&false);
         }
 
+        /// function for caller to collect AZERO payouts that failed to transfer earlier.
         #[ink(message)]
-        pub fn get_deployer_account(&self) -> AccountId {
-            self.deployer
+        pub fn claim_a0(&mut self) -> Result<(), TradingPairErrors> {
+            // making sure that the pair is not re-entered while this message runs
+            self.lock_reentrancy()?;
+
+            let caller = self.env().caller();
+
+            let claimable_amount: Balance = self.pending_a0_claims.get(&caller).unwrap_or(0);
+
+            // making sure that the caller has a pending AZERO claim
+            if claimable_amount == 0 {
+                return Err(TradingPairErrors::ZeroClaimableAmount)
+            }
+
+            let new_total_pending_a0_claims: Balance;
+
+            match self.total_pending_a0_claims.checked_sub(claimable_amount) {
+                Some(result) => {
+                    new_total_pending_a0_claims = result;
+                }
+                None => return Err(TradingPairErrors::Overflow),
+            };
+
+            self.pending_a0_claims.remove(&caller);
+
+            self.total_pending_a0_claims = new_total_pending_a0_claims;
+
+            // function to transfer the claimed A0 to the caller
+            if let Err(error) = self.env().transfer(caller, claimable_amount) {
+                return Err(TradingPairErrors::A0TransferFailed(EnvErrorCode::from(error)))
+            }
+
+            Self::env().emit_event(A0Claimed {
+                caller,
+                amount: claimable_amount,
+            });
+
+            self.unlock_reentrancy();
+
+            Ok(())
+        }
+
+        /// function to get the amount of AZERO an account can collect with `claim_a0`
+        #[ink(message)]
+        pub fn get_pending_a0_claim(&self, account: AccountId) -> Balance {
+            self.pending_a0_claims.get(&account).unwrap_or(0)
+        }
+
+        /// function to get the total AZERO owed to accounts through pending claims
+        #[ink(message)]
+        pub fn get_total_pending_a0_claims(&self) -> Balance {
+            self.total_pending_a0_claims
+        }
+
+        /// function for the admin to return tokens sent to the pair by mistake, `token` None stands for AZERO.
+        /// the pooled PSP22 token can't be rescued, and only AZERO sent outside the pool reserve can.
+        #[ink(message)]
+        pub fn rescue_token(
+            &mut self,
+            token: Option<AccountId>,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), TradingPairErrors> {
+            // making sure that the pair is not re-entered while this message runs
+            self.lock_reentrancy()?;
+
+            if self.env().caller() != self.admin {
+                return Err(TradingPairErrors::CallerIsNotAdmin)
+            }
+
+            match token {
+                Some(token) => {
+                    if token == self.psp22_token {
+                        return Err(TradingPairErrors::CannotRescuePoolToken)
+                    }
+
+                    // streamed rewards belong to the LPs
+                    if self.reward_tokens.contains(&token) {
+                        return Err(TradingPairErrors::CannotRescueRewardToken)
+                    }
+
+                    // cross contract call to the stray PSP22 token to transfer it out
+                    if let Err(error) = PSP22Ref::transfer(&token, to, amount, vec![]) {
+                        return Err(TradingPairErrors::PSP22TransferFailed(error))
+                    }
+                }
+                None => {
+                    // AZERO received since the last reserve change is not part of the pool
+                    if amount > self.get_a0_balance().saturating_sub(self.reserve_a0) {
+                        return Err(TradingPairErrors::InsufficientRescuableA0)
+                    }
+
+                    if let Err(error) = self.env().transfer(to, amount) {
+                        return Err(TradingPairErrors::A0TransferFailed(EnvErrorCode::from(error)))
+                    }
+                }
+            }
+
+            Self::env().emit_event(TokensRescued { token, to, amount });
+
+            self.unlock_reentrancy();
+
+            Ok(())
+        }
+
+        /// function for the admin to add a PSP22 token that can be streamed to the LPs by the admin and its `distributor`.
+        #[ink(message)]
+        pub fn add_reward_token(
+            &mut self,
+            token: AccountId,
+            distributor: AccountId,
+        ) -> Result<(), TradingPairErrors> {
+            // making sure that the pair is not re-entered while this message runs
+            self.lock_reentrancy()?;
+
+            if self.env().caller() != self.admin {
+                return Err(TradingPairErrors::CallerIsNotAdmin)
+            }
+
+            // the pooled PSP22 token's balance backs the reserve, it can't be streamed
+            if token == self.psp22_token {
+                return Err(TradingPairErrors::CannotStreamPoolToken)
+            }
+
+            if self.reward_tokens.contains(&token) {
+                return Err(TradingPairErrors::RewardTokenAlreadyAdded)
+            }
+
+            if self.reward_tokens.len() >= MAX_REWARD_TOKENS {
+                return Err(TradingPairErrors::TooManyRewardTokens)
+            }
+
+            self.reward_tokens.push(token);
+
+            self.reward_distributors.insert(token, &distributor);
+
+            self.unlock_reentrancy();
+
+            Ok(())
+        }
+
+        /// function for the token's distributor or the admin to stream `amount` of a reward token to the LPs pro-rata to their shares over `duration` seconds.
+        /// rewards left from the current stream are streamed along with it, the caller has to approve the pair first.
+        /// only they can notify, since a notify spreads the rest of the running stream over the new duration.
+        /// the tokens the pair received are streamed, so a token taking a fee on transfer streams less than `amount`.
+        /// LP shares held by the LP locker, the farm or a gauge earn nothing for their owners: those contracts can't claim,
+        /// their rewards are swept by the admin.
+        #[ink(message)]
+        pub fn notify_reward(
+            &mut self,
+            token: AccountId,
+            amount: Balance,
+            duration: u64,
+        ) -> Result<(), TradingPairErrors> {
+            // making sure that the pair is not re-entered while this message runs
+            self.lock_reentrancy()?;
+
+            if !self.reward_tokens.contains(&token) {
+                return Err(TradingPairErrors::UnknownRewardToken)
+            }
+
+            let caller = self.env().caller();
+
+            if caller != self.admin && Some(caller) != self.reward_distributors.get(token) {
+                return Err(TradingPairErrors::CallerIsNotRewardDistributor)
+            }
+
+            let balance_before = PSP22Ref::balance_of(&token, Self::env().account_id());
+
+            // cross contract call to the reward token to transfer the streamed rewards
+            let transfer_from_result = PSP22Ref::transfer_from_builder(
+                &token,
+                caller,
+                Self::env().account_id(),
+                amount,
+                vec![],
+            )
+            .call_flags(CallFlags::default().set_allow_reentry(true))
+            .try_invoke();
+
+            check_transfer_from_result(transfer_from_result)?;
+
+            let received_amount: Balance;
+
+            // only the tokens the pair received can be streamed
+            match PSP22Ref::balance_of(&token, Self::env().account_id()).checked_sub(balance_before)
+            {
+                Some(result) => {
+                    received_amount = result;
+                }
+                None => return Err(TradingPairErrors::Underflow),
+            };
+
+            self.start_reward_stream(token, received_amount, duration)?;
+
+            self.unlock_reentrancy();
+
+            Ok(())
+        }
+
+        /// function to claim the caller's streamed rewards of every reward token.
+        #[ink(message)]
+        pub fn claim_rewards(&mut self) -> Result<(), TradingPairErrors> {
+            // making sure that the pair is not re-entered while this message runs
+            self.lock_reentrancy()?;
+
+            let caller = self.env().caller();
+
+            self.update_stream_rewards_of(caller)?;
+
+            let mut claimed_any = false;
+
+            for token in self.reward_tokens.clone() {
+                let amount = self
+                    .pending_stream_rewards
+                    .get((token, caller))
+                    .unwrap_or(0);
+
+                if amount == 0 {
+                    continue
+                }
+
+                claimed_any = true;
+
+                self.pending_stream_rewards.remove((token, caller));
+
+                // cross contract call to the reward token to transfer the rewards
+                if let Err(error) = PSP22Ref::transfer(&token, caller, amount, vec![]) {
+                    return Err(TradingPairErrors::PSP22TransferFailed(error))
+                }
+
+                Self::env().emit_event(RewardClaimed {
+                    account: caller,
+                    token,
+                    amount,
+                });
+            }
+
+            if !claimed_any {
+                return Err(TradingPairErrors::ZeroRewardsToClaim)
+            }
+
+            self.unlock_reentrancy();
+
+            Ok(())
+        }
+
+        /// function for the admin to mark a contract holding LP shares that can't claim streamed rewards, e.g. the LP locker, the farm or a gauge.
+        #[ink(message)]
+        pub fn set_unclaimable_reward_holder(
+            &mut self,
+            holder: AccountId,
+            unclaimable: bool,
+        ) -> Result<(), TradingPairErrors> {
+            // making sure that the pair is not re-entered while this message runs
+            self.lock_reentrancy()?;
+
+            if self.env().caller() != self.admin {
+                return Err(TradingPairErrors::CallerIsNotAdmin)
+            }
+
+            if unclaimable {
+                self.unclaimable_reward_holders.insert(holder, &true);
+            } else {
+                self.unclaimable_reward_holders.remove(holder);
+            }
+
+            self.unlock_reentrancy();
+
+            Ok(())
+        }
+
+        /// function for the admin to sweep streamed rewards no LP can claim to `to`, e.g. the distributor to stream them again.
+        /// `holder` None sweeps the unallocated rewards of the stream, `holder` Some the rewards of an unclaimable holder.
+        #[ink(message)]
+        pub fn sweep_rewards(
+            &mut self,
+            token: AccountId,
+            holder: Option<AccountId>,
+            to: AccountId,
+        ) -> Result<Balance, TradingPairErrors> {
+            // making sure that the pair is not re-entered while this message runs
+            self.lock_reentrancy()?;
+
+            if self.env().caller() != self.admin {
+                return Err(TradingPairErrors::CallerIsNotAdmin)
+            }
+
+            if !self.reward_tokens.contains(&token) {
+                return Err(TradingPairErrors::UnknownRewardToken)
+            }
+
+            let amount = self.take_unclaimable_rewards(token, holder)?;
+
+            if amount == 0 {
+                return Err(TradingPairErrors::ZeroRewardsToSweep)
+            }
+
+            // cross contract call to the reward token to transfer the swept rewards
+            if let Err(error) = PSP22Ref::transfer(&token, to, amount, vec![]) {
+                return Err(TradingPairErrors::PSP22TransferFailed(error))
+            }
+
+            Self::env().emit_event(RewardsSwept {
+                token,
+                holder,
+                to,
+                amount,
+            });
+
+            self.unlock_reentrancy();
+
+            Ok(amount)
+        }
+
+        /// function to take the unallocated rewards of a token, or the pending rewards of an unclaimable holder, out of the stream.
+        fn take_unclaimable_rewards(
+            &mut self,
+            token: AccountId,
+            holder: Option<AccountId>,
+        ) -> Result<Balance, TradingPairErrors> {
+            match holder {
+                Some(holder) => {
+                    if !self.is_unclaimable_reward_holder(holder) {
+                        return Err(TradingPairErrors::NotAnUnclaimableRewardHolder)
+                    }
+
+                    self.update_stream_rewards_of(holder)?;
+
+                    let amount = self
+                        .pending_stream_rewards
+                        .get((token, holder))
+                        .unwrap_or(0);
+
+                    self.pending_stream_rewards.remove((token, holder));
+
+                    Ok(amount)
+                }
+                None => {
+                    let mut stream = self.update_reward_stream(token);
+
+                    let amount = stream.unallocated;
+
+                    stream.unallocated = 0;
+
+                    self.reward_streams.insert(token, &stream);
+
+                    Ok(amount)
+                }
+            }
+        }
+
+        /// function to get whether the admin marked an LP holder as unable to claim streamed rewards
+        #[ink(message)]
+        pub fn is_unclaimable_reward_holder(&self, holder: AccountId) -> bool {
+            self.unclaimable_reward_holders.get(holder).unwrap_or(false)
+        }
+
+        /// function to get the account allowed to stream a reward token besides the admin
+        #[ink(message)]
+        pub fn get_reward_distributor(&self, token: AccountId) -> Option<AccountId> {
+            self.reward_distributors.get(token)
+        }
+
+        /// function to get the tokens that can be streamed to the LPs
+        #[ink(message)]
+        pub fn get_reward_tokens(&self) -> Vec<AccountId> {
+            self.reward_tokens.clone()
+        }
+
+        /// function to get the stream of a reward token
+        #[ink(message)]
+        pub fn get_reward_stream(&self, token: AccountId) -> RewardStream {
+            self.reward_streams.get(token).unwrap_or_default()
+        }
+
+        /// function to get the rewards of a token per LP share as of now, scaled by REWARD_PRECISION
+        #[ink(message)]
+        pub fn get_reward_per_share(&self, token: AccountId) -> Balance {
+            let stream = self.get_reward_stream(token);
+
+            // rewards streamed while there are no LP shares are not given to anyone, they are left unallocated
+            if self.total_supply == 0 {
+                return stream.reward_per_share
+            }
+
+            let elapsed_seconds = self
+                .get_current_timestamp()
+                .min(stream.period_finish)
+                .saturating_sub(stream.last_update_time);
+
+            let reward_per_share_increase = (U256::from(stream.rate)
+                * U256::from(elapsed_seconds)
+                * U256::from(REWARD_PRECISION))
+            .checked_div(U256::from(self.total_supply))
+            .and_then(|result| Balance::try_from(result).ok())
+            .unwrap_or(0);
+
+            stream
+                .reward_per_share
+                .saturating_add(reward_per_share_increase)
+        }
+
+        /// function to get the streamed rewards of a token an account can claim, as of now.
+        #[ink(message)]
+        pub fn get_pending_reward(&self, account: AccountId, token: AccountId) -> Balance {
+            let earned_rewards = (U256::from(self.get_lp_token_of(account))
+                * U256::from(
+                    self.get_reward_per_share(token).saturating_sub(
+                        self.reward_per_share_paid
+                            .get((token, account))
+                            .unwrap_or(0),
+                    ),
+                ))
+            .checked_div(U256::from(REWARD_PRECISION))
+            .and_then(|result| Balance::try_from(result).ok())
+            .unwrap_or(0);
+
+            self.pending_stream_rewards
+                .get((token, account))
+                .unwrap_or(0)
+                .saturating_add(earned_rewards)
+        }
+
+        /// function to update the reward streams, then move the rewards an account earned since its last update to its pending rewards.
+        /// it has to run before any change of the account's LP shares or of the total supply.
+        fn update_stream_rewards_of(&mut self, account: AccountId) -> Result<(), TradingPairErrors> {
+            for token in self.reward_tokens.clone() {
+                let pending_reward = self.get_pending_reward(account, token);
+
+                let stream = self.update_reward_stream(token);
+
+                self.pending_stream_rewards
+                    .insert((token, account), &pending_reward);
+
+                self.reward_per_share_paid
+                    .insert((token, account), &stream.reward_per_share);
+            }
+
+            Ok(())
+        }
+
+        /// function to store the reward per share of a token as of now, keeping the rewards streamed while there were no LP shares as unallocated.
+        fn update_reward_stream(&mut self, token: AccountId) -> RewardStream {
+            let mut stream = self.get_reward_stream(token);
+
+            let last_update_time = self.get_current_timestamp().min(stream.period_finish);
+
+            if self.total_supply == 0 {
+                let elapsed_seconds = last_update_time.saturating_sub(stream.last_update_time);
+
+                stream.unallocated = stream
+                    .unallocated
+                    .saturating_add(stream.rate.saturating_mul(elapsed_seconds as Balance));
+            }
+
+            stream.reward_per_share = self.get_reward_per_share(token);
+
+            stream.last_update_time = stream.last_update_time.max(last_update_time);
+
+            self.reward_streams.insert(token, &stream);
+
+            stream
+        }
+
+        /// function to stream `amount` of a reward token plus what is left from its current stream over the next `duration` seconds.
+        fn start_reward_stream(
+            &mut self,
+            token: AccountId,
+            amount: Balance,
+            duration: u64,
+        ) -> Result<(), TradingPairErrors> {
+            if !(MIN_REWARD_DURATION..=MAX_REWARD_DURATION).contains(&duration) {
+                return Err(TradingPairErrors::InvalidRewardDuration)
+            }
+
+            let current_timestamp = self.get_current_timestamp();
+
+            let mut stream = self.update_reward_stream(token);
+
+            let mut reward_amount = amount;
+
+            if current_timestamp < stream.period_finish {
+                let remaining_rewards = stream
+                    .rate
+                    .saturating_mul((stream.period_finish - current_timestamp) as Balance);
+
+                match reward_amount.checked_add(remaining_rewards) {
+                    Some(result) => {
+                        reward_amount = result;
+                    }
+                    None => return Err(TradingPairErrors::Overflow),
+                };
+            }
+
+            stream.rate = reward_amount / duration as Balance;
+
+            if stream.rate == 0 {
+                return Err(TradingPairErrors::ZeroRewardRate)
+            }
+
+            // the rewards the rate can't stream are left unallocated, for the admin to sweep
+            match stream
+                .unallocated
+                .checked_add(reward_amount % duration as Balance)
+            {
+                Some(result) => {
+                    stream.unallocated = result;
+                }
+                None => return Err(TradingPairErrors::Overflow),
+            };
+
+            stream.last_update_time = current_timestamp;
+
+            stream.period_finish = current_timestamp + duration;
+
+            self.reward_streams.insert(token, &stream);
+
+            Self::env().emit_event(RewardNotified {
+                token,
+                amount,
+                rate: stream.rate,
+                period_finish: stream.period_finish,
+            });
+
+            Ok(())
+        }
+
+        /// function to add caller to the LP incentive program
+        fn update_incentive_program(&mut self, caller: AccountId) -> Result<(), TradingPairErrors> {
+            // calc how many tokens to give in a day
+            self.update_daily_incentive(caller)?;
+
+            self.last_redeemed
+                .insert(caller, &self.get_current_timestamp());
+
+            Ok(())
+        }
+
+        /// function to set the daily incentive of an account from its pooled PSP22, boosted for its locked shares.
+        fn update_daily_incentive(&mut self, account: AccountId) -> Result<(), TradingPairErrors> {
+            let account_shares_balance: Balance = self.get_lp_token_of(account);
+
+            let mut psp22_amount_to_give_each_day: Balance = 0;
+
+            let mut psp22_boost_each_day: Balance = 0;
+
+            let mut commitment_boost_each_day: Balance = 0;
+
+            if account_shares_balance > 0 {
+                // amount of PSP22 to give to the account without LP fee
+                let account_locked_psp22_balance =
+                    self.get_psp22_withdraw_tokens_amount(account_shares_balance)?;
+
+                let base_amount_to_give_each_day =
+                    self.get_daily_incentive(account_locked_psp22_balance)?;
+
+                let lp_lock = self.get_lp_lock(account);
+
+                // boost of the locked part of the account shares
+                match (U256::from(base_amount_to_give_each_day)
+                    * U256::from(lp_lock.shares)
+                    * U256::from(lp_lock.boost_bps))
+                .checked_div(U256::from(account_shares_balance) * U256::from(10000u128))
+                .and_then(|result| Balance::try_from(result).ok())
+                {
+                    Some(result) => {
+                        psp22_boost_each_day = result;
+                    }
+                    None => return Err(TradingPairErrors::Overflow),
+                };
+
+                // boost of the account's staked or locked PANX, refreshed at every redeem
+                commitment_boost_each_day =
+                    self.get_commitment_boost_each_day(account, base_amount_to_give_each_day)?;
+
+                match base_amount_to_give_each_day
+                    .checked_add(psp22_boost_each_day)
+                    .and_then(|result| result.checked_add(commitment_boost_each_day))
+                {
+                    Some(result) => {
+                        psp22_amount_to_give_each_day = result;
+                    }
+                    None => return Err(TradingPairErrors::Overflow),
+                };
+            }
+
+            // insert the daily amount of PSP22 tokens to give to the account
+            self.psp22_to_give_in_a_day
+                .insert(account, &psp22_amount_to_give_each_day);
+
+            self.psp22_boost_in_a_day
+                .insert(account, &psp22_boost_each_day);
+
+            self.psp22_commitment_boost_in_a_day
+                .insert(account, &commitment_boost_each_day);
+
+            Ok(())
+        }
+
+        /// function to get the daily boost of an account's staked or locked PANX on its daily incentive without boosts.
+        fn get_commitment_boost_each_day(
+            &self,
+            account: AccountId,
+            base_amount_to_give_each_day: Balance,
+        ) -> Result<Balance, TradingPairErrors> {
+            match (U256::from(base_amount_to_give_each_day)
+                * U256::from(self.get_incentive_boost_bps_of(account)))
+            .checked_div(U256::from(10000u128))
+            .and_then(|result| Balance::try_from(result).ok())
+            {
+                Some(result) => Ok(result),
+                None => Err(TradingPairErrors::Overflow),
+            }
+        }
+
+        /// function to replace the PANX boost in the daily incentive of an account by the boost of its current staked or locked PANX.
+        /// with `lower_only`, a higher boost is left for a later refresh, so that only a lower one applies to the time already accrued.
+        fn refresh_commitment_boost(
+            &mut self,
+            account: AccountId,
+            lower_only: bool,
+        ) -> Result<(), TradingPairErrors> {
+            let psp22_to_give_each_day: Balance =
+                self.psp22_to_give_in_a_day.get(account).unwrap_or(0);
+
+            let commitment_boost_each_day: Balance = self
+                .psp22_commitment_boost_in_a_day
+                .get(account)
+                .unwrap_or(0);
+
+            // the daily incentive without the LP lock and PANX boosts
+            let base_amount_to_give_each_day = psp22_to_give_each_day
+                .saturating_sub(self.psp22_boost_in_a_day.get(account).unwrap_or(0))
+                .saturating_sub(commitment_boost_each_day);
+
+            let new_commitment_boost_each_day =
+                self.get_commitment_boost_each_day(account, base_amount_to_give_each_day)?;
+
+            if new_commitment_boost_each_day == commitment_boost_each_day
+                || (lower_only && new_commitment_boost_each_day > commitment_boost_each_day)
+            {
+                return Ok(())
+            }
+
+            match psp22_to_give_each_day
+                .saturating_sub(commitment_boost_each_day)
+                .checked_add(new_commitment_boost_each_day)
+            {
+                Some(result) => {
+                    self.psp22_to_give_in_a_day.insert(account, &result);
+                }
+                None => return Err(TradingPairErrors::Overflow),
+            };
+
+            self.psp22_commitment_boost_in_a_day
+                .insert(account, &new_commitment_boost_each_day);
+
+            Ok(())
+        }
+
+        /// function to get the daily incentive program PSP22 for an amount of pooled PSP22 (2% a year).
+        fn get_daily_incentive(
+            &self,
+            locked_psp22_balance: Balance,
+        ) -> Result<Balance, TradingPairErrors> {
+            const STAKING_PERCENTAGE: Balance = 2; // Percentage of fees to be distributed as staking rewards
+
+            match locked_psp22_balance.checked_mul(STAKING_PERCENTAGE) {
+                Some(result) => Ok(result / 100u128 / 365),
+                None => Err(TradingPairErrors::Overflow),
+            }
+        }
+
+        /// function to get caller redeemable amount of pooled PSP22
+        #[ink(message)]
+        pub fn get_psp22_redeemable_amount(&self) -> Result<Balance, TradingPairErrors> {
+            self.get_psp22_redeemable_amount_of(self.env().caller())
+        }
+
+        /// function to get the redeemable amount of pooled PSP22 of a given account
+        fn get_psp22_redeemable_amount_of(
+            &self,
+            caller: AccountId,
+        ) -> Result<Balance, TradingPairErrors> {
+            // current timestamp
+            let current_tsp = self.get_current_timestamp();
+
+            let account_shares_balance: Balance = self.balances.get(&caller).unwrap_or(0);
+
+            // amount of PSP22 to give to the caller without LP fee
+            let caller_locked_psp22_balance = self
+                .get_psp22_withdraw_tokens_amount(account_shares_balance)
+                .unwrap_or(0);
+
+            // last time caller redeemed tokens
+            let last_redeemed: u64 = self.last_redeemed.get(caller).unwrap_or(0);
+
+            // the amount of daily PSP22 tokens to give ot the caller
+            let psp22_to_give_each_day: Balance =
+                self.psp22_to_give_in_a_day.get(caller).unwrap_or(0);
+
+            // Declare a variable to hold the difference in days between current timestamp and last redeemed timestamp
+            let days_difference: u64;
+
+            // Calculate the difference in days by dividing the difference between current timestamp and last redeemed timestamp by 86400 (number of seconds in a day)
+            match current_tsp.checked_sub(last_redeemed) {
+                Some(result) => {
+                    days_difference = result / 86400;
+                }
+                None => {
+                    // the last redeem can't be in the future
+                    return Err(TradingPairErrors::Underflow)
+                }
+            };
+
+            // making sure that caller has more then 0 pooled PSP22 tokens
+            if caller_locked_psp22_balance <= 0 {
+                return Err(TradingPairErrors::CallerInsufficientPSP22Balance)
+            }
+
+            // making sure that caller has more than 0 daily PSP22 tokens to claim
+            if psp22_to_give_each_day <= 0 {
+                return Err(TradingPairErrors::ZeroDailyPSP22)
+            }
+
+            let psp22_redeemable_amount: Balance;
+
+            // The amount of PSP22 tokens and AZERO to give to the caller
+            match psp22_to_give_each_day.checked_mul(days_difference as u128) {
+                Some(result) => {
+                    psp22_redeemable_amount = result;
+                }
+                None => return Err(TradingPairErrors::Overflow),
+            };
+
+            // the lock boost is not earned for the days after the lock expired
+            let unlock_timestamp = self.lp_locks.get(caller).unwrap_or_default().unlock_timestamp;
+
+            let days_without_boost: u64 =
+                current_tsp.saturating_sub(unlock_timestamp.max(last_redeemed)) / 86400;
+
+            let psp22_boost_each_day: Balance = self.psp22_boost_in_a_day.get(caller).unwrap_or(0);
+
+            match psp22_boost_each_day
+                .checked_mul(days_without_boost as u128)
+                .and_then(|result| psp22_redeemable_amount.checked_sub(result))
+            {
+                Some(result) => Ok(result),
+                None => Err(TradingPairErrors::Overflow),
+            }
+        }
+
+        /// function for caller to redeem LP incentive tokens.
+        #[ink(message)]
+        pub fn redeem_redeemable_amount(&mut self) -> Result<(), TradingPairErrors> {
+            // making sure that the pair is not re-entered while this message runs
+            self.lock_reentrancy()?;
+
+            // pool reserves and LP supply before the payout, for the invariant check
+            let reserves_before = self.get_reserves_and_supply();
+
+            let psp22_incentive_paid = self.redeem_caller_redeemable_amount()?;
+
+            self.check_incentive_payout(reserves_before, psp22_incentive_paid)?;
+
+            // the incentive program pays from the pooled PSP22
+            self.sync_reserves();
+
+            self.unlock_reentrancy();
+
+            Ok(())
+        }
+
+        /// function to transfer the caller's redeemable LP incentive tokens, used by `redeem_redeemable_amount` and `remove_lp`.
+        /// returns the amount of pooled PSP22 paid to the caller.
+        fn redeem_caller_redeemable_amount(&mut self) -> Result<Balance, TradingPairErrors> {
+            // caller address
+            let caller = self.env().caller();
+            // caller timestamp
+            let current_tsp = self.get_current_timestamp();
+
+            // unstaked PANX or decayed vePANX lowers the boost of the time since the last redeem
+            self.refresh_commitment_boost(caller, true)?;
+
+            let psp22_redeemable_amount = self.get_psp22_redeemable_amount().unwrap_or(0);
+
+            //if psp22_redeemable_amount == 0 {
+            //    return Err(TradingPairErrors::ZeroRedeemableAmount)
+            //}
+
+            let current_account_overall_psp22_staking_rewards = self
+                .account_overall_staking_rewards
+                .get(&caller)
+                .unwrap_or(0);
+
+            let new_account_overall_psp22_staking_rewards: Balance;
+
+            match current_account_overall_psp22_staking_rewards.checked_add(psp22_redeemable_amount) {
+                Some(result) => {
+                    new_account_overall_psp22_staking_rewards = result;
+                }
+                None => return Err(TradingPairErrors::Overflow),
+            };
+
+            self.account_overall_staking_rewards
+                .insert(&caller, &new_account_overall_psp22_staking_rewards);
+
+            // Making sure to set his last redeem to current timestamp
+            self.last_redeemed.insert(caller, &current_tsp);
+
+            // removing the boost of an expired lock from the daily incentive
+            let psp22_boost_each_day: Balance = self.psp22_boost_in_a_day.get(caller).unwrap_or(0);
+
+            if psp22_boost_each_day > 0 && self.get_lp_lock(caller).shares == 0 {
+                let psp22_to_give_each_day: Balance =
+                    self.psp22_to_give_in_a_day.get(caller).unwrap_or(0);
+
+                self.psp22_to_give_in_a_day.insert(
+                    caller,
+                    &psp22_to_give_each_day.saturating_sub(psp22_boost_each_day),
+                );
+
+                self.psp22_boost_in_a_day.insert(caller, &0);
+            }
+
+            // a higher PANX boost only applies from now on
+            self.refresh_commitment_boost(caller, false)?;
+
+            // cross contract call to PSP22 contract to transfer PSP22 to caller
+            if let Err(error) =
+                PSP22Ref::transfer(&self.psp22_token, caller, psp22_redeemable_amount, vec![])
+            {
+                return Err(TradingPairErrors::PSP22TransferFailed(error))
+            }
+
+            Ok(psp22_redeemable_amount)
+        }
+
+        /// function to get the pool reserves and LP supply, for the invariant checks.
+        fn get_reserves_and_supply(&self) -> (Balance, Balance, Balance) {
+            (
+                self.get_a0_balance(),
+                self.get_psp22_balance(),
+                self.total_supply,
+            )
+        }
+
+        /// function to check that an incentive payout decreased the reserves backing each LP share by no more than the paid PSP22.
+        /// the incentive program pays from the pooled PSP22, so only that amount is allowed to leave the pool.
+        fn check_incentive_payout(
+            &self,
+            reserves_before: (Balance, Balance, Balance),
+            psp22_incentive_paid: Balance,
+        ) -> Result<(), TradingPairErrors> {
+            if !is_incentive_payout_kept(
+                reserves_before,
+                self.get_reserves_and_supply(),
+                psp22_incentive_paid,
+            ) {
+                return Err(TradingPairErrors::InvariantViolated)
+            }
+
+            Ok(())
+        }
+
+        /// function to reduce the incentive program rewards allocation after LP removal.
+        /// returns the amount of pooled PSP22 paid to the caller.
+        fn remove_lp(&mut self, new_shares: Balance) -> Result<Balance, TradingPairErrors> {
+            // caller address
+            let caller = self.env().caller();
+
+            let psp22_incentive_paid: Balance;
+
+            match self.redeem_caller_redeemable_amount() {
+                Ok(result) => {
+                    psp22_incentive_paid = result;
+                }
+                Err(_) => return Err(TradingPairErrors::RemoveLpIncentiveProgramError),
+            };
+
+            if new_shares == 0 {
+                // insert the daily amount of PSP22 and AZERO tokens to give to the caller
+                self.psp22_to_give_in_a_day.insert(caller, &0);
+
+                self.psp22_boost_in_a_day.insert(caller, &0);
+
+                self.psp22_commitment_boost_in_a_day.insert(caller, &0);
+            }
+
+            if new_shares > 0 {
+                // calculating the amount of daily PSP22 to give to the user with the remaining shares
+                self.update_daily_incentive(caller)?;
+            }
+
+            Ok(psp22_incentive_paid)
+        }
+
+        /// function to get the amount of tokens to give to caller each day.
+        #[ink(message)]
+        pub fn get_amount_to_give_each_day_to_caller(&self, caller: AccountId) -> Balance {
+            let psp22_daily_amount: Balance = self.psp22_to_give_in_a_day.get(&caller).unwrap_or(0);
+
+            psp22_daily_amount
+        }
+
+        #[ink(message)]
+        pub fn get_generated_lp_fees(&self) -> (Balance, Balance) {
+            let psp22_lp_fees: Balance = self.psp22_lp_fee_vault;
+            let azero_lp_fees: Balance = self.azero_lp_fee_vault;
+
+            (psp22_lp_fees, azero_lp_fees)
+        }
+
+        #[ink(message)]
+        pub fn get_account_overall_staking_rewards(&self, owner: AccountId) -> Balance {
+            let psp22_overall_amount = self
+                .account_overall_staking_rewards
+                .get(&owner)
+                .unwrap_or(0);
+
+            psp22_overall_amount
+        }
+
+        #[ink(message)]
+        pub fn get_account_overall_lp_fee_rewards(&self, owner: AccountId) -> (Balance, Balance) {
+            let (psp22_overall_amount, azero_overall_amount) = self
+                .account_overall_lp_fee_rewards
+                .get(&owner)
+                .unwrap_or((0, 0));
+
+            (psp22_overall_amount, azero_overall_amount)
+        }
+
+        // function to get the contract's overall generated LP fees
+        #[ink(message)]
+        pub fn get_contract_overall_generated_fee(&self) -> (Balance, Balance) {
+            let psp22_lp_fees: Balance = self.contract_overall_generated_psp22_fee;
+            let azero_lp_fees: Balance = self.contract_overall_generated_azero_fee;
+
+            (psp22_lp_fees, azero_lp_fees)
+        }
+
+        /// function to get the pool reserves, supply, fees and price in a single query
+        #[ink(message)]
+        pub fn get_pool_state(&self) -> PoolState {
+            let a0_reserve = self.get_a0_balance();
+
+            let psp22_reserve = self.get_psp22_balance();
+
+            // mid-price of one PSP22 token (10^12 units) in AZERO
+            let price_for_one_psp22: Balance = (U256::from(a0_reserve)
+                * U256::from(10u128.pow(12)))
+            .checked_div(U256::from(psp22_reserve))
+            .map(|price| Balance::try_from(price).unwrap_or(Balance::MAX))
+            .unwrap_or(0);
+
+            PoolState {
+                psp22_token: self.psp22_token,
+                a0_reserve,
+                psp22_reserve,
+                total_supply: self.total_supply,
+                fee: self.fee,
+                psp22_lp_fee_vault: self.psp22_lp_fee_vault,
+                azero_lp_fee_vault: self.azero_lp_fee_vault,
+                overall_generated_psp22_fee: self.contract_overall_generated_psp22_fee,
+                overall_generated_azero_fee: self.contract_overall_generated_azero_fee,
+                price_for_one_psp22,
+                transactions_num: self.transasction_number,
+                lp_lock_timestamp: self.lp_lock_timestamp,
+            }
+        }
+
+        /// function to get the LP position of an account in a single query
+        #[ink(message)]
+        pub fn get_position(&self, account: AccountId) -> Result<Position, TradingPairErrors> {
+            let shares: Balance = self.balances.get(&account).unwrap_or(0);
+
+            let mut a0_amount: Balance = 0;
+            let mut psp22_amount: Balance = 0;
+            let mut pending_a0_lp_fee: Balance = 0;
+            let mut pending_psp22_lp_fee: Balance = 0;
+
+            if shares > 0 {
+                (a0_amount, psp22_amount) = self.get_withdraw_tokens_amount_with_lp(shares)?;
+                pending_a0_lp_fee = self.get_a0_lp_fee_tokens(shares)?;
+                pending_psp22_lp_fee = self.get_psp22_lp_fee_tokens(shares)?;
+            }
+
+            let psp22_daily_incentive: Balance =
+                self.psp22_to_give_in_a_day.get(&account).unwrap_or(0);
+
+            // accounts without pooled PSP22 or daily incentive have nothing to redeem
+            let pending_psp22_incentive: Balance =
+                self.get_psp22_redeemable_amount_of(account).unwrap_or(0);
+
+            let lp_lock = self.get_lp_lock(account);
+
+            Ok(Position {
+                account,
+                shares,
+                a0_amount,
+                psp22_amount,
+                pending_a0_lp_fee,
+                pending_psp22_lp_fee,
+                pending_psp22_incentive,
+                psp22_daily_incentive,
+                is_locked: lp_lock.shares > 0,
+                locked_shares: lp_lock.shares,
+                lock_until: lp_lock.unlock_timestamp,
+                lock_boost_bps: lp_lock.boost_bps,
+                pending_rewards: self
+                    .reward_tokens
+                    .iter()
+                    .map(|token| (*token, self.get_pending_reward(account, *token)))
+                    .collect(),
+            })
+        }
+
+        /// function to add shares to the LP lock of an account, the lock ends at the later of its current and the given timestamp.
+        fn add_to_lp_lock(
+            &mut self,
+            account: AccountId,
+            shares: Balance,
+            unlock_timestamp: u64,
+            boosted: bool, // false for the deployer's mandatory lock
+        ) -> Result<(), TradingPairErrors> {
+            // an expired lock no longer holds any shares
+            let current_lock = self.get_lp_lock(account);
+
+            let new_locked_shares: Balance;
+
+            match current_lock.shares.checked_add(shares) {
+                Some(result) => {
+                    new_locked_shares = result;
+                }
+                None => return Err(TradingPairErrors::Overflow),
+            };
+
+            // locking more shares than the account holds
+            if new_locked_shares > self.get_lp_token_of(account) {
+                return Err(TradingPairErrors::CallerInsufficientLPBalance)
+            }
+
+            let new_unlock_timestamp = current_lock.unlock_timestamp.max(unlock_timestamp);
+
+            let new_boost_bps: Balance;
+
+            if boosted {
+                new_boost_bps = self.get_lp_lock_boost_bps(new_unlock_timestamp);
+            } else {
+                // shares of the mandatory lock don't earn a boost, they dilute the boost of the locked shares
+                match (U256::from(current_lock.boost_bps) * U256::from(current_lock.shares))
+                    .checked_div(U256::from(new_locked_shares))
+                    .and_then(|result| Balance::try_from(result).ok())
+                {
+                    Some(result) => {
+                        new_boost_bps = result;
+                    }
+                    None => return Err(TradingPairErrors::Overflow),
+                };
+            }
+
+            self.set_lp_lock(
+                account,
+                LpLock {
+                    shares: new_locked_shares,
+                    unlock_timestamp: new_unlock_timestamp,
+                    boost_bps: new_boost_bps,
+                },
+            );
+
+            Ok(())
+        }
+
+        /// function to store the LP lock of an account and emit it.
+        fn set_lp_lock(&mut self, account: AccountId, lp_lock: LpLock) {
+            Self::env().emit_event(LpLocked {
+                owner: account,
+                shares: lp_lock.shares,
+                unlock_timestamp: lp_lock.unlock_timestamp,
+                boost_bps: lp_lock.boost_bps,
+            });
+
+            self.lp_locks.insert(account, &lp_lock);
+        }
+
+        /// function to get the incentive boost of a lock ending at `unlock_timestamp`, growing linearly up to one year.
+        #[ink(message)]
+        pub fn get_lp_lock_boost_bps(&self, unlock_timestamp: u64) -> Balance {
+            let lock_duration = unlock_timestamp
+                .saturating_sub(self.get_current_timestamp())
+                .min(MAX_LP_LOCK_BOOST_DURATION);
+
+            // lock_duration is capped, the product can't overflow
+            MAX_LP_LOCK_BOOST_BPS * lock_duration as Balance / MAX_LP_LOCK_BOOST_DURATION as Balance
+        }
+
+        /// function for caller to lock LP shares until `unlock_timestamp` in exchange for a boosted incentive.
+        /// shares added to an existing lock share its unlock timestamp, the later of the two.
+        #[ink(message)]
+        pub fn lock_lp(
+            &mut self,
+            shares: Balance,
+            unlock_timestamp: u64,
+        ) -> Result<(), TradingPairErrors> {
+            // making sure that the pair is not re-entered while this message runs
+            self.lock_reentrancy()?;
+
+            let caller = self.env().caller();
+
+            // throw error is the caller tries to lock 0 LP shares
+            if shares == 0 {
+                return Err(TradingPairErrors::ZeroSharesGiven)
+            }
+
+            if unlock_timestamp <= self.get_current_timestamp() {
+                return Err(TradingPairErrors::InvalidLockTimestamp)
+            }
+
+            // pool reserves and LP supply before the payout, for the invariant check
+            let reserves_before = self.get_reserves_and_supply();
+
+            // paying the incentive earned so far at the current rate
+            let psp22_incentive_paid = self.redeem_caller_redeemable_amount()?;
+
+            self.check_incentive_payout(reserves_before, psp22_incentive_paid)?;
+
+            self.add_to_lp_lock(caller, shares, unlock_timestamp, true)?;
+
+            // boosting the caller's daily incentive
+            self.update_daily_incentive(caller)?;
+
+            // the incentive program pays from the pooled PSP22
+            self.sync_reserves();
+
+            self.unlock_reentrancy();
+
+            Ok(())
+        }
+
+        /// function for caller to move the end of an active LP lock to a later `unlock_timestamp`.
+        #[ink(message)]
+        pub fn extend_lp_lock(&mut self, unlock_timestamp: u64) -> Result<(), TradingPairErrors> {
+            // making sure that the pair is not re-entered while this message runs
+            self.lock_reentrancy()?;
+
+            let caller = self.env().caller();
+
+            let current_lock = self.get_lp_lock(caller);
+
+            if current_lock.shares == 0 {
+                return Err(TradingPairErrors::NoActiveLpLock)
+            }
+
+            if unlock_timestamp <= current_lock.unlock_timestamp {
+                return Err(TradingPairErrors::InvalidLockTimestamp)
+            }
+
+            // pool reserves and LP supply before the payout, for the invariant check
+            let reserves_before = self.get_reserves_and_supply();
+
+            // paying the incentive earned so far at the current rate
+            let psp22_incentive_paid = self.redeem_caller_redeemable_amount()?;
+
+            self.check_incentive_payout(reserves_before, psp22_incentive_paid)?;
+
+            self.set_lp_lock(
+                caller,
+                LpLock {
+                    shares: current_lock.shares,
+                    unlock_timestamp,
+                    boost_bps: self.get_lp_lock_boost_bps(unlock_timestamp),
+                },
+            );
+
+            // boosting the caller's daily incentive
+            self.update_daily_incentive(caller)?;
+
+            // the incentive program pays from the pooled PSP22
+            self.sync_reserves();
+
+            self.unlock_reentrancy();
+
+            Ok(())
+        }
+
+        /// function to get the LP shares of an account that are not locked.
+        fn get_unlocked_shares_of(&self, account: AccountId) -> Balance {
+            self.get_lp_token_of(account)
+                .saturating_sub(self.get_lp_lock(account).shares)
+        }
+
+        /// function to get the active LP lock of an account (empty once the lock expired).
+        #[ink(message)]
+        pub fn get_lp_lock(&self, account: AccountId) -> LpLock {
+            let lp_lock = self.lp_locks.get(account).unwrap_or_default();
+
+            if lp_lock.unlock_timestamp <= self.get_current_timestamp() {
+                return LpLock::default()
+            }
+
+            lp_lock
+        }
+
+        /// function to get the pooled AZERO and PSP22 (LP fees included) backing the locked LP shares of an account.
+        #[ink(message)]
+        pub fn get_locked_liquidity(
+            &self,
+            account: AccountId,
+        ) -> Result<(Balance, Balance), TradingPairErrors> {
+            let locked_shares = self.get_lp_lock(account).shares;
+
+            if locked_shares == 0 {
+                return Ok((0, 0))
+            }
+
+            self.get_withdraw_tokens_amount_with_lp(locked_shares)
+        }
+
+        /// function to get AzeroTradingPair contract address (self)
+        #[ink(message)]
+        pub fn get_account_id(&self) -> AccountId {
+            Self::env().account_id()
+        }
+
+        #[ink(message)]
+        pub fn get_deployer_account(&self) -> AccountId {
+            self.deployer
+        }
+
+        /// function to get the account allowed to rescue tokens sent to the pair
+        #[ink(message)]
+        pub fn get_admin(&self) -> AccountId {
+            self.admin
+        }
+
+        /// function to get the address of the pooled PSP22 token
+        #[ink(message)]
+        pub fn get_psp22_token(&self) -> AccountId {
+            self.psp22_token
+        }
+
+        /// function to get AzeroTradingPair contract address (self)
+        #[ink(message)]
+        pub fn get_caller_id(&self) -> AccountId {
+            self.env().caller()
+        }
+
+        /// function to fetch current price for one PSP22
+        #[ink(message)]
+        pub fn get_current_price(&self) -> Result<Balance, TradingPairErrors> {
+            let current_price = self.get_est_price_psp22_to_a0(1u128 * 10u128.pow(12))?;
+
+            Ok(current_price)
+        }
+
+        /// function to get total supply of LP shares
+        #[ink(message)]
+        pub fn get_total_supply(&self) -> Balance {
+            self.total_supply
+        }
+
+        /// function to get trading contract AZERO balance (pending AZERO claims and vault fees excluded)
+        #[ink(message)]
+        pub fn get_a0_balance(&self) -> Balance {
+            let a0_balance = self
+                .env()
+                .balance()
+                .saturating_sub(self.total_pending_a0_claims)
+                .saturating_sub(self.pending_vault_a0);
+            a0_balance
+        }
+
+        /// function to get shares of specific account
+        #[ink(message)]
+        pub fn get_lp_token_of(&self, account: AccountId) -> Balance {
+            self.balances.get(&account).unwrap_or(0)
+        }
+
+        // function to get contract PSP22 reserve (self, vault fees excluded)
+        #[ink(message)]
+        pub fn get_psp22_balance(&self) -> Balance {
+            let psp22_balance: Balance =
+                PSP22Ref::balance_of(&self.psp22_token, Self::env().account_id())
+                    .saturating_sub(self.pending_vault_psp22);
+            psp22_balance
+        }
+
+        /// function to get current fee
+        #[ink(message)]
+        pub fn get_fee(&self) -> Balance {
+            let fee: Balance = self.fee;
+            fee
+        }
+
+        // function to get the total number of swaps
+        #[ink(message)]
+        pub fn get_transactions_num(&self) -> i64 {
+            self.transasction_number
+        }
+
+        /// function to calculate the percentage between values.
+        #[ink(message)]
+        pub fn check_difference(
+            &self,
+            value1: Balance,
+            value2: Balance,
+        ) -> Result<Balance, TradingPairErrors> {
+            if value1 == value2 {
+                return Ok(0)
+            }
+
+            // the difference is relative to the smaller value
+            let smaller_value: Balance = self._min(value1, value2);
+
+            let percentage_difference: U256;
+
+            match (U256::from(value1.abs_diff(value2)) * U256::from(10u128.pow(12)))
+                .checked_div(U256::from(smaller_value))
+            {
+                Some(result) => {
+                    percentage_difference = result * U256::from(100u128);
+                }
+                None => return Err(TradingPairErrors::DivisionByZero),
+            };
+
+            match Balance::try_from(percentage_difference) {
+                Ok(result) => Ok(result),
+                Err(_) => Err(TradingPairErrors::Overflow),
+            }
+        }
+
+        #[ink(message)]
+        pub fn get_psp22_amount_for_lp(
+            &self,
+            a0_deposit_amount: Balance,
+            a0_contract_balance: Balance,
+        ) -> Result<Balance, TradingPairErrors> {
+            self.get_deposit_amount_for_lp(
+                a0_deposit_amount,
+                a0_contract_balance,
+                self.get_psp22_balance(),
+            )
+        }
+
+        #[ink(message)]
+        pub fn get_a0_amount_for_lp(
+            &self,
+            psp22_deposit_amount: Balance,
+            a0_contract_balance: Balance,
+        ) -> Result<Balance, TradingPairErrors> {
+            self.get_deposit_amount_for_lp(
+                psp22_deposit_amount,
+                self.get_psp22_balance(),
+                a0_contract_balance,
+            )
+        }
+
+        /// function to get the amount of one asset matching `deposit_amount` of the other at the current pool ratio.
+        fn get_deposit_amount_for_lp(
+            &self,
+            deposit_amount: Balance,
+            deposit_reserve: Balance,
+            matching_reserve: Balance,
+        ) -> Result<Balance, TradingPairErrors> {
+            let price: U256;
+
+            // price of one deposited token (10^12 units) in the matching asset
+            match (U256::from(matching_reserve) * U256::from(10u128.pow(12)))
+                .checked_div(U256::from(deposit_reserve))
+            {
+                Some(result) => {
+                    price = result;
+                }
+                None => return Err(TradingPairErrors::DivisionByZero),
+            };
+
+            let matching_amount: U256;
+
+            match price.checked_mul(U256::from(deposit_amount)) {
+                Some(result) => {
+                    matching_amount = result / U256::from(10u128.pow(12));
+                }
+                None => return Err(TradingPairErrors::Overflow),
+            };
+
+            match Balance::try_from(matching_amount) {
+                Ok(result) => Ok(result),
+                Err(_) => Err(TradingPairErrors::Overflow),
+            }
+        }
+
+        /// function to get current timpstamp in seconds
+        #[ink(message)]
+        pub fn get_current_timestamp(&self) -> u64 {
+            let time_stamp_in_seconds = self.env().block_timestamp() / 1000;
+            time_stamp_in_seconds
+        }
+
+        /// function to get LP lock timestamp
+        #[ink(message)]
+        pub fn get_lp_lock_timestamp(&self) -> u64 {
+            self.lp_lock_timestamp
+        }
+
+        /// function to get LP lock timestamp
+        fn _min(&self, value1: Balance, value2: Balance) -> Balance {
+            if value1 < value2 {
+                return value1
+            } else {
+                return value2
+            }
+        }
+    }
+
+    /// Xorshift generator so that the randomized sequences of the unit and E2E tests are reproducible.
+    #[cfg(test)]
+    struct Rng(u64);
+
+    #[cfg(test)]
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        /// Random amount between 1 and `max`.
+        fn amount(&mut self, max: Balance) -> Balance {
+            1 + Balance::from(self.next()) % max.max(1)
+        }
+    }
+
+    /// Unit tests of the pool math
+    ///
+    /// cargo test
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn new_trading_pair(fee: Balance) -> TradingPairAzero {
+            TradingPairAzero::new(
+                AccountId::from([0x01; 32]),
+                fee,
+                AccountId::from([0x02; 32]),
+                AccountId::from([0x03; 32]),
+                0,
+                AccountId::from([0x04; 32]),
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice,
+            )
+            .unwrap()
+        }
+
+        #[ink::test]
+        fn fees_above_the_maximum_are_rejected() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            // above the cap, the whole amount in, and a fee still scaled by 10^12
+            for fee in [MAX_FEE + 1, FEE_DENOMINATOR, 10u128.pow(12)] {
+                assert_eq!(
+                    TradingPairAzero::new(
+                        AccountId::from([0x01; 32]),
+                        fee,
+                        AccountId::from([0x02; 32]),
+                        AccountId::from([0x03; 32]),
+                        0,
+                        AccountId::from([0x04; 32]),
+                        accounts.alice,
+                    )
+                    .err(),
+                    Some(TradingPairErrors::InvalidFee)
+                );
+            }
+
+            assert_eq!(new_trading_pair(MAX_FEE).get_fee(), MAX_FEE);
+        }
+
+        #[ink::test]
+        fn invariant_checks_reject_value_loss() {
+            assert!(is_constant_product_kept(100, 100, 101, 99));
+            assert!(!is_constant_product_kept(100, 100, 100, 99));
+
+            assert!(are_reserves_per_share_kept((100, 100, 10), (50, 50, 5)));
+            assert!(!are_reserves_per_share_kept((100, 100, 10), (50, 49, 5)));
+            assert!(are_reserves_per_share_kept((100, 100, 10), (0, 0, 0)));
+
+            assert!(is_incentive_payout_kept((100, 100, 10), (100, 90, 10), 10));
+            assert!(!is_incentive_payout_kept((100, 100, 10), (100, 89, 10), 10));
+            assert!(!is_incentive_payout_kept((100, 100, 10), (100, 0, 10), 101));
+        }
+
+        #[ink::test]
+        fn pool_math_returns_errors_instead_of_trapping() {
+            let trading_pair = new_trading_pair(10000);
+
+            assert_eq!(trading_pair.check_difference(10, 10), Ok(0));
+            assert_eq!(trading_pair.check_difference(100, 110), Ok(10 * 10u128.pow(12)));
+            assert_eq!(
+                trading_pair.check_difference(0, 10),
+                Err(TradingPairErrors::DivisionByZero)
+            );
+
+            assert_eq!(
+                trading_pair.get_share_of_reserve(1, 100, 0),
+                Err(TradingPairErrors::DivisionByZero)
+            );
+            assert_eq!(
+                trading_pair.get_share_of_reserve(Balance::MAX, Balance::MAX, 1),
+                Err(TradingPairErrors::Overflow)
+            );
+
+            assert_eq!(
+                trading_pair.get_amount_out(0, 0, 100, 0),
+                Err(TradingPairErrors::DivisionByZero)
+            );
+            assert_eq!(
+                trading_pair.get_scaled_price(1, 0),
+                Err(TradingPairErrors::DivisionByZero)
+            );
+            assert_eq!(
+                trading_pair.get_daily_incentive(Balance::MAX),
+                Err(TradingPairErrors::Overflow)
+            );
+        }
+
+        #[ink::test]
+        fn price_impact_bps_matches_the_reported_prices() {
+            let trading_pair = new_trading_pair(10000);
+            let mut rng = Rng(0x5eed_1234_abcd_0042);
+
+            for _ in 0..1000 {
+                let reserve_in = rng.amount(10u128.pow(18));
+                let reserve_out = rng.amount(10u128.pow(18));
+                let amount_in = rng.amount(reserve_in);
+
+                let price_impact = trading_pair
+                    .get_price_impact(amount_in, reserve_in, reserve_out, FeeTier::Standard)
+                    .unwrap();
+
+                let quote = trading_pair
+                    .get_swap_quote(amount_in, reserve_in, reserve_out, FeeTier::Standard)
+                    .unwrap();
+
+                if price_impact.mid_price_before == 0 {
+                    assert_eq!(price_impact.price_impact_bps, 0);
+                    continue
+                }
+
+                assert_eq!(
+                    price_impact.price_impact_bps,
+                    (price_impact.mid_price_before - price_impact.execution_price) * 10000
+                        / price_impact.mid_price_before
+                );
+                assert_eq!(price_impact.price_impact_bps, quote.price_impact_bps);
+            }
+        }
+
+        #[ink::test]
+        fn admin_messages_check_the_reentrancy_lock() {
+            let mut trading_pair = new_trading_pair(10000);
+
+            // a message of the pair is already running
+            trading_pair.lock_reentrancy().unwrap();
+
+            assert_eq!(
+                trading_pair.set_discount(None, vec![]),
+                Err(TradingPairErrors::Reentrancy)
+            );
+            assert_eq!(
+                trading_pair.set_protocol_fee(None, 0),
+                Err(TradingPairErrors::Reentrancy)
+            );
+            assert_eq!(
+                trading_pair.add_reward_token(AccountId::from([0x07; 32]), AccountId::from([0x08; 32])),
+                Err(TradingPairErrors::Reentrancy)
+            );
+            assert_eq!(
+                trading_pair.sweep_rewards(
+                    AccountId::from([0x07; 32]),
+                    None,
+                    AccountId::from([0x08; 32])
+                ),
+                Err(TradingPairErrors::Reentrancy)
+            );
+            assert_eq!(
+                trading_pair.set_unclaimable_reward_holder(AccountId::from([0x08; 32]), true),
+                Err(TradingPairErrors::Reentrancy)
+            );
+        }
+
+        #[ink::test]
+        fn transfer_from_errors_keep_their_cause() {
+            assert_eq!(check_transfer_from_result(Ok(Ok(Ok(())))), Ok(()));
+            assert_eq!(
+                check_transfer_from_result(Ok(Ok(Err(PSP22Error::InsufficientAllowance)))),
+                Err(TradingPairErrors::PSP22TransferFromFailed(
+                    PSP22Error::InsufficientAllowance
+                ))
+            );
+            assert_eq!(
+                check_transfer_from_result(Ok(Err(LangError::CouldNotReadInput))),
+                Err(TradingPairErrors::Lang(LangError::CouldNotReadInput))
+            );
+            assert_eq!(
+                check_transfer_from_result(Err(ink::env::Error::CalleeTrapped)),
+                Err(TradingPairErrors::Env(EnvErrorCode::CalleeTrapped))
+            );
+        }
+
+        #[ink::test]
+        fn record_swap_updates_pair_daily_and_account_stats() {
+            let mut trading_pair = new_trading_pair(10000);
+            let trader = AccountId::from([0x05; 32]);
+
+            let swap = SwapStats {
+                a0_volume: 100,
+                psp22_volume: 1000,
+                a0_traders_fee: 1,
+                psp22_traders_fee: 2,
+                a0_lp_fee: 3,
+                psp22_to_a0_swaps: 1,
+                ..Default::default()
+            };
+
+            trading_pair.record_swap(trader, &swap).unwrap();
+            trading_pair.record_swap(trader, &swap).unwrap();
+
+            let expected_stats = swap.checked_add(&swap).unwrap();
+
+            assert_eq!(trading_pair.get_swap_stats(), expected_stats);
+            assert_eq!(
+                trading_pair.get_daily_swap_stats(trading_pair.get_current_day()),
+                expected_stats
+            );
+            assert_eq!(
+                trading_pair.get_daily_swap_stats(trading_pair.get_current_day() + 1),
+                SwapStats::default()
+            );
+            assert_eq!(trading_pair.get_account_swap_volume(trader), (200, 2000));
+
+            let overflowing_swap = SwapStats {
+                a0_volume: Balance::MAX,
+                ..Default::default()
+            };
+
+            assert_eq!(
+                trading_pair.record_swap(trader, &overflowing_swap),
+                Err(TradingPairErrors::Overflow)
+            );
+        }
+
+        #[ink::test]
+        fn lp_lock_boost_scales_with_lock_duration() {
+            let mut trading_pair = new_trading_pair(10000);
+            let owner = AccountId::from([0x05; 32]);
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000_000);
+            let now = trading_pair.get_current_timestamp();
+
+            assert_eq!(trading_pair.get_lp_lock_boost_bps(now), 0);
+            assert_eq!(
+                trading_pair.get_lp_lock_boost_bps(now + MAX_LP_LOCK_BOOST_DURATION / 2),
+                MAX_LP_LOCK_BOOST_BPS / 2
+            );
+            assert_eq!(
+                trading_pair.get_lp_lock_boost_bps(now + 2 * MAX_LP_LOCK_BOOST_DURATION),
+                MAX_LP_LOCK_BOOST_BPS
+            );
+
+            trading_pair.balances.insert(owner, &1000);
+
+            trading_pair
+                .add_to_lp_lock(owner, 400, now + MAX_LP_LOCK_BOOST_DURATION / 4, true)
+                .unwrap();
+
+            // adding shares keeps the later unlock timestamp and its boost
+            trading_pair
+                .add_to_lp_lock(owner, 100, now + 86400, true)
+                .unwrap();
+
+            assert_eq!(
+                trading_pair.get_lp_lock(owner),
+                LpLock {
+                    shares: 500,
+                    unlock_timestamp: now + MAX_LP_LOCK_BOOST_DURATION / 4,
+                    boost_bps: MAX_LP_LOCK_BOOST_BPS / 4,
+                }
+            );
+            assert_eq!(
+                trading_pair.add_to_lp_lock(owner, 501, now + 86400, true),
+                Err(TradingPairErrors::CallerInsufficientLPBalance)
+            );
+        }
+
+        #[ink::test]
+        fn commitment_boost_follows_the_staked_panx() {
+            let mut trading_pair = new_trading_pair(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            // 1000 PSP22 a day, boosted by 100 for an LP lock and by 200 for PANX staked at the last update
+            trading_pair.psp22_to_give_in_a_day.insert(accounts.bob, &1300);
+            trading_pair.psp22_boost_in_a_day.insert(accounts.bob, &100);
+            trading_pair
+                .psp22_commitment_boost_in_a_day
+                .insert(accounts.bob, &200);
+
+            // the PANX is no longer reported by a discount source, the lower boost applies at once
+            trading_pair
+                .refresh_commitment_boost(accounts.bob, true)
+                .unwrap();
+
+            assert_eq!(trading_pair.psp22_to_give_in_a_day.get(accounts.bob), Some(1100));
+            assert_eq!(trading_pair.psp22_boost_in_a_day.get(accounts.bob), Some(100));
+            assert_eq!(
+                trading_pair.psp22_commitment_boost_in_a_day.get(accounts.bob),
+                Some(0)
+            );
+
+            // an account without a boost has nothing to refresh
+            trading_pair
+                .refresh_commitment_boost(accounts.bob, false)
+                .unwrap();
+
+            assert_eq!(trading_pair.psp22_to_give_in_a_day.get(accounts.bob), Some(1100));
+        }
+
+        #[ink::test]
+        fn deployer_mandatory_lock_is_not_boosted() {
+            let mut trading_pair = new_trading_pair(10000);
+            let deployer = AccountId::from([0x05; 32]);
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000_000);
+            let now = trading_pair.get_current_timestamp();
+
+            trading_pair.balances.insert(deployer, &1000);
+
+            // the deployer's liquidity locked until the LP lock timestamp
+            trading_pair
+                .add_to_lp_lock(deployer, 400, now + MAX_LP_LOCK_BOOST_DURATION, false)
+                .unwrap();
+
+            assert_eq!(trading_pair.get_lp_lock(deployer).boost_bps, 0);
+
+            // a lock made by the deployer boosts all the locked shares
+            trading_pair
+                .add_to_lp_lock(deployer, 100, now + MAX_LP_LOCK_BOOST_DURATION, true)
+                .unwrap();
+
+            assert_eq!(
+                trading_pair.get_lp_lock(deployer).boost_bps,
+                MAX_LP_LOCK_BOOST_BPS
+            );
+
+            // later mandatory shares dilute the boost instead of sharing it
+            trading_pair
+                .add_to_lp_lock(deployer, 500, now + MAX_LP_LOCK_BOOST_DURATION, false)
+                .unwrap();
+
+            assert_eq!(
+                trading_pair.get_lp_lock(deployer),
+                LpLock {
+                    shares: 1000,
+                    unlock_timestamp: now + MAX_LP_LOCK_BOOST_DURATION,
+                    boost_bps: MAX_LP_LOCK_BOOST_BPS / 2,
+                }
+            );
+        }
+
+        #[ink::test]
+        fn rescue_token_keeps_the_pool_reserves() {
+            let mut trading_pair = new_trading_pair(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+                trading_pair.get_account_id(),
+                1000,
+            );
+
+            // 10 AZERO sent to the pair outside of the pool
+            trading_pair.reserve_a0 = 990;
+
+            assert_eq!(
+                trading_pair.rescue_token(Some(AccountId::from([0x01; 32])), accounts.bob, 1),
+                Err(TradingPairErrors::CannotRescuePoolToken)
+            );
+
+            // failed messages revert the reentrancy lock on chain, the off-chain environment keeps it
+            trading_pair.unlock_reentrancy();
+
+            assert_eq!(
+                trading_pair.rescue_token(None, accounts.bob, 11),
+                Err(TradingPairErrors::InsufficientRescuableA0)
+            );
+
+            trading_pair.unlock_reentrancy();
+
+            let bob_balance =
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob)
+                    .unwrap();
+
+            trading_pair.rescue_token(None, accounts.bob, 10).unwrap();
+
+            assert_eq!(
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob),
+                Ok(bob_balance + 10)
+            );
+            assert_eq!(trading_pair.get_a0_balance(), trading_pair.reserve_a0);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            assert_eq!(
+                trading_pair.rescue_token(None, accounts.bob, 0),
+                Err(TradingPairErrors::CallerIsNotAdmin)
+            );
+        }
+
+        #[ink::test]
+        fn protocol_fee_mints_a_share_of_the_root_k_growth() {
+            let mut trading_pair = new_trading_pair(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let treasury = AccountId::from([0x06; 32]);
+
+            assert_eq!(get_protocol_fee_shares(100, 100, 100, 500_000), Some(0));
+            assert_eq!(get_protocol_fee_shares(100, 121, 100, 0), Some(0));
+            // 100 * 0.5 * 21 / (121 * 0.5 + 100 * 0.5)
+            assert_eq!(get_protocol_fee_shares(100, 121, 100, 500_000), Some(9));
+
+            assert_eq!(
+                trading_pair.set_protocol_fee(Some(treasury), MAX_PROTOCOL_FEE_SHARE + 1),
+                Err(TradingPairErrors::InvalidProtocolFeeShare)
+            );
+
+            // failed messages revert the reentrancy lock on chain, the off-chain environment keeps it
+            trading_pair.unlock_reentrancy();
+
+            trading_pair
+                .set_protocol_fee(Some(treasury), MAX_PROTOCOL_FEE_SHARE)
+                .unwrap();
+
+            trading_pair.total_supply = 100;
+            trading_pair.reserve_a0 = 100;
+            trading_pair.reserve_psp22 = 100;
+            trading_pair.update_root_k_last();
+
+            assert_eq!(trading_pair.get_root_k_last(), 100);
+
+            // swaps grew the reserves by the LP fee
+            trading_pair.reserve_a0 = 121;
+            trading_pair.reserve_psp22 = 121;
+            trading_pair.mint_protocol_fee().unwrap();
+
+            assert_eq!(trading_pair.get_lp_token_of(treasury), 9);
+            assert_eq!(trading_pair.total_supply, 109);
+
+            trading_pair.set_protocol_fee(None, MAX_PROTOCOL_FEE_SHARE).unwrap();
+
+            assert_eq!(trading_pair.get_protocol_fee(), (None, 0));
+            assert_eq!(trading_pair.get_root_k_last(), 0);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            assert_eq!(
+                trading_pair.set_protocol_fee(Some(treasury), 1),
+                Err(TradingPairErrors::CallerIsNotAdmin)
+            );
+        }
+
+        #[ink::test]
+        fn vault_fees_accrue_outside_of_the_reserves() {
+            let mut trading_pair = new_trading_pair(10000);
+
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+                trading_pair.get_account_id(),
+                1000,
+            );
+
+            assert_eq!(
+                trading_pair.collect_protocol_fees(),
+                Err(TradingPairErrors::ZeroPendingVaultFees)
+            );
+
+            trading_pair.accrue_vault_fees(20, 10).unwrap();
+            trading_pair.accrue_vault_fees(20, 10).unwrap();
+
+            assert_eq!(trading_pair.get_pending_vault_fees(), (20, 40));
+            assert_eq!(trading_pair.get_a0_balance(), 980);
+            assert_eq!(
+                trading_pair.accrue_vault_fees(Balance::MAX, 0),
+                Err(TradingPairErrors::Overflow)
+            );
+        }
+
+        #[ink::test]
+        fn price_cumulatives_accumulate_the_old_reserves_price() {
+            let mut trading_pair = new_trading_pair(10000);
+
+            // 1 PSP22 = 0.1 AZERO until the reserves change, 100 seconds later
+            trading_pair.reserve_a0 = 100;
+            trading_pair.reserve_psp22 = 1000;
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(100_000);
+
+            assert_eq!(
+                trading_pair.get_price_cumulatives(),
+                (100 * 10u128.pow(11), 100 * 10u128.pow(13), 100)
+            );
+            assert_eq!(
+                get_price_cumulative_increase(Balance::MAX, 1, 1),
+                Balance::MAX.wrapping_mul(10u128.pow(12))
+            );
+        }
+
+        #[ink::test]
+        fn discount_tiers_need_a_discount_source() {
+            let mut trading_pair = new_trading_pair(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            // without a discount source every account pays the full LP fee
+            assert_eq!(trading_pair.get_discount_balance_of(accounts.bob), 0);
+            assert_eq!(trading_pair.get_fee_tier_of(Some(accounts.bob)), FeeTier::Standard);
+            assert_eq!(trading_pair.get_incentive_boost_bps_of(accounts.bob), 0);
+
+            // the default tier halves the LP fee
+            assert_eq!(trading_pair.get_lp_fee_for_tier(FeeTier::Discounted(0)), Ok(5000));
+
+            let tiers = vec![
+                DiscountTier {
+                    min_panx: 1000,
+                    discount_bps: 2500,
+                    incentive_boost_bps: 2000,
+                },
+                DiscountTier {
+                    min_panx: 5000,
+                    discount_bps: 5000,
+                    incentive_boost_bps: 5000,
+                },
+            ];
+
+            assert_eq!(
+                trading_pair.set_discount(None, vec![tiers[1].clone(), tiers[0].clone()]),
+                Err(TradingPairErrors::InvalidDiscountTiers)
+            );
+
+            // failed messages revert the reentrancy lock on chain, the off-chain environment keeps it
+            trading_pair.unlock_reentrancy();
+
+            assert_eq!(
+                trading_pair.set_discount(
+                    None,
+                    vec![
+                        tiers[0].clone(),
+                        DiscountTier {
+                            incentive_boost_bps: 1000,
+                            ..tiers[1].clone()
+                        }
+                    ]
+                ),
+                Err(TradingPairErrors::InvalidDiscountTiers)
+            );
+
+            trading_pair.unlock_reentrancy();
+
+            assert_eq!(
+                trading_pair.set_discount(
+                    None,
+                    vec![DiscountTier {
+                        min_panx: 1000,
+                        discount_bps: MAX_DISCOUNT_BPS + 1,
+                        incentive_boost_bps: 0,
+                    }]
+                ),
+                Err(TradingPairErrors::InvalidDiscountTiers)
+            );
+
+            trading_pair.unlock_reentrancy();
+
+            trading_pair.set_discount(None, tiers.clone()).unwrap();
+
+            assert_eq!(trading_pair.get_discount(), (None, tiers));
+            assert_eq!(trading_pair.get_lp_fee_for_tier(FeeTier::Discounted(0)), Ok(7500));
+            assert_eq!(trading_pair.get_lp_fee_for_tier(FeeTier::Discounted(1)), Ok(5000));
+            assert_eq!(trading_pair.get_lp_fee_for_tier(FeeTier::Discounted(2)), Ok(10000));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            assert_eq!(
+                trading_pair.set_discount(None, vec![]),
+                Err(TradingPairErrors::CallerIsNotAdmin)
+            );
         }
 
-        /// function to get AzeroTradingPair contract address (self)
-        #[ink(message)]
-        pub fn get_caller_id(&self) -> AccountId {
-            self.env().caller()
+        #[ink::test]
+        fn self_transfers_keep_the_balance_and_supply() {
+            let mut trading_pair = new_trading_pair(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            // Alice holds 300 LP shares
+            trading_pair.balances.insert(accounts.alice, &300);
+            trading_pair.total_supply = 300;
+
+            psp22::PSP22::transfer(&mut trading_pair, accounts.alice, 200, vec![]).unwrap();
+
+            assert_eq!(psp22::PSP22::balance_of(&trading_pair, accounts.alice), 300);
+            assert_eq!(psp22::PSP22::total_supply(&trading_pair), 300);
+
+            // Bob moves Alice's shares back to Alice with her allowance
+            psp22::PSP22::approve(&mut trading_pair, accounts.bob, 200).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            psp22::PSP22::transfer_from(
+                &mut trading_pair,
+                accounts.alice,
+                accounts.alice,
+                200,
+                vec![],
+            )
+            .unwrap();
+
+            assert_eq!(psp22::PSP22::balance_of(&trading_pair, accounts.alice), 300);
+            assert_eq!(psp22::PSP22::total_supply(&trading_pair), 300);
+            assert_eq!(psp22::PSP22::allowance(&trading_pair, accounts.alice, accounts.bob), 0);
         }
 
-        /// function to fetch current price for one PSP22
-        #[ink(message)]
-        pub fn get_current_price(&self) -> Balance {
-            let current_price = self
-                .get_est_price_psp22_to_a0(1u128 * 10u128.pow(12))
+        #[ink::test]
+        fn streamed_rewards_follow_the_lp_shares() {
+            let mut trading_pair = new_trading_pair(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let reward_token = AccountId::from([0x07; 32]);
+
+            trading_pair
+                .add_reward_token(reward_token, accounts.charlie)
                 .unwrap();
 
-            current_price
-        }
+            assert_eq!(
+                trading_pair.add_reward_token(reward_token, accounts.charlie),
+                Err(TradingPairErrors::RewardTokenAlreadyAdded)
+            );
 
-        /// function to get total supply of LP shares
-        #[ink(message)]
-        pub fn get_total_supply(&self) -> Balance {
-            self.total_supply
-        }
+            // failed messages revert the reentrancy lock on chain, the off-chain environment keeps it
+            trading_pair.unlock_reentrancy();
 
-        /// function to get trading contract AZERO balance
-        #[ink(message)]
-        pub fn get_a0_balance(&self) -> Balance {
-            let mut a0_balance = self.env().balance();
-            a0_balance = a0_balance;
-            a0_balance
-        }
+            assert_eq!(
+                trading_pair.notify_reward(AccountId::from([0x08; 32]), 1000, 86400),
+                Err(TradingPairErrors::UnknownRewardToken)
+            );
 
+            trading_pair.unlock_reentrancy();
 
-        /// function to get shares of specific account
-        #[ink(message)]
-        pub fn get_lp_token_of(&self, account: AccountId) -> Balance {
-            self.balances.get(&account).unwrap_or(0)
-        }
+            assert_eq!(
+                trading_pair.start_reward_stream(reward_token, 1000, 60),
+                Err(TradingPairErrors::InvalidRewardDuration)
+            );
 
-        // function to get contract PSP22 reserve (self)
-        #[ink(message)]
-        pub fn get_psp22_balance(&self) -> Balance {
-            let psp22_balance: Balance =
-                PSP22Ref::balance_of(&self.psp22_token, Self::env().account_id());
-            psp22_balance
-        }
+            // Alice holds 300 LP shares and Bob 100
+            trading_pair.balances.insert(accounts.alice, &300);
+            trading_pair.balances.insert(accounts.bob, &100);
+            trading_pair.total_supply = 400;
 
-        /// function to get current fee
-        #[ink(message)]
-        pub fn get_fee(&self) -> Balance {
-            let fee: Balance = self.fee;
-            fee
-        }
+            // 4 reward tokens every second for a day
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000_000);
+            trading_pair
+                .start_reward_stream(reward_token, 4 * 86400, 86400)
+                .unwrap();
 
-        // function to get the total number of swaps
-        #[ink(message)]
-        pub fn get_transactions_num(&self) -> i64 {
-            self.transasction_number
-        }
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_100_000);
 
-        /// function to calculate the percentage between values.
-        #[ink(message)]
-        pub fn check_difference(
-            &mut self,
-            value1: Balance,
-            value2: Balance,
-        ) -> Result<Balance, TradingPairErrors> {
-            let mut percentage_difference: Balance = 0;
+            assert_eq!(trading_pair.get_pending_reward(accounts.alice, reward_token), 300);
+            assert_eq!(trading_pair.get_pending_reward(accounts.bob, reward_token), 100);
 
-            if value1 > value2 {
-                percentage_difference = (value1 - value2) * (10u128.pow(12));
-                match (percentage_difference / value2).checked_mul(100u128) {
-                    Some(result) => {
-                        percentage_difference = result;
-                    }
-                    None => return Err(TradingPairErrors::Overflow),
-                };
-            }
+            // Alice sends Bob 200 LP shares, the rewards earned so far stay hers
+            psp22::PSP22::transfer(&mut trading_pair, accounts.bob, 200, vec![]).unwrap();
 
-            if value2 > value1 {
-                percentage_difference = (value2 - value1) * (10u128.pow(12));
-                match (percentage_difference / value1).checked_mul(100u128) {
-                    Some(result) => {
-                        percentage_difference = result;
-                    }
-                    None => return Err(TradingPairErrors::Overflow),
-                };
-            }
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_200_000);
 
-            Ok(percentage_difference)
+            assert_eq!(trading_pair.get_pending_reward(accounts.alice, reward_token), 400);
+            assert_eq!(trading_pair.get_pending_reward(accounts.bob, reward_token), 400);
+            assert_eq!(
+                trading_pair.rescue_token(Some(reward_token), accounts.alice, 1),
+                Err(TradingPairErrors::CannotRescueRewardToken)
+            );
         }
 
-        #[ink(message)]
-        pub fn get_psp22_amount_for_lp(
-            &self,
-            a0_deposit_amount: Balance,
-            a0_contract_balance: Balance,
-        ) -> Balance {
+        #[ink::test]
+        fn third_parties_cant_dilute_a_running_stream() {
+            let mut trading_pair = new_trading_pair(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let reward_token = AccountId::from([0x07; 32]);
 
-            let psp22_amount_to_deposit:U256;
+            // Charlie distributes the reward token
+            trading_pair
+                .add_reward_token(reward_token, accounts.charlie)
+                .unwrap();
 
-            psp22_amount_to_deposit = ((U256::from(self.get_psp22_balance()) * (U256::from(10u128.pow(12))))
-                / U256::from(a0_contract_balance)
-                * U256::from(a0_deposit_amount))
-                / (U256::from(10u128.pow(12)));
+            assert_eq!(trading_pair.get_reward_distributor(reward_token), Some(accounts.charlie));
 
-            psp22_amount_to_deposit.as_u128()
-        }
+            // 4 reward tokens every second for a day
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000_000);
+            trading_pair
+                .start_reward_stream(reward_token, 4 * 86400, 86400)
+                .unwrap();
 
-        #[ink(message)]
-        pub fn get_a0_amount_for_lp(
-            &self,
-            psp22_deposit_amount: Balance,
-            a0_contract_balance: Balance,
-        ) -> Balance {
-            let a0_amount_to_deposit:U256;
+            let stream = trading_pair.get_reward_stream(reward_token);
 
-            a0_amount_to_deposit = ((U256::from(a0_contract_balance) * (U256::from(10u128.pow(12))))
-                / U256::from(self.get_psp22_balance())
-                * U256::from(psp22_deposit_amount))
-                / (U256::from(10u128.pow(12)));
+            // Bob tries to spread the rest of the stream over the longest duration with 1 token
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_100_000);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            assert_eq!(
+                trading_pair.notify_reward(reward_token, 1, MAX_REWARD_DURATION),
+                Err(TradingPairErrors::CallerIsNotRewardDistributor)
+            );
 
-            a0_amount_to_deposit.as_u128()
+            assert_eq!(trading_pair.get_reward_stream(reward_token), stream);
         }
 
-        /// function to get current timpstamp in seconds
-        #[ink(message)]
-        pub fn get_current_timestamp(&self) -> u64 {
-            let time_stamp_in_seconds = self.env().block_timestamp() / 1000;
-            time_stamp_in_seconds
+        #[ink::test]
+        fn reward_stream_dust_is_left_unallocated() {
+            let mut trading_pair = new_trading_pair(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let reward_token = AccountId::from([0x07; 32]);
+
+            trading_pair
+                .add_reward_token(reward_token, accounts.charlie)
+                .unwrap();
+
+            // Alice holds all the LP shares
+            trading_pair.balances.insert(accounts.alice, &100);
+            trading_pair.total_supply = 100;
+
+            // 4 reward tokens every second for a day, 100 of them can't be streamed
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000_000);
+            trading_pair
+                .start_reward_stream(reward_token, 4 * 86400 + 100, 86400)
+                .unwrap();
+
+            let stream = trading_pair.get_reward_stream(reward_token);
+
+            assert_eq!(stream.rate, 4);
+            assert_eq!(stream.unallocated, 100);
+
+            // half a day later, the rest of the stream and 50 new tokens are spread over a day
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(44_200_000);
+            trading_pair
+                .start_reward_stream(reward_token, 50, 86400)
+                .unwrap();
+
+            let stream = trading_pair.get_reward_stream(reward_token);
+
+            // 4 * 43200 + 50 tokens, 2 every second and 50 left over
+            assert_eq!(stream.rate, 2);
+            assert_eq!(stream.unallocated, 150);
+            assert_eq!(trading_pair.take_unclaimable_rewards(reward_token, None), Ok(150));
         }
 
-        /// function to get LP lock timestamp
-        #[ink(message)]
-        pub fn get_lp_lock_timestamp(&self) -> u64 {
-            self.lp_lock_timestamp
+        #[ink::test]
+        fn unclaimable_rewards_can_be_swept() {
+            let mut trading_pair = new_trading_pair(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let reward_token = AccountId::from([0x07; 32]);
+            // contract holding LP shares that never claims, e.g. the LP locker
+            let lp_locker = accounts.django;
+
+            trading_pair
+                .add_reward_token(reward_token, accounts.charlie)
+                .unwrap();
+
+            // 4 reward tokens every second for a day, while there are no LP shares yet
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000_000);
+            trading_pair
+                .start_reward_stream(reward_token, 4 * 86400, 86400)
+                .unwrap();
+
+            // after 100 seconds, Alice gets 300 LP shares and the LP locker 100
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_100_000);
+            trading_pair.update_stream_rewards_of(accounts.alice).unwrap();
+            trading_pair.balances.insert(accounts.alice, &300);
+            trading_pair.balances.insert(lp_locker, &100);
+            trading_pair.total_supply = 400;
+
+            assert_eq!(trading_pair.get_reward_stream(reward_token).unallocated, 400);
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_200_000);
+
+            assert_eq!(trading_pair.get_pending_reward(accounts.alice, reward_token), 300);
+            assert_eq!(trading_pair.get_pending_reward(lp_locker, reward_token), 100);
+
+            assert_eq!(
+                trading_pair.sweep_rewards(reward_token, Some(lp_locker), accounts.charlie),
+                Err(TradingPairErrors::NotAnUnclaimableRewardHolder)
+            );
+
+            // failed messages revert the reentrancy lock on chain, the off-chain environment keeps it
+            trading_pair.unlock_reentrancy();
+
+            trading_pair
+                .set_unclaimable_reward_holder(lp_locker, true)
+                .unwrap();
+
+            assert!(trading_pair.is_unclaimable_reward_holder(lp_locker));
+
+            // sweep_rewards transfers what these take out of the stream
+            assert_eq!(
+                trading_pair.take_unclaimable_rewards(reward_token, Some(lp_locker)),
+                Ok(100)
+            );
+            assert_eq!(trading_pair.take_unclaimable_rewards(reward_token, None), Ok(400));
+
+            assert_eq!(trading_pair.get_pending_reward(lp_locker, reward_token), 0);
+            assert_eq!(trading_pair.get_reward_stream(reward_token).unallocated, 0);
+            assert_eq!(
+                trading_pair.sweep_rewards(reward_token, None, accounts.charlie),
+                Err(TradingPairErrors::ZeroRewardsToSweep)
+            );
+
+            trading_pair.unlock_reentrancy();
+
+            // Alice's rewards are not swept
+            assert_eq!(trading_pair.get_pending_reward(accounts.alice, reward_token), 300);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            assert_eq!(
+                trading_pair.set_unclaimable_reward_holder(accounts.bob, true),
+                Err(TradingPairErrors::CallerIsNotAdmin)
+            );
         }
 
-        /// function to get LP lock timestamp
-        fn _min(&self, value1: Balance, value2: Balance) -> Balance {
-            if value1 < value2 {
-                return value1
-            } else {
-                return value2
+        #[ink::test]
+        fn randomized_pool_operation_sequences_keep_invariant() {
+            for (fee, seed) in [(0, 1u64), (3000, 7), (10000, 42), (50000, 1337)] {
+                let trading_pair = new_trading_pair(fee);
+                let mut rng = Rng(seed);
+
+                let mut a0_reserve: Balance = 1000 * 10u128.pow(12);
+                let mut psp22_reserve: Balance = 10000 * 10u128.pow(12);
+                let mut total_supply: Balance = trading_pair
+                    .get_shares_for_deposit(a0_reserve, psp22_reserve, 0, 0, 0)
+                    .unwrap();
+
+                // shares given to later providers, the first deposit is never withdrawn
+                let mut withdrawable_shares: Balance = 0;
+
+                for _ in 0..5000 {
+                    let reserves_before = (a0_reserve, psp22_reserve, total_supply);
+
+                    let step = rng.next() % 6;
+
+                    match step {
+                        0 => {
+                            let psp22_amount_in = rng.amount(psp22_reserve / 10);
+                            let quote = trading_pair
+                                .get_swap_quote(
+                                    psp22_amount_in,
+                                    psp22_reserve,
+                                    a0_reserve,
+                                    FeeTier::Standard,
+                                )
+                                .unwrap();
+
+                            psp22_reserve += psp22_amount_in - quote.traders_fee_in;
+                            a0_reserve -= quote.amount_out + quote.traders_fee;
+
+                            assert!(is_constant_product_kept(
+                                reserves_before.0,
+                                reserves_before.1,
+                                a0_reserve,
+                                psp22_reserve,
+                            ));
+                        }
+                        1 => {
+                            let a0_amount_in = rng.amount(a0_reserve / 10);
+                            let quote = trading_pair
+                                .get_swap_quote(
+                                    a0_amount_in,
+                                    a0_reserve,
+                                    psp22_reserve,
+                                    FeeTier::Discounted(0),
+                                )
+                                .unwrap();
+
+                            a0_reserve += a0_amount_in - quote.traders_fee_in;
+                            psp22_reserve -= quote.amount_out + quote.traders_fee;
+
+                            assert!(is_constant_product_kept(
+                                reserves_before.0,
+                                reserves_before.1,
+                                a0_reserve,
+                                psp22_reserve,
+                            ));
+                        }
+                        2 => {
+                            let a0_deposit_amount = rng.amount(a0_reserve / 5);
+                            let psp22_deposit_amount = rng.amount(psp22_reserve / 5);
+                            let shares = trading_pair
+                                .get_shares_for_deposit(
+                                    a0_deposit_amount,
+                                    psp22_deposit_amount,
+                                    a0_reserve,
+                                    psp22_reserve,
+                                    total_supply,
+                                )
+                                .unwrap();
+
+                            a0_reserve += a0_deposit_amount;
+                            psp22_reserve += psp22_deposit_amount;
+                            total_supply += shares;
+                            withdrawable_shares += shares;
+
+                            assert!(are_reserves_per_share_kept(
+                                reserves_before,
+                                (a0_reserve, psp22_reserve, total_supply),
+                            ));
+                        }
+                        3 | 4 => {
+                            // redeeming (3) or locking shares (4) pays the incentive earned since the last redeem
+                            let shares = rng.amount(total_supply);
+                            let psp22_amount = trading_pair
+                                .get_share_of_reserve(shares, psp22_reserve, total_supply)
+                                .unwrap();
+                            let days = rng.amount(30);
+
+                            let mut psp22_to_give_each_day =
+                                trading_pair.get_daily_incentive(psp22_amount).unwrap();
+
+                            if step == 4 {
+                                // the boost of the locked shares, capped for locks of a year or more
+                                let boost_bps = trading_pair
+                                    .get_lp_lock_boost_bps(rng.amount(2 * 365 * 86400) as u64);
+
+                                psp22_to_give_each_day += psp22_to_give_each_day * boost_bps / 10000;
+                            }
+
+                            let psp22_incentive_paid = psp22_to_give_each_day * days as Balance;
+
+                            psp22_reserve -= psp22_incentive_paid;
+
+                            assert!(is_incentive_payout_kept(
+                                reserves_before,
+                                (a0_reserve, psp22_reserve, total_supply),
+                                psp22_incentive_paid,
+                            ));
+
+                            // a payout taking more than the incentive from the pool is rejected
+                            assert!(!is_incentive_payout_kept(
+                                reserves_before,
+                                (a0_reserve, psp22_reserve - 1, total_supply),
+                                psp22_incentive_paid,
+                            ));
+                        }
+                        _ => {
+                            if withdrawable_shares == 0 {
+                                continue
+                            }
+
+                            let shares = rng.amount(withdrawable_shares);
+                            let a0_amount_out = trading_pair
+                                .get_share_of_reserve(shares, a0_reserve, total_supply)
+                                .unwrap();
+                            let psp22_amount_out = trading_pair
+                                .get_share_of_reserve(shares, psp22_reserve, total_supply)
+                                .unwrap();
+
+                            a0_reserve -= a0_amount_out;
+                            psp22_reserve -= psp22_amount_out;
+                            total_supply -= shares;
+                            withdrawable_shares -= shares;
+
+                            assert!(are_reserves_per_share_kept(
+                                reserves_before,
+                                (a0_reserve, psp22_reserve, total_supply),
+                            ));
+                        }
+                    }
+                }
             }
         }
     }
@@ -2009,6 +5296,10 @@ pub mod trading_pair_azero {
         use ink::primitives::AccountId;
         use ink_e2e::build_message;
         use my_psp22::my_psp22::MyPsp22Ref;
+        use reentrant_psp22::reentrant_psp22::{
+            ReentrantPsp22Ref,
+            ReentryCall,
+        };
         use openbrush::{
             contracts::psp22::{
                 extensions::metadata::*,
@@ -2044,6 +5335,158 @@ pub mod trading_pair_azero {
             charlie_account_id
         }
 
+        /// PSP22 tokens Alice provides in the shared pool fixture
+        const PSP22_PROVIDED: Balance = 100000000000000;
+
+        /// AZERO Alice provides in the shared pool fixture
+        const A0_PROVIDED: Balance = 10000000000000;
+
+        /// LP fee (1% in parts per million) of the pairs instantiated by the fixture
+        const FIXTURE_FEE: Balance = 10000;
+
+        /// Helper to instantiate a 10000 PSP22 tokens `my_psp22` contract owned by Alice
+        async fn instantiate_psp22(
+            client: &mut ink_e2e::Client<ink_e2e::PolkadotConfig, ink::env::DefaultEnvironment>,
+        ) -> AccountId {
+            // PSP22 token constructor object
+            let psp22_constructor = MyPsp22Ref::new(
+                10000000000000000,
+                Some(String::from("TOKEN").into()),
+                Some(String::from("TKN").into()),
+                12,
+            );
+
+            // Instantiate new PSP22 token using the constructor
+            client
+                .instantiate("my_psp22", &ink_e2e::alice(), psp22_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id
+        }
+
+        /// Helper to instantiate a 1% LP fee pair of the given PSP22 token, with Alice as deployer and admin
+        async fn instantiate_pair(
+            client: &mut ink_e2e::Client<ink_e2e::PolkadotConfig, ink::env::DefaultEnvironment>,
+            psp22_acc_id: AccountId,
+            lp_lock_timestamp: u64,
+        ) -> AccountId {
+            // Trading pair constructor object
+            let tpa_constructor = TradingPairAzeroRef::new(
+                psp22_acc_id,
+                FIXTURE_FEE,
+                psp22_acc_id,
+                get_charlie_account_id(),
+                lp_lock_timestamp,
+                get_alice_account_id(),
+                get_alice_account_id(),
+            );
+
+            // Instantiate new TPA contract using the constructor
+            client
+                .instantiate(
+                    "trading_pair_azero",
+                    &ink_e2e::alice(),
+                    tpa_constructor,
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate failed")
+                .account_id
+        }
+
+        /// Helper to approve and provide PSP22_PROVIDED tokens and A0_PROVIDED AZERO from Alice to the pair
+        async fn provide_from_alice(
+            client: &mut ink_e2e::Client<ink_e2e::PolkadotConfig, ink::env::DefaultEnvironment>,
+            psp22_acc_id: AccountId,
+            tpa_acc_id: AccountId,
+        ) {
+            // Build PSP22 approve message: Alice approves trading pair for the provided PSP22 tokens
+            let approve_psp22_to_provide_lp = build_message::<MyPsp22Ref>(psp22_acc_id.clone())
+                .call(|my_psp22| my_psp22.approve(tpa_acc_id, PSP22_PROVIDED));
+
+            // Call the PSP22 approve message
+            client
+                .call(&ink_e2e::alice(), approve_psp22_to_provide_lp, 0, None)
+                .await
+                .expect("calling `approve_psp22_to_provide_lp` failed");
+
+            // Build TPA's provide to pool message: (100 PSP22 tokens, 10 A0, 1000 as expected LP tokens shares, and 0.5 as slippage)
+            let provide_to_tpa = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone()).call(
+                |trading_pair_azero| {
+                    trading_pair_azero.provide_to_pool(
+                        PSP22_PROVIDED,
+                        A0_PROVIDED,
+                        1000000000000000,
+                        500000000000,
+                    )
+                },
+            );
+
+            // Call the provide to pool function
+            client
+                .call(&ink_e2e::alice(), provide_to_tpa, A0_PROVIDED, None)
+                .await
+                .expect("calling `provide_to_tpa` failed");
+        }
+
+        /// Helper to instantiate a PSP22 token and a pair of it, and provide to the pool from Alice.
+        /// Returns the PSP22 token and the pair addresses
+        async fn instantiate_provided_pair(
+            client: &mut ink_e2e::Client<ink_e2e::PolkadotConfig, ink::env::DefaultEnvironment>,
+            lp_lock_timestamp: u64,
+        ) -> (AccountId, AccountId) {
+            let psp22_acc_id = instantiate_psp22(client).await;
+            let tpa_acc_id = instantiate_pair(client, psp22_acc_id, lp_lock_timestamp).await;
+
+            provide_from_alice(client, psp22_acc_id, tpa_acc_id).await;
+
+            (psp22_acc_id, tpa_acc_id)
+        }
+
+        /// Helper to query the pool state of a pair
+        async fn query_pool_state(
+            client: &mut ink_e2e::Client<ink_e2e::PolkadotConfig, ink::env::DefaultEnvironment>,
+            tpa_acc_id: AccountId,
+        ) -> PoolState {
+            let get_pool_state = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| trading_pair_azero.get_pool_state());
+
+            client
+                .call_dry_run(&ink_e2e::alice(), &get_pool_state, 0, None)
+                .await
+                .return_value()
+        }
+
+        /// Helper to query the reserves stored by a pair
+        async fn query_reserves(
+            client: &mut ink_e2e::Client<ink_e2e::PolkadotConfig, ink::env::DefaultEnvironment>,
+            tpa_acc_id: AccountId,
+        ) -> (Balance, Balance) {
+            let get_reserves = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| trading_pair_azero.get_reserves());
+
+            client
+                .call_dry_run(&ink_e2e::alice(), &get_reserves, 0, None)
+                .await
+                .return_value()
+        }
+
+        /// Helper to query the PSP22 balance of an account
+        async fn query_psp22_balance_of(
+            client: &mut ink_e2e::Client<ink_e2e::PolkadotConfig, ink::env::DefaultEnvironment>,
+            psp22_acc_id: AccountId,
+            account: AccountId,
+        ) -> Balance {
+            let balance_of = build_message::<MyPsp22Ref>(psp22_acc_id.clone())
+                .call(|my_psp22| my_psp22.balance_of(account));
+
+            client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+                .await
+                .return_value()
+        }
+
         /// Tests included in "provide_to_pool_works":
         /// 1. provide_to_pool
         /// 2. get_a0_balance
@@ -2069,7 +5512,7 @@ pub mod trading_pair_azero {
             // Create a new instance of TradingPairAzeroRef contract
             let tpa_constructor = TradingPairAzeroRef::new(
                 psp22_acc_id,
-                1000000000000,
+                10000,
                 psp22_acc_id,
                 get_charlie_account_id(),
             );
@@ -2200,7 +5643,7 @@ pub mod trading_pair_azero {
             // Create a new instance of TradingPairAzeroRef contract
             let tpa_constructor = TradingPairAzeroRef::new(
                 psp22_acc_id,
-                1000000000000,
+                10000,
                 psp22_acc_id,
                 get_charlie_account_id(),
             );
@@ -2450,7 +5893,7 @@ pub mod trading_pair_azero {
             // Trading pair constructor object
             let tpa_constructor = TradingPairAzeroRef::new(
                 psp22_acc_id,
-                1000000000000,
+                10000,
                 psp22_acc_id,
                 get_charlie_account_id(),
             );
@@ -2656,7 +6099,7 @@ pub mod trading_pair_azero {
             // Trading pair constructor object
             let tpa_constructor = TradingPairAzeroRef::new(
                 psp22_acc_id,
-                1000000000000,
+                10000,
                 psp22_acc_id,
                 get_charlie_account_id(),
             );
@@ -2828,7 +6271,7 @@ pub mod trading_pair_azero {
             // Trading pair constructor object
             let tpa_constructor = TradingPairAzeroRef::new(
                 psp22_acc_id,
-                1000000000000,
+                10000,
                 psp22_acc_id,
                 get_charlie_account_id(),
             );
@@ -3000,7 +6443,7 @@ pub mod trading_pair_azero {
             // Trading pair constructor object
             let tpa_constructor = TradingPairAzeroRef::new(
                 psp22_acc_id,
-                1000000000000,
+                10000,
                 psp22_acc_id,
                 get_charlie_account_id(),
             );
@@ -3182,7 +6625,7 @@ pub mod trading_pair_azero {
             // Create a TradingPairAzero contract instance
             let tpa_constructor = TradingPairAzeroRef::new(
                 psp22_acc_id,
-                1000000000000,
+                10000,
                 psp22_acc_id,
                 get_charlie_account_id(),
             );
@@ -3266,7 +6709,7 @@ pub mod trading_pair_azero {
             // Create a TradingPairAzero contract instance
             let tpa_constructor = TradingPairAzeroRef::new(
                 psp22_acc_id,
-                1000000000000,
+                10000,
                 psp22_acc_id,
                 get_charlie_account_id(),
             );
@@ -3352,7 +6795,7 @@ pub mod trading_pair_azero {
             // Create a TradingPairAzero contract instance
             let tpa_constructor = TradingPairAzeroRef::new(
                 psp22_acc_id,
-                1000000000000,
+                10000,
                 psp22_acc_id,
                 get_charlie_account_id(),
             );
@@ -3446,7 +6889,7 @@ pub mod trading_pair_azero {
             // Create a TradingPairAzero contract instance
             let tpa_constructor = TradingPairAzeroRef::new(
                 psp22_acc_id,
-                1000000000000,
+                10000,
                 psp22_acc_id,
                 get_charlie_account_id(),
             );
@@ -3547,7 +6990,7 @@ pub mod trading_pair_azero {
             // Create a TradingPairAzero contract instance
             let tpa_constructor = TradingPairAzeroRef::new(
                 psp22_acc_id,
-                1000000000000,
+                10000,
                 psp22_acc_id,
                 get_charlie_account_id(),
             );
@@ -3631,7 +7074,7 @@ pub mod trading_pair_azero {
             // Create a TradingPairAzero contract instance
             let tpa_constructor = TradingPairAzeroRef::new(
                 psp22_acc_id,
-                1000000000000,
+                10000,
                 psp22_acc_id,
                 get_charlie_account_id(),
             );
@@ -3717,7 +7160,7 @@ pub mod trading_pair_azero {
             // Create a TradingPairAzero contract instance
             let tpa_constructor = TradingPairAzeroRef::new(
                 psp22_acc_id,
-                1000000000000,
+                10000,
                 psp22_acc_id,
                 get_charlie_account_id(),
             );
@@ -3809,7 +7252,7 @@ pub mod trading_pair_azero {
             // Instantiate TradingPairAzeroRef contract
             let tpa_constructor = TradingPairAzeroRef::new(
                 psp22_acc_id,
-                1000000000000,
+                10000,
                 psp22_acc_id,
                 get_charlie_account_id(),
             );
@@ -3936,25 +7379,285 @@ pub mod trading_pair_azero {
             // Verify the balance of MyPsp22Ref in TradingPairAzeroRef
             assert_eq!(get_psp22_res.return_value(), 100998000000000);
 
-            // Get the balance of MyPsp22Ref for Charlie account after swapping
+            // Collect the traders fees accrued for the vault (Charlie)
+            let collect_protocol_fees = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| trading_pair_azero.collect_protocol_fees());
+
+            client
+                .call(&ink_e2e::alice(), collect_protocol_fees, 0, None)
+                .await
+                .expect("calling `collect_protocol_fees` failed");
+
+            // Get the balance of MyPsp22Ref for Charlie account after swapping
+            let psp22_balance_of = build_message::<MyPsp22Ref>(psp22_acc_id.clone())
+                .call(|my_psp22| my_psp22.balance_of(get_charlie_account_id()));
+
+            // Call and fetch the results
+            let psp22_balance_of_res = client
+                .call(&ink_e2e::alice(), psp22_balance_of, 0, None)
+                .await
+                .expect("psp22_balance_of failed");
+            let psp22_balance = psp22_balance_of_res.return_value();
+
+            // Verify the balance of MyPsp22Ref for Charlie account after swapping
+            assert_eq!(psp22_balance, 2000000000);
+
+            // Get the generated LP fees in TradingPairAzeroRef
+            let get_generated_lp_fees = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| trading_pair_azero.get_generated_lp_fees());
+
+            // Call and fetch the results
+            let get_generated_lp_fees_res = client
+                .call(&ink_e2e::alice(), get_generated_lp_fees, 0, None)
+                .await
+                .expect("get_generated_lp_fees failed");
+
+            let (psp22_fees, a0_fees) = get_generated_lp_fees_res.return_value();
+
+            // Verify the generated LP fees in TradingPairAzeroRef
+            assert_eq!(psp22_fees, 0);
+
+            assert_eq!(a0_fees, 990198019);
+
+            // Get the percentage difference of Azero LP fees
+            let get_a0_difference_by_percentage = build_message::<TradingPairAzeroRef>(
+                tpa_acc_id.clone(),
+            )
+            .call(|trading_pair_azero| trading_pair_azero.get_a0_difference_by_percentage());
+
+            // Call and fetch the results
+            let get_a0_difference_by_percentage_res = client
+                .call(&ink_e2e::alice(), get_a0_difference_by_percentage, 0, None)
+                .await
+                .expect("get_a0_difference_by_percentage failed");
+            let Some(a0_lp_fee_diff) = get_a0_difference_by_percentage_res.return_value().ok() else { panic!("test") };
+
+            // Verify the percentage difference of Azero LP fees
+            assert_eq!(a0_lp_fee_diff, 9998500200);
+
+            let amount: u128 = 500000000000000;
+
+            // Withdraw a specific amount from the pool
+            let withdraw_from_pool = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| trading_pair_azero.withdraw_specific_amount(amount));
+
+            client
+                .call(&ink_e2e::alice(), withdraw_from_pool, 0, None)
+                .await
+                .expect("withdraw_from_pool failed");
+
+            // Get the overall LP fee rewards for Alice account
+            let get_account_overall_lp_fee_rewards = build_message::<TradingPairAzeroRef>(
+                tpa_acc_id.clone(),
+            )
+            .call(|trading_pair_azero| {
+                trading_pair_azero.get_account_overall_lp_fee_rewards(get_alice_account_id())
+            });
+
+            // Call and fetch the results
+            let get_account_overall_lp_fee_rewards_res = client
+                .call(
+                    &ink_e2e::alice(),
+                    get_account_overall_lp_fee_rewards,
+                    0,
+                    None,
+                )
+                .await
+                .expect("get_account_overall_lp_fee_rewards failed");
+
+            let (psp22_fees, a0_fees) = get_account_overall_lp_fee_rewards_res.return_value();
+
+            // Verify the overall LP fee rewards for Alice account
+            assert_eq!(psp22_fees, 0);
+
+            assert_eq!(a0_fees, 495099009);
+
+            // Get A0 LP fee tokens
+            let get_a0_lp_fee_tokens = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| trading_pair_azero.get_a0_lp_fee_tokens(amount));
+
+            let get_a0_lp_fee_tokens_res = client
+                .call(&ink_e2e::alice(), get_a0_lp_fee_tokens, 0, None)
+                .await
+                .expect("get_a0_lp_fee_tokens failed");
+
+            let Some(a0_lp_fee) = get_a0_lp_fee_tokens_res.return_value().ok() else {
+                panic!("failed!")
+            };
+
+            assert_eq!(a0_lp_fee, 495099010);
+
+            Ok(())
+        }
+
+        /// Tests included in 'swap_a0_works'
+        /// 1. provide_to_pool
+        /// 2. swap_a0
+        /// 3. get_a0_balance
+        /// 4. get_psp22_balance
+        /// 5. get_generated_lp_fees
+        /// 6. get_contract_overall_generated_fee
+        /// 7. get_psp22_difference_by_percentage
+        /// 8. withdraw_specific_amount
+        /// 9. get_account_overall_lp_fee_rewards
+        /// 10.get_psp22_lp_fee_tokens
+        #[ink_e2e::test(additional_contracts = "../my_psp22/Cargo.toml")]
+        async fn swap_a0_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Create and initialize MyPsp22 contract
+            let psp22_constructor = MyPsp22Ref::new(
+                10000000000000000,
+                Some(String::from("TOKEN").into()),
+                Some(String::from("TKN").into()),
+                12,
+            );
+
+            let psp22_acc_id = client
+                .instantiate("my_psp22", &ink_e2e::alice(), psp22_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // Create and initialize TradingPairAzero contract
+            let tpa_constructor = TradingPairAzeroRef::new(
+                psp22_acc_id,
+                10000,
+                psp22_acc_id,
+                get_charlie_account_id(),
+            );
+
+            let tpa_acc_id = client
+                .instantiate(
+                    "trading_pair_azero",
+                    &ink_e2e::alice(),
+                    tpa_constructor,
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // Approve MyPsp22 to provide LP to TradingPairAzero
+            let approve_psp22_to_provide_lp = build_message::<MyPsp22Ref>(psp22_acc_id.clone())
+                .call(|my_psp22| my_psp22.approve(tpa_acc_id, 100000000000000));
+
+            client
+                .call(&ink_e2e::alice(), approve_psp22_to_provide_lp, 0, None)
+                .await
+                .expect("calling `approve_psp22_to_provide_lp` failed");
+
+            // Provide LP to TradingPairAzero
+            let provide_to_tpa = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone()).call(
+                |trading_pair_azero| {
+                    trading_pair_azero.provide_to_pool(
+                        100000000000000,
+                        1000000000000000,
+                        500000000000,
+                    )
+                },
+            );
+
+            let amount: u128 = 10000000000000;
+
+            client
+                .call(&ink_e2e::alice(), provide_to_tpa, amount, None)
+                .await
+                .expect("calling `provide_to_tpa` failed");
+
+            // Fetch the estimated price for swap
+            let get_est_price_a0_to_psp22_for_swap = build_message::<TradingPairAzeroRef>(
+                tpa_acc_id.clone(),
+            )
+            .call(|trading_pair_azero| {
+                trading_pair_azero.get_est_price_a0_to_psp22_for_swap(100000000000)
+            });
+
+            let get_est_price_a0_to_psp22_for_swap_res = client
+                .call(
+                    &ink_e2e::alice(),
+                    get_est_price_a0_to_psp22_for_swap,
+                    0,
+                    None,
+                )
+                .await
+                .expect("get_est_price_a0_to_psp22_for_swap failed");
+
+            let Some(price) = get_est_price_a0_to_psp22_for_swap_res.return_value().ok() else { panic!("failed!") };
+
+            assert_eq!(price, 999900009999);
+
+            // Perform the swap
+            let swap_a0 = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| trading_pair_azero.swap_a0(price, 1000000000000));
+
+            let amount: u128 = 100000000000;
+
+            client
+                .call(&ink_e2e::alice(), swap_a0, amount, None)
+                .await
+                .expect("calling `swap_a0` failed");
+
+            // Check MyPsp22 balance
+            let psp22_balance_of = build_message::<MyPsp22Ref>(psp22_acc_id.clone())
+                .call(|my_psp22| my_psp22.balance_of(get_alice_account_id()));
+
+            let psp22_balance_of_res = client
+                .call(&ink_e2e::alice(), psp22_balance_of, 0, None)
+                .await
+                .expect("psp22_balance_of failed");
+
+            let psp22_balance = psp22_balance_of_res.return_value();
+
+            assert_eq!(psp22_balance, 9900978120978120);
+
+            // Get TradingPairAzero a0 balance
+            let get_a0_balance = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| trading_pair_azero.get_a0_balance());
+
+            let get_a0_res = client
+                .call(&ink_e2e::alice(), get_a0_balance, 0, None)
+                .await
+                .expect("get_a0_balance failed");
+
+            assert_eq!(get_a0_res.return_value(), 10100800000000);
+
+            // Get TradingPairAzero MyPsp22 balance
+            let get_psp22_balance = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| trading_pair_azero.get_psp22_balance());
+
+            let get_psp22_res = client
+                .call(&ink_e2e::alice(), get_psp22_balance, 0, None)
+                .await
+                .expect("get_psp22_balance failed");
+
+            assert_eq!(get_psp22_res.return_value(), 99019899019900);
+
+            // Collect the traders fees accrued for the vault (Charlie)
+            let collect_protocol_fees = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| trading_pair_azero.collect_protocol_fees());
+
+            client
+                .call(&ink_e2e::alice(), collect_protocol_fees, 0, None)
+                .await
+                .expect("calling `collect_protocol_fees` failed");
+
+            // Check MyPsp22 balance for Charlie
             let psp22_balance_of = build_message::<MyPsp22Ref>(psp22_acc_id.clone())
                 .call(|my_psp22| my_psp22.balance_of(get_charlie_account_id()));
 
-            // Call and fetch the results
             let psp22_balance_of_res = client
                 .call(&ink_e2e::alice(), psp22_balance_of, 0, None)
                 .await
                 .expect("psp22_balance_of failed");
+
             let psp22_balance = psp22_balance_of_res.return_value();
 
-            // Verify the balance of MyPsp22Ref for Charlie account after swapping
-            assert_eq!(psp22_balance, 2000000000);
+            assert_eq!(psp22_balance, 1980001980);
 
-            // Get the generated LP fees in TradingPairAzeroRef
+            // Get generated LP fees
             let get_generated_lp_fees = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
                 .call(|trading_pair_azero| trading_pair_azero.get_generated_lp_fees());
 
-            // Call and fetch the results
             let get_generated_lp_fees_res = client
                 .call(&ink_e2e::alice(), get_generated_lp_fees, 0, None)
                 .await
@@ -3962,30 +7665,53 @@ pub mod trading_pair_azero {
 
             let (psp22_fees, a0_fees) = get_generated_lp_fees_res.return_value();
 
-            // Verify the generated LP fees in TradingPairAzeroRef
-            assert_eq!(psp22_fees, 0);
+            assert_eq!(psp22_fees, 9900009900);
+            assert_eq!(a0_fees, 0);
 
-            assert_eq!(a0_fees, 990198019);
+            // Get overall generated fees by the contract
+            let get_contract_overall_generated_fee = build_message::<TradingPairAzeroRef>(
+                tpa_acc_id.clone(),
+            )
+            .call(|trading_pair_azero| trading_pair_azero.get_contract_overall_generated_fee());
 
-            // Get the percentage difference of Azero LP fees
-            let get_a0_difference_by_percentage = build_message::<TradingPairAzeroRef>(
+            let get_contract_overall_generated_fee_res = client
+                .call(
+                    &ink_e2e::alice(),
+                    get_contract_overall_generated_fee,
+                    0,
+                    None,
+                )
+                .await
+                .expect("get_contract_overall_generated_fee failed");
+
+            let (psp22_fees, a0_fees) = get_contract_overall_generated_fee_res.return_value();
+
+            assert_eq!(psp22_fees, 9900009900);
+            assert_eq!(a0_fees, 0);
+
+            // Get MyPsp22 difference by percentage
+            let get_psp22_difference_by_percentage = build_message::<TradingPairAzeroRef>(
                 tpa_acc_id.clone(),
             )
-            .call(|trading_pair_azero| trading_pair_azero.get_a0_difference_by_percentage());
+            .call(|trading_pair_azero| trading_pair_azero.get_psp22_difference_by_percentage());
 
-            // Call and fetch the results
-            let get_a0_difference_by_percentage_res = client
-                .call(&ink_e2e::alice(), get_a0_difference_by_percentage, 0, None)
+            let get_psp22_difference_by_percentage_res = client
+                .call(
+                    &ink_e2e::alice(),
+                    get_psp22_difference_by_percentage,
+                    0,
+                    None,
+                )
                 .await
-                .expect("get_a0_difference_by_percentage failed");
-            let Some(a0_lp_fee_diff) = get_a0_difference_by_percentage_res.return_value().ok() else { panic!("test") };
+                .expect("get_psp22_difference_by_percentage failed");
 
-            // Verify the percentage difference of Azero LP fees
-            assert_eq!(a0_lp_fee_diff, 9998500200);
+            let Some(psp22_lp_fee_diff) = get_psp22_difference_by_percentage_res.return_value().ok() else {
+                panic!("failed!")
+            };
 
-            let amount: u128 = 500000000000000;
+            assert_eq!(psp22_lp_fee_diff, 9997500600);
 
-            // Withdraw a specific amount from the pool
+            // Withdraw from the pool
             let withdraw_from_pool = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
                 .call(|trading_pair_azero| trading_pair_azero.withdraw_specific_amount(amount));
 
@@ -3994,7 +7720,7 @@ pub mod trading_pair_azero {
                 .await
                 .expect("withdraw_from_pool failed");
 
-            // Get the overall LP fee rewards for Alice account
+            // Get account overall LP fee rewards
             let get_account_overall_lp_fee_rewards = build_message::<TradingPairAzeroRef>(
                 tpa_acc_id.clone(),
             )
@@ -4002,7 +7728,6 @@ pub mod trading_pair_azero {
                 trading_pair_azero.get_account_overall_lp_fee_rewards(get_alice_account_id())
             });
 
-            // Call and fetch the results
             let get_account_overall_lp_fee_rewards_res = client
                 .call(
                     &ink_e2e::alice(),
@@ -4015,43 +7740,152 @@ pub mod trading_pair_azero {
 
             let (psp22_fees, a0_fees) = get_account_overall_lp_fee_rewards_res.return_value();
 
-            // Verify the overall LP fee rewards for Alice account
-            assert_eq!(psp22_fees, 0);
-
-            assert_eq!(a0_fees, 495099009);
+            assert_eq!(psp22_fees, 990000);
+            assert_eq!(a0_fees, 0);
 
-            // Get A0 LP fee tokens
-            let get_a0_lp_fee_tokens = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
-                .call(|trading_pair_azero| trading_pair_azero.get_a0_lp_fee_tokens(amount));
+            // Get PSP22 LP fee tokens
+            let get_psp22_lp_fee_tokens = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| trading_pair_azero.get_psp22_lp_fee_tokens(amount));
 
-            let get_a0_lp_fee_tokens_res = client
-                .call(&ink_e2e::alice(), get_a0_lp_fee_tokens, 0, None)
+            let get_psp22_lp_fee_tokens_res = client
+                .call(&ink_e2e::alice(), get_psp22_lp_fee_tokens, 0, None)
                 .await
-                .expect("get_a0_lp_fee_tokens failed");
+                .expect("get_psp22_lp_fee_tokens failed");
 
-            let Some(a0_lp_fee) = get_a0_lp_fee_tokens_res.return_value().ok() else {
+            let Some(psp22_lp_fee) = get_psp22_lp_fee_tokens_res.return_value().ok() else {
                 panic!("failed!")
             };
 
-            assert_eq!(a0_lp_fee, 495099010);
+            assert_eq!(psp22_lp_fee, 990000);
 
             Ok(())
         }
 
-        /// Tests included in 'swap_a0_works'
+        /// Tests included in 'transfer_lp_tokens_works'
+        /// 1. provide_to_tpa
+        /// 2. get_lp_token_of
+        /// 3. transfer_lp_tokens
+        /// 4. get_lp_token_of
+        #[ink_e2e::test(additional_contracts = "../my_psp22/Cargo.toml")]
+        async fn transfer_lp_tokens_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Instantiate MyPsp22 contract
+            let psp22_constructor = MyPsp22Ref::new(
+                10000000000000000,
+                Some(String::from("TOKEN").into()),
+                Some(String::from("TKN").into()),
+                12,
+            );
+            let psp22_acc_id = client
+                .instantiate("my_psp22", &ink_e2e::alice(), psp22_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // Instantiate TradingPairAzero contract
+            let tpa_constructor = TradingPairAzeroRef::new(
+                psp22_acc_id.clone(),
+                1000000000000,
+                psp22_acc_id.clone(),
+                get_charlie_account_id(),
+            );
+            let tpa_acc_id = client
+                .instantiate(
+                    "trading_pair_azero",
+                    &ink_e2e::alice(),
+                    tpa_constructor,
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // Approve MyPsp22 contract to provide LP tokens to TradingPairAzero
+            let approve_psp22_to_provide_lp = build_message::<MyPsp22Ref>(psp22_acc_id.clone())
+                .call(|my_psp22| my_psp22.approve(tpa_acc_id.clone(), 100000000000000));
+            client
+                .call(&ink_e2e::alice(), approve_psp22_to_provide_lp, 0, None)
+                .await
+                .expect("calling `approve_psp22_to_provide_lp` failed");
+
+            // Provide LP tokens to TradingPairAzero
+            let provide_to_tpa = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone()).call(
+                |trading_pair_azero| {
+                    trading_pair_azero.provide_to_pool(
+                        100000000000000,
+                        1000000000000000,
+                        500000000000,
+                    )
+                },
+            );
+            let amount: u128 = 10000000000000;
+            client
+                .call(&ink_e2e::alice(), provide_to_tpa, amount, None)
+                .await
+                .expect("calling `provide_to_tpa` failed");
+
+            // Check LP token balance for Alice
+            let get_lp_share_balance = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| {
+                    trading_pair_azero.get_lp_token_of(get_alice_account_id())
+                });
+            let get_lp_share_res = client
+                .call(&ink_e2e::alice(), get_lp_share_balance, 0, None)
+                .await
+                .expect("get_lp_share_balance failed");
+
+            assert_eq!(get_lp_share_res.return_value(), 1000000000000000);
+
+            // Transfer LP tokens from Alice to Bob
+            let transfer_lp_tokens = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone()).call(
+                |trading_pair_azero| {
+                    trading_pair_azero.transfer_lp_tokens(get_bob_account_id(), 500000000000000)
+                },
+            );
+            client
+                .call(&ink_e2e::alice(), transfer_lp_tokens, 0, None)
+                .await
+                .expect("calling `transfer_lp_tokens` failed");
+
+            // Check LP token balance for Alice after transfer
+            let get_lp_share_balance = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| {
+                    trading_pair_azero.get_lp_token_of(get_alice_account_id())
+                });
+            let get_lp_share_res = client
+                .call(&ink_e2e::alice(), get_lp_share_balance, 0, None)
+                .await
+                .expect("get_lp_share_balance failed");
+
+            assert_eq!(get_lp_share_res.return_value(), 500000000000000);
+
+            // Check LP token balance for Bob after transfer
+            let get_lp_share_balance = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| {
+                    trading_pair_azero.get_lp_token_of(get_bob_account_id())
+                });
+            let get_lp_share_res = client
+                .call(&ink_e2e::alice(), get_lp_share_balance, 0, None)
+                .await
+                .expect("get failed");
+
+            assert_eq!(get_lp_share_res.return_value(), 500000000000000);
+
+            Ok(())
+        }
+
+        /// Tests included in 'transfer_lp_tokens_from_to_works'
         /// 1. provide_to_pool
-        /// 2. swap_a0
-        /// 3. get_a0_balance
-        /// 4. get_psp22_balance
-        /// 5. get_generated_lp_fees
-        /// 6. get_contract_overall_generated_fee
-        /// 7. get_psp22_difference_by_percentage
-        /// 8. withdraw_specific_amount
-        /// 9. get_account_overall_lp_fee_rewards
-        /// 10.get_psp22_lp_fee_tokens
+        /// 2. get_lp_token_of
+        /// 3. approve_lp_tokens
+        /// 4. get_lp_tokens_allowance
+        /// 5. transfer_lp_tokens_from_to
+        /// 6.
         #[ink_e2e::test(additional_contracts = "../my_psp22/Cargo.toml")]
-        async fn swap_a0_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
-            // Create and initialize MyPsp22 contract
+        async fn transfer_lp_tokens_from_to_works(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            // Instantiate MyPsp22 contract
             let psp22_constructor = MyPsp22Ref::new(
                 10000000000000000,
                 Some(String::from("TOKEN").into()),
@@ -4065,10 +7899,10 @@ pub mod trading_pair_azero {
                 .expect("instantiate failed")
                 .account_id;
 
-            // Create and initialize TradingPairAzero contract
+            // Instantiate TradingPairAzero contract
             let tpa_constructor = TradingPairAzeroRef::new(
                 psp22_acc_id,
-                1000000000000,
+                10000,
                 psp22_acc_id,
                 get_charlie_account_id(),
             );
@@ -4085,7 +7919,7 @@ pub mod trading_pair_azero {
                 .expect("instantiate failed")
                 .account_id;
 
-            // Approve MyPsp22 to provide LP to TradingPairAzero
+            // Approve MyPsp22 to provide LP tokens to TradingPairAzero
             let approve_psp22_to_provide_lp = build_message::<MyPsp22Ref>(psp22_acc_id.clone())
                 .call(|my_psp22| my_psp22.approve(tpa_acc_id, 100000000000000));
 
@@ -4094,7 +7928,7 @@ pub mod trading_pair_azero {
                 .await
                 .expect("calling `approve_psp22_to_provide_lp` failed");
 
-            // Provide LP to TradingPairAzero
+            // Provide LP tokens to TradingPairAzero
             let provide_to_tpa = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone()).call(
                 |trading_pair_azero| {
                     trading_pair_azero.provide_to_pool(
@@ -4105,379 +7939,534 @@ pub mod trading_pair_azero {
                 },
             );
 
-            let amount: u128 = 10000000000000;
-
+            let amount: u128 = 10000000000000;
+
+            client
+                .call(&ink_e2e::alice(), provide_to_tpa, amount, None)
+                .await
+                .expect("calling `provide_to_tpa` failed");
+
+            // Get LP share balance of Alice
+            let get_lp_share_balance = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| {
+                    trading_pair_azero.get_lp_token_of(get_alice_account_id())
+                });
+
+            let get_lp_share_res = client
+                .call(&ink_e2e::alice(), get_lp_share_balance, 0, None)
+                .await
+                .expect("get_lp_share_balance failed");
+
+            assert_eq!(get_lp_share_res.return_value(), 1000000000000000);
+
+            // Approve LP tokens for Bob
+            let approve_lp_tokens = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone()).call(
+                |trading_pair_azero| {
+                    trading_pair_azero.approve_lp_tokens(get_bob_account_id(), 500000000000000)
+                },
+            );
+
             client
-                .call(&ink_e2e::alice(), provide_to_tpa, amount, None)
+                .call(&ink_e2e::alice(), approve_lp_tokens, 0, None)
                 .await
-                .expect("calling `provide_to_tpa` failed");
+                .expect("calling `approve_lp_tokens` failed");
 
-            // Fetch the estimated price for swap
-            let get_est_price_a0_to_psp22_for_swap = build_message::<TradingPairAzeroRef>(
+            // Get LP tokens allowance from Alice to Bob
+            let get_lp_tokens_allowance = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| {
+                    trading_pair_azero
+                        .get_lp_tokens_allowance(get_alice_account_id(), get_bob_account_id())
+                });
+
+            let get_lp_tokens_allowance_res = client
+                .call(&ink_e2e::alice(), get_lp_tokens_allowance, 0, None)
+                .await
+                .expect("get_lp_tokens_allowance failed");
+
+            assert_eq!(get_lp_tokens_allowance_res.return_value(), 500000000000000);
+
+            // Transfer LP tokens from Alice to Bob
+            let transfer_lp_tokens_from_to = build_message::<TradingPairAzeroRef>(
                 tpa_acc_id.clone(),
             )
             .call(|trading_pair_azero| {
-                trading_pair_azero.get_est_price_a0_to_psp22_for_swap(100000000000)
+                trading_pair_azero.transfer_lp_tokens_from_to(
+                    get_alice_account_id(),
+                    get_bob_account_id(),
+                    500000000000000,
+                )
             });
 
-            let get_est_price_a0_to_psp22_for_swap_res = client
-                .call(
-                    &ink_e2e::alice(),
-                    get_est_price_a0_to_psp22_for_swap,
-                    0,
-                    None,
-                )
+            client
+                .call(&ink_e2e::alice(), transfer_lp_tokens_from_to, 0, None)
                 .await
-                .expect("get_est_price_a0_to_psp22_for_swap failed");
+                .expect("calling `transfer_lp_tokens_from_to` failed");
 
-            let Some(price) = get_est_price_a0_to_psp22_for_swap_res.return_value().ok() else { panic!("failed!") };
+            // Get LP share balance of Alice after transfer
+            let get_lp_share_balance = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| {
+                    trading_pair_azero.get_lp_token_of(get_alice_account_id())
+                });
 
-            assert_eq!(price, 999900009999);
+            let get_lp_share_res = client
+                .call(&ink_e2e::alice(), get_lp_share_balance, 0, None)
+                .await
+                .expect("get failed");
 
-            // Perform the swap
-            let swap_a0 = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
-                .call(|trading_pair_azero| trading_pair_azero.swap_a0(price, 1000000000000));
+            assert_eq!(get_lp_share_res.return_value(), 500000000000000);
 
-            let amount: u128 = 100000000000;
+            // Get LP share balance of Bob after transfer
+            let get_lp_share_balance = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| {
+                    trading_pair_azero.get_lp_token_of(get_bob_account_id())
+                });
 
-            client
-                .call(&ink_e2e::alice(), swap_a0, amount, None)
+            let get_lp_share_res = client
+                .call(&ink_e2e::alice(), get_lp_share_balance, 0, None)
                 .await
-                .expect("calling `swap_a0` failed");
-
-            // Check MyPsp22 balance
-            let psp22_balance_of = build_message::<MyPsp22Ref>(psp22_acc_id.clone())
-                .call(|my_psp22| my_psp22.balance_of(get_alice_account_id()));
+                .expect("get failed");
 
-            let psp22_balance_of_res = client
-                .call(&ink_e2e::alice(), psp22_balance_of, 0, None)
-                .await
-                .expect("psp22_balance_of failed");
+            assert_eq!(get_lp_share_res.return_value(), 500000000000000);
 
-            let psp22_balance = psp22_balance_of_res.return_value();
+            Ok(())
+        }
 
-            assert_eq!(psp22_balance, 9900978120978120);
+        /// Tests included in "get_pool_state_and_position_works":
+        /// 1. provide_to_pool
+        /// 2. get_pool_state
+        /// 3. get_position
+        #[ink_e2e::test(additional_contracts = "../my_psp22/Cargo.toml")]
+        async fn get_pool_state_and_position_works(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            // Instantiate a PSP22 token and a 1% LP fee pair of it without LP lock
+            let psp22_acc_id = instantiate_psp22(&mut client).await;
+            let tpa_acc_id = instantiate_pair(&mut client, psp22_acc_id, 0).await;
 
-            // Get TradingPairAzero a0 balance
+            // Build get_a0_balance message to fetch the AZERO the pair was instantiated with
             let get_a0_balance = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
                 .call(|trading_pair_azero| trading_pair_azero.get_a0_balance());
 
-            let get_a0_res = client
+            // Call and fetch the result
+            let a0_balance_before = client
                 .call(&ink_e2e::alice(), get_a0_balance, 0, None)
                 .await
-                .expect("get_a0_balance failed");
+                .expect("get_a0_balance failed")
+                .return_value();
 
-            assert_eq!(get_a0_res.return_value(), 10100800000000);
+            // Alice provides 100 PSP22 tokens and 10 A0
+            provide_from_alice(&mut client, psp22_acc_id, tpa_acc_id).await;
 
-            // Get TradingPairAzero MyPsp22 balance
-            let get_psp22_balance = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
-                .call(|trading_pair_azero| trading_pair_azero.get_psp22_balance());
+            // Build get_pool_state message to fetch the pool snapshot
+            let get_pool_state = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| trading_pair_azero.get_pool_state());
 
-            let get_psp22_res = client
-                .call(&ink_e2e::alice(), get_psp22_balance, 0, None)
+            // Call and fetch the result
+            let pool_state = client
+                .call(&ink_e2e::alice(), get_pool_state, 0, None)
                 .await
-                .expect("get_psp22_balance failed");
+                .expect("get_pool_state failed")
+                .return_value();
 
-            assert_eq!(get_psp22_res.return_value(), 99019899019900);
+            // the first provision gets a0 * psp22 / 10^12 shares
+            let expected_shares: Balance = A0_PROVIDED * PSP22_PROVIDED / 10u128.pow(12);
 
-            // Check MyPsp22 balance for Charlie
-            let psp22_balance_of = build_message::<MyPsp22Ref>(psp22_acc_id.clone())
-                .call(|my_psp22| my_psp22.balance_of(get_charlie_account_id()));
+            // the AZERO reserve also holds the balance the pair was instantiated with
+            let expected_a0_reserve: Balance = a0_balance_before + A0_PROVIDED;
 
-            let psp22_balance_of_res = client
-                .call(&ink_e2e::alice(), psp22_balance_of, 0, None)
-                .await
-                .expect("psp22_balance_of failed");
+            // Validate the pool reserves, LP supply and fee
+            assert_eq!(pool_state.psp22_token, psp22_acc_id);
+            assert_eq!(pool_state.a0_reserve, expected_a0_reserve);
+            assert_eq!(pool_state.psp22_reserve, PSP22_PROVIDED);
+            assert_eq!(pool_state.total_supply, expected_shares);
+            assert_eq!(pool_state.fee, FIXTURE_FEE);
+            assert_eq!(
+                pool_state.price_for_one_psp22,
+                expected_a0_reserve * 10u128.pow(12) / PSP22_PROVIDED
+            );
 
-            let psp22_balance = psp22_balance_of_res.return_value();
+            // Build get_position message to fetch Alice's LP position
+            let get_position = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| trading_pair_azero.get_position(get_alice_account_id()));
 
-            assert_eq!(psp22_balance, 1980001980);
+            // Call and fetch the result
+            let position = client
+                .call(&ink_e2e::alice(), get_position, 0, None)
+                .await
+                .expect("get_position failed")
+                .return_value()
+                .expect("get_position returned an error");
+
+            // Validate that Alice owns the whole pool and that her shares are not locked
+            assert_eq!(position.shares, expected_shares);
+            assert_eq!(position.a0_amount, expected_a0_reserve);
+            assert_eq!(position.psp22_amount, PSP22_PROVIDED);
+            assert_eq!(position.pending_a0_lp_fee, 0);
+            assert_eq!(position.pending_psp22_lp_fee, 0);
+            // the daily incentive is 2% a year of the pooled PSP22
+            assert_eq!(position.psp22_daily_incentive, PSP22_PROVIDED * 2 / 100 / 365);
+            assert!(!position.is_locked);
 
-            // Get generated LP fees
-            let get_generated_lp_fees = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
-                .call(|trading_pair_azero| trading_pair_azero.get_generated_lp_fees());
+            Ok(())
+        }
 
-            let get_generated_lp_fees_res = client
-                .call(&ink_e2e::alice(), get_generated_lp_fees, 0, None)
+        /// Tests included in "quote_psp22_to_a0_works":
+        /// 1. provide_to_pool
+        /// 2. quote_psp22_to_a0
+        /// 3. quote_psp22_to_a0_with_tier
+        #[ink_e2e::test(additional_contracts = "../my_psp22/Cargo.toml")]
+        async fn quote_psp22_to_a0_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Instantiate a PSP22 token and a 1% LP fee pair of it without LP lock, then provide 100 PSP22 tokens and 10 A0 from Alice
+            let (_, tpa_acc_id) = instantiate_provided_pair(&mut client, 0).await;
+
+            // Build quote message without an account (standard fee tier)
+            let quote_psp22_to_a0 = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| trading_pair_azero.quote_psp22_to_a0(1000000000000, None));
+
+            // Call and fetch the result, the quote must not depend on the caller
+            let standard_quote = client
+                .call(&ink_e2e::bob(), quote_psp22_to_a0, 0, None)
+                .await
+                .expect("quote_psp22_to_a0 failed")
+                .return_value()
+                .expect("quote_psp22_to_a0 returned an error");
+
+            // Validate the fee breakdown of the standard fee tier
+            assert_eq!(standard_quote.fee_tier, FeeTier::Standard);
+            assert_eq!(standard_quote.amount_out, 97552117761);
+            assert_eq!(standard_quote.lp_fee, 978088109);
+            assert_eq!(standard_quote.traders_fee, 244491523);
+            assert_eq!(standard_quote.traders_fee_in, 2500000000);
+            assert_eq!(standard_quote.price_impact_bps, 98);
+
+            // Build quote message with the first discount tier
+            let quote_psp22_to_a0_with_tier = build_message::<TradingPairAzeroRef>(
+                tpa_acc_id.clone(),
+            )
+            .call(|trading_pair_azero| {
+                trading_pair_azero.quote_psp22_to_a0_with_tier(1000000000000, FeeTier::Discounted(0))
+            });
+
+            // Call and fetch the result
+            let panx_holder_quote = client
+                .call(&ink_e2e::bob(), quote_psp22_to_a0_with_tier, 0, None)
                 .await
-                .expect("get_generated_lp_fees failed");
+                .expect("quote_psp22_to_a0_with_tier failed")
+                .return_value()
+                .expect("quote_psp22_to_a0_with_tier returned an error");
 
-            let (psp22_fees, a0_fees) = get_generated_lp_fees_res.return_value();
+            // Validate that the discounted tier gives more A0 out
+            assert_eq!(panx_holder_quote.fee_tier, FeeTier::Discounted(0));
+            assert_eq!(panx_holder_quote.amount_out, 98039963297);
 
-            assert_eq!(psp22_fees, 9900009900);
-            assert_eq!(a0_fees, 0);
+            // Build get_fee_tier_of message for Alice, who holds the pooled token the fixture also uses as PANX
+            let get_alice_fee_tier = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| {
+                    trading_pair_azero.get_fee_tier_of(Some(get_alice_account_id()))
+                });
 
-            // Get overall generated fees by the contract
-            let get_contract_overall_generated_fee = build_message::<TradingPairAzeroRef>(
-                tpa_acc_id.clone(),
-            )
-            .call(|trading_pair_azero| trading_pair_azero.get_contract_overall_generated_fee());
+            let alice_fee_tier = client
+                .call(&ink_e2e::alice(), get_alice_fee_tier, 0, None)
+                .await
+                .expect("get_fee_tier_of failed")
+                .return_value();
 
-            let get_contract_overall_generated_fee_res = client
-                .call(
-                    &ink_e2e::alice(),
-                    get_contract_overall_generated_fee,
-                    0,
-                    None,
-                )
+            // without a discount source, Alice's 9900 PANX in her wallet give no discount
+            assert_eq!(alice_fee_tier, FeeTier::Standard);
+
+            // Build get_fee_tier_of message for Bob, who holds no PANX
+            let get_bob_fee_tier = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| {
+                    trading_pair_azero.get_fee_tier_of(Some(get_bob_account_id()))
+                });
+
+            let bob_fee_tier = client
+                .call(&ink_e2e::alice(), get_bob_fee_tier, 0, None)
                 .await
-                .expect("get_contract_overall_generated_fee failed");
+                .expect("get_fee_tier_of failed")
+                .return_value();
 
-            let (psp22_fees, a0_fees) = get_contract_overall_generated_fee_res.return_value();
+            assert_eq!(bob_fee_tier, FeeTier::Standard);
 
-            assert_eq!(psp22_fees, 9900009900);
-            assert_eq!(a0_fees, 0);
+            Ok(())
+        }
 
-            // Get MyPsp22 difference by percentage
-            let get_psp22_difference_by_percentage = build_message::<TradingPairAzeroRef>(
-                tpa_acc_id.clone(),
-            )
-            .call(|trading_pair_azero| trading_pair_azero.get_psp22_difference_by_percentage());
+        /// Tests included in "reentrant_calls_are_rejected":
+        /// 1. provide_to_pool with a PSP22 token that calls back into the pair
+        /// 2. swap_psp22 with a PSP22 token that calls back into the pair
+        #[ink_e2e::test(additional_contracts = "../reentrant_psp22/Cargo.toml")]
+        async fn reentrant_calls_are_rejected(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Hostile PSP22 token constructor object
+            let psp22_constructor = ReentrantPsp22Ref::new(10000000000000000);
 
-            let get_psp22_difference_by_percentage_res = client
-                .call(
-                    &ink_e2e::alice(),
-                    get_psp22_difference_by_percentage,
-                    0,
-                    None,
-                )
+            // Instantiate new hostile PSP22 token using the constructor
+            let psp22_acc_id = client
+                .instantiate("reentrant_psp22", &ink_e2e::alice(), psp22_constructor, 0, None)
                 .await
-                .expect("get_psp22_difference_by_percentage failed");
+                .expect("instantiate failed")
+                .account_id;
 
-            let Some(psp22_lp_fee_diff) = get_psp22_difference_by_percentage_res.return_value().ok() else {
-                panic!("failed!")
-            };
+            // Instantiate a 1% LP fee pair of the hostile token without LP lock
+            let tpa_acc_id = instantiate_pair(&mut client, psp22_acc_id, 0).await;
 
-            assert_eq!(psp22_lp_fee_diff, 9997500600);
+            // Make the hostile token approve LP tokens on every transfer the pair makes
+            let set_target = build_message::<ReentrantPsp22Ref>(psp22_acc_id.clone())
+                .call(|reentrant_psp22| reentrant_psp22.set_target(tpa_acc_id, ReentryCall::Approve));
 
-            // Withdraw from the pool
-            let withdraw_from_pool = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
-                .call(|trading_pair_azero| trading_pair_azero.withdraw_specific_amount(amount));
+            client
+                .call(&ink_e2e::alice(), set_target, 0, None)
+                .await
+                .expect("calling `set_target` failed");
+
+            // Build PSP22 approve message: Alice approves trading pair for 200 PSP22 tokens
+            let approve_psp22 = build_message::<ReentrantPsp22Ref>(psp22_acc_id.clone())
+                .call(|reentrant_psp22| reentrant_psp22.approve(tpa_acc_id, 200000000000000));
 
             client
-                .call(&ink_e2e::alice(), withdraw_from_pool, 0, None)
+                .call(&ink_e2e::alice(), approve_psp22, 0, None)
                 .await
-                .expect("withdraw_from_pool failed");
+                .expect("calling `approve_psp22` failed");
 
-            // Get account overall LP fee rewards
-            let get_account_overall_lp_fee_rewards = build_message::<TradingPairAzeroRef>(
-                tpa_acc_id.clone(),
-            )
-            .call(|trading_pair_azero| {
-                trading_pair_azero.get_account_overall_lp_fee_rewards(get_alice_account_id())
-            });
+            // Build TPA's provide to pool message: (100 PSP22 tokens, 10 A0, 1000 as expected LP tokens shares, and 0.5 as slippage)
+            let provide_to_tpa = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone()).call(
+                |trading_pair_azero| {
+                    trading_pair_azero.provide_to_pool(
+                        100000000000000,
+                        10000000000000,
+                        1000000000000000,
+                        500000000000,
+                    )
+                },
+            );
 
-            let get_account_overall_lp_fee_rewards_res = client
-                .call(
-                    &ink_e2e::alice(),
-                    get_account_overall_lp_fee_rewards,
-                    0,
-                    None,
-                )
+            // Amount of native coin to transfer in the provide to pool call
+            let amount: u128 = 10000000000000;
+
+            // The provision itself goes through, only the nested call is rejected
+            client
+                .call(&ink_e2e::alice(), provide_to_tpa, amount, None)
                 .await
-                .expect("get_account_overall_lp_fee_rewards failed");
+                .expect("calling `provide_to_tpa` failed");
 
-            let (psp22_fees, a0_fees) = get_account_overall_lp_fee_rewards_res.return_value();
+            // Build TPA's swap PSP22 message: (1 PSP22 token, 0.09 A0 expected, 100% slippage)
+            let swap_psp22 = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone()).call(
+                |trading_pair_azero| {
+                    trading_pair_azero.swap_psp22(1000000000000, 90000000000, 100000000000000)
+                },
+            );
 
-            assert_eq!(psp22_fees, 990000);
-            assert_eq!(a0_fees, 0);
+            client
+                .call(&ink_e2e::alice(), swap_psp22, 0, None)
+                .await
+                .expect("calling `swap_psp22` failed");
 
-            // Get PSP22 LP fee tokens
-            let get_psp22_lp_fee_tokens = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
-                .call(|trading_pair_azero| trading_pair_azero.get_psp22_lp_fee_tokens(amount));
+            // Get the number of calls the hostile token made back into the pair
+            let get_reentry_attempts = build_message::<ReentrantPsp22Ref>(psp22_acc_id.clone())
+                .call(|reentrant_psp22| reentrant_psp22.get_reentry_attempts());
 
-            let get_psp22_lp_fee_tokens_res = client
-                .call(&ink_e2e::alice(), get_psp22_lp_fee_tokens, 0, None)
+            let reentry_attempts = client
+                .call(&ink_e2e::alice(), get_reentry_attempts, 0, None)
                 .await
-                .expect("get_psp22_lp_fee_tokens failed");
+                .expect("get_reentry_attempts failed")
+                .return_value();
 
-            let Some(psp22_lp_fee) = get_psp22_lp_fee_tokens_res.return_value().ok() else {
-                panic!("failed!")
-            };
+            // Get the number of calls back into the pair that succeeded
+            let get_successful_reentries = build_message::<ReentrantPsp22Ref>(psp22_acc_id.clone())
+                .call(|reentrant_psp22| reentrant_psp22.get_successful_reentries());
 
-            assert_eq!(psp22_lp_fee, 990000);
+            let successful_reentries = client
+                .call(&ink_e2e::alice(), get_successful_reentries, 0, None)
+                .await
+                .expect("get_successful_reentries failed")
+                .return_value();
 
-            Ok(())
-        }
+            // the token tried to re-enter during both the provision and the swap, and failed every time
+            assert!(reentry_attempts >= 2);
+            assert_eq!(successful_reentries, 0);
 
-        /// Tests included in 'transfer_lp_tokens_works'
-        /// 1. provide_to_tpa
-        /// 2. get_lp_token_of
-        /// 3. transfer_lp_tokens
-        /// 4. get_lp_token_of
-        #[ink_e2e::test(additional_contracts = "../my_psp22/Cargo.toml")]
-        async fn transfer_lp_tokens_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
-            // Instantiate MyPsp22 contract
-            let psp22_constructor = MyPsp22Ref::new(
-                10000000000000000,
-                Some(String::from("TOKEN").into()),
-                Some(String::from("TKN").into()),
-                12,
-            );
+            // the re-entrant LP approval did not go through
+            let get_allowance = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| {
+                    trading_pair_azero.allowance(psp22_acc_id, psp22_acc_id)
+                });
+
+            let allowance = client
+                .call(&ink_e2e::alice(), get_allowance, 0, None)
+                .await
+                .expect("get_allowance failed")
+                .return_value();
+
+            assert_eq!(allowance, 0);
+
+            Ok(())
+        }
+
+        /// Tests included in "reentrant_swaps_are_rejected":
+        /// 1. provide_to_pool with a PSP22 token that swaps on the pair mid-provision
+        /// 2. swap_psp22 with a PSP22 token that swaps on the pair mid-swap
+        #[ink_e2e::test(additional_contracts = "../reentrant_psp22/Cargo.toml")]
+        async fn reentrant_swaps_are_rejected(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Hostile PSP22 token constructor object
+            let psp22_constructor = ReentrantPsp22Ref::new(10000000000000000);
+
+            // Instantiate new hostile PSP22 token using the constructor
             let psp22_acc_id = client
-                .instantiate("my_psp22", &ink_e2e::alice(), psp22_constructor, 0, None)
+                .instantiate("reentrant_psp22", &ink_e2e::alice(), psp22_constructor, 0, None)
                 .await
                 .expect("instantiate failed")
                 .account_id;
 
-            // Instantiate TradingPairAzero contract
-            let tpa_constructor = TradingPairAzeroRef::new(
-                psp22_acc_id.clone(),
-                1000000000000,
-                psp22_acc_id.clone(),
-                get_charlie_account_id(),
-            );
-            let tpa_acc_id = client
-                .instantiate(
-                    "trading_pair_azero",
-                    &ink_e2e::alice(),
-                    tpa_constructor,
-                    0,
-                    None,
-                )
+            // Instantiate a 1% LP fee pair of the hostile token without LP lock
+            let tpa_acc_id = instantiate_pair(&mut client, psp22_acc_id, 0).await;
+
+            // Make the hostile token swap on the pair on every transfer the pair makes
+            let set_target = build_message::<ReentrantPsp22Ref>(psp22_acc_id.clone())
+                .call(|reentrant_psp22| reentrant_psp22.set_target(tpa_acc_id, ReentryCall::SwapPsp22));
+
+            client
+                .call(&ink_e2e::alice(), set_target, 0, None)
                 .await
-                .expect("instantiate failed")
-                .account_id;
+                .expect("calling `set_target` failed");
+
+            // Build PSP22 approve message: Alice approves trading pair for 200 PSP22 tokens
+            let approve_psp22 = build_message::<ReentrantPsp22Ref>(psp22_acc_id.clone())
+                .call(|reentrant_psp22| reentrant_psp22.approve(tpa_acc_id, 200000000000000));
 
-            // Approve MyPsp22 contract to provide LP tokens to TradingPairAzero
-            let approve_psp22_to_provide_lp = build_message::<MyPsp22Ref>(psp22_acc_id.clone())
-                .call(|my_psp22| my_psp22.approve(tpa_acc_id.clone(), 100000000000000));
             client
-                .call(&ink_e2e::alice(), approve_psp22_to_provide_lp, 0, None)
+                .call(&ink_e2e::alice(), approve_psp22, 0, None)
                 .await
-                .expect("calling `approve_psp22_to_provide_lp` failed");
+                .expect("calling `approve_psp22` failed");
 
-            // Provide LP tokens to TradingPairAzero
+            // Build TPA's provide to pool message: (100 PSP22 tokens, 10 A0, 1000 as expected LP tokens shares, and 0.5 as slippage)
             let provide_to_tpa = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone()).call(
                 |trading_pair_azero| {
                     trading_pair_azero.provide_to_pool(
                         100000000000000,
+                        10000000000000,
                         1000000000000000,
                         500000000000,
                     )
                 },
             );
+
+            // Amount of native coin to transfer in the provide to pool call
             let amount: u128 = 10000000000000;
+
+            // The provision itself goes through, only the nested swap is rejected
             client
                 .call(&ink_e2e::alice(), provide_to_tpa, amount, None)
                 .await
                 .expect("calling `provide_to_tpa` failed");
 
-            // Check LP token balance for Alice
-            let get_lp_share_balance = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
-                .call(|trading_pair_azero| {
-                    trading_pair_azero.get_lp_token_of(get_alice_account_id())
-                });
-            let get_lp_share_res = client
-                .call(&ink_e2e::alice(), get_lp_share_balance, 0, None)
-                .await
-                .expect("get_lp_share_balance failed");
-
-            assert_eq!(get_lp_share_res.return_value(), 1000000000000000);
-
-            // Transfer LP tokens from Alice to Bob
-            let transfer_lp_tokens = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone()).call(
+            // Build TPA's swap PSP22 message: (1 PSP22 token, 0.09 A0 expected, 100% slippage)
+            let swap_psp22 = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone()).call(
                 |trading_pair_azero| {
-                    trading_pair_azero.transfer_lp_tokens(get_bob_account_id(), 500000000000000)
+                    trading_pair_azero.swap_psp22(1000000000000, 90000000000, 100000000000000)
                 },
             );
+
+            // The swap itself goes through, only the nested swap is rejected
             client
-                .call(&ink_e2e::alice(), transfer_lp_tokens, 0, None)
+                .call(&ink_e2e::alice(), swap_psp22, 0, None)
                 .await
-                .expect("calling `transfer_lp_tokens` failed");
+                .expect("calling `swap_psp22` failed");
 
-            // Check LP token balance for Alice after transfer
-            let get_lp_share_balance = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
-                .call(|trading_pair_azero| {
-                    trading_pair_azero.get_lp_token_of(get_alice_account_id())
-                });
-            let get_lp_share_res = client
-                .call(&ink_e2e::alice(), get_lp_share_balance, 0, None)
+            // Get the number of calls the hostile token made back into the pair
+            let get_reentry_attempts = build_message::<ReentrantPsp22Ref>(psp22_acc_id.clone())
+                .call(|reentrant_psp22| reentrant_psp22.get_reentry_attempts());
+
+            let reentry_attempts = client
+                .call(&ink_e2e::alice(), get_reentry_attempts, 0, None)
                 .await
-                .expect("get_lp_share_balance failed");
+                .expect("get_reentry_attempts failed")
+                .return_value();
 
-            assert_eq!(get_lp_share_res.return_value(), 500000000000000);
+            // Get the number of calls back into the pair that succeeded
+            let get_successful_reentries = build_message::<ReentrantPsp22Ref>(psp22_acc_id.clone())
+                .call(|reentrant_psp22| reentrant_psp22.get_successful_reentries());
 
-            // Check LP token balance for Bob after transfer
-            let get_lp_share_balance = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
-                .call(|trading_pair_azero| {
-                    trading_pair_azero.get_lp_token_of(get_bob_account_id())
-                });
-            let get_lp_share_res = client
-                .call(&ink_e2e::alice(), get_lp_share_balance, 0, None)
+            let successful_reentries = client
+                .call(&ink_e2e::alice(), get_successful_reentries, 0, None)
                 .await
-                .expect("get failed");
+                .expect("get_successful_reentries failed")
+                .return_value();
 
-            assert_eq!(get_lp_share_res.return_value(), 500000000000000);
+            // the token tried to swap during both the provision and the swap, and failed every time
+            assert!(reentry_attempts >= 2);
+            assert_eq!(successful_reentries, 0);
+
+            // Build get_swap_stats message to fetch the swaps the pair recorded
+            let get_swap_stats = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| trading_pair_azero.get_swap_stats());
+
+            let swap_stats = client
+                .call(&ink_e2e::alice(), get_swap_stats, 0, None)
+                .await
+                .expect("get_swap_stats failed")
+                .return_value();
+
+            // only Alice's swap went through
+            assert_eq!(swap_stats.psp22_to_a0_swaps, 1);
+            assert_eq!(swap_stats.psp22_volume, 1000000000000);
 
             Ok(())
         }
 
-        /// Tests included in 'transfer_lp_tokens_from_to_works'
-        /// 1. provide_to_pool
-        /// 2. get_lp_token_of
-        /// 3. approve_lp_tokens
-        /// 4. get_lp_tokens_allowance
-        /// 5. transfer_lp_tokens_from_to
-        /// 6.
-        #[ink_e2e::test(additional_contracts = "../my_psp22/Cargo.toml")]
-        async fn transfer_lp_tokens_from_to_works(
+        /// Tests included in "reentrant_withdrawals_are_rejected":
+        /// 1. provide_to_pool and an LP transfer to a PSP22 token that withdraws from the pair
+        /// 2. swap_psp22 with the token withdrawing its LP shares mid-swap
+        /// 3. withdraw_specific_amount with the token withdrawing its LP shares mid-withdrawal
+        #[ink_e2e::test(additional_contracts = "../reentrant_psp22/Cargo.toml")]
+        async fn reentrant_withdrawals_are_rejected(
             mut client: ink_e2e::Client<C, E>,
         ) -> E2EResult<()> {
-            // Instantiate MyPsp22 contract
-            let psp22_constructor = MyPsp22Ref::new(
-                10000000000000000,
-                Some(String::from("TOKEN").into()),
-                Some(String::from("TKN").into()),
-                12,
-            );
+            // Hostile PSP22 token constructor object
+            let psp22_constructor = ReentrantPsp22Ref::new(10000000000000000);
 
+            // Instantiate new hostile PSP22 token using the constructor
             let psp22_acc_id = client
-                .instantiate("my_psp22", &ink_e2e::alice(), psp22_constructor, 0, None)
+                .instantiate("reentrant_psp22", &ink_e2e::alice(), psp22_constructor, 0, None)
                 .await
                 .expect("instantiate failed")
                 .account_id;
 
-            // Instantiate TradingPairAzero contract
-            let tpa_constructor = TradingPairAzeroRef::new(
-                psp22_acc_id,
-                1000000000000,
-                psp22_acc_id,
-                get_charlie_account_id(),
+            // Instantiate a 1% LP fee pair of the hostile token without LP lock
+            let tpa_acc_id = instantiate_pair(&mut client, psp22_acc_id, 0).await;
+
+            // Make the hostile token withdraw its LP shares on every transfer the pair makes
+            let set_target = build_message::<ReentrantPsp22Ref>(psp22_acc_id.clone()).call(
+                |reentrant_psp22| {
+                    reentrant_psp22.set_target(tpa_acc_id, ReentryCall::WithdrawSpecificAmount)
+                },
             );
 
-            let tpa_acc_id = client
-                .instantiate(
-                    "trading_pair_azero",
-                    &ink_e2e::alice(),
-                    tpa_constructor,
-                    0,
-                    None,
-                )
+            client
+                .call(&ink_e2e::alice(), set_target, 0, None)
                 .await
-                .expect("instantiate failed")
-                .account_id;
+                .expect("calling `set_target` failed");
 
-            // Approve MyPsp22 to provide LP tokens to TradingPairAzero
-            let approve_psp22_to_provide_lp = build_message::<MyPsp22Ref>(psp22_acc_id.clone())
-                .call(|my_psp22| my_psp22.approve(tpa_acc_id, 100000000000000));
+            // Build PSP22 approve message: Alice approves trading pair for 200 PSP22 tokens
+            let approve_psp22 = build_message::<ReentrantPsp22Ref>(psp22_acc_id.clone())
+                .call(|reentrant_psp22| reentrant_psp22.approve(tpa_acc_id, 200000000000000));
 
             client
-                .call(&ink_e2e::alice(), approve_psp22_to_provide_lp, 0, None)
+                .call(&ink_e2e::alice(), approve_psp22, 0, None)
                 .await
-                .expect("calling `approve_psp22_to_provide_lp` failed");
+                .expect("calling `approve_psp22` failed");
 
-            // Provide LP tokens to TradingPairAzero
+            // Build TPA's provide to pool message: (100 PSP22 tokens, 10 A0, 1000 as expected LP tokens shares, and 0.5 as slippage)
             let provide_to_tpa = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone()).call(
                 |trading_pair_azero| {
                     trading_pair_azero.provide_to_pool(
                         100000000000000,
+                        10000000000000,
                         1000000000000000,
                         500000000000,
                     )
                 },
             );
 
+            // Amount of native coin to transfer in the provide to pool call
             let amount: u128 = 10000000000000;
 
             client
@@ -4485,87 +8474,452 @@ pub mod trading_pair_azero {
                 .await
                 .expect("calling `provide_to_tpa` failed");
 
-            // Get LP share balance of Alice
-            let get_lp_share_balance = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+            // LP shares the hostile token tries to withdraw
+            let token_shares: Balance = 1000000000000;
+
+            // Build LP transfer message: Alice gives the hostile token LP shares to withdraw
+            let transfer_lp = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
                 .call(|trading_pair_azero| {
-                    trading_pair_azero.get_lp_token_of(get_alice_account_id())
+                    trading_pair_azero.transfer(psp22_acc_id, token_shares, vec![])
                 });
 
-            let get_lp_share_res = client
-                .call(&ink_e2e::alice(), get_lp_share_balance, 0, None)
+            client
+                .call(&ink_e2e::alice(), transfer_lp, 0, None)
                 .await
-                .expect("get_lp_share_balance failed");
-
-            assert_eq!(get_lp_share_res.return_value(), 1000000000000000);
+                .expect("calling `transfer_lp` failed");
 
-            // Approve LP tokens for Bob
-            let approve_lp_tokens = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone()).call(
+            // Build TPA's swap PSP22 message: (1 PSP22 token, 0.09 A0 expected, 100% slippage)
+            let swap_psp22 = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone()).call(
                 |trading_pair_azero| {
-                    trading_pair_azero.approve_lp_tokens(get_bob_account_id(), 500000000000000)
+                    trading_pair_azero.swap_psp22(1000000000000, 90000000000, 100000000000000)
                 },
             );
 
+            // The swap itself goes through, only the nested withdrawal is rejected
             client
-                .call(&ink_e2e::alice(), approve_lp_tokens, 0, None)
+                .call(&ink_e2e::alice(), swap_psp22, 0, None)
                 .await
-                .expect("calling `approve_lp_tokens` failed");
+                .expect("calling `swap_psp22` failed");
 
-            // Get LP tokens allowance from Alice to Bob
-            let get_lp_tokens_allowance = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+            // Build withdraw message: Alice withdraws some of her shares, the pair sends her PSP22
+            let withdraw = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
                 .call(|trading_pair_azero| {
-                    trading_pair_azero
-                        .get_lp_tokens_allowance(get_alice_account_id(), get_bob_account_id())
+                    trading_pair_azero.withdraw_specific_amount(500000000000000)
                 });
 
-            let get_lp_tokens_allowance_res = client
-                .call(&ink_e2e::alice(), get_lp_tokens_allowance, 0, None)
+            // The withdrawal itself goes through, only the nested withdrawal is rejected
+            client
+                .call(&ink_e2e::alice(), withdraw, 0, None)
                 .await
-                .expect("get_lp_tokens_allowance failed");
+                .expect("calling `withdraw` failed");
 
-            assert_eq!(get_lp_tokens_allowance_res.return_value(), 500000000000000);
+            // Get the number of calls back into the pair that succeeded
+            let get_successful_reentries = build_message::<ReentrantPsp22Ref>(psp22_acc_id.clone())
+                .call(|reentrant_psp22| reentrant_psp22.get_successful_reentries());
 
-            // Transfer LP tokens from Alice to Bob
-            let transfer_lp_tokens_from_to = build_message::<TradingPairAzeroRef>(
-                tpa_acc_id.clone(),
-            )
-            .call(|trading_pair_azero| {
-                trading_pair_azero.transfer_lp_tokens_from_to(
-                    get_alice_account_id(),
-                    get_bob_account_id(),
-                    500000000000000,
-                )
-            });
+            let successful_reentries = client
+                .call(&ink_e2e::alice(), get_successful_reentries, 0, None)
+                .await
+                .expect("get_successful_reentries failed")
+                .return_value();
+
+            // Get the number of calls the hostile token made back into the pair
+            let get_reentry_attempts = build_message::<ReentrantPsp22Ref>(psp22_acc_id.clone())
+                .call(|reentrant_psp22| reentrant_psp22.get_reentry_attempts());
+
+            let reentry_attempts = client
+                .call(&ink_e2e::alice(), get_reentry_attempts, 0, None)
+                .await
+                .expect("get_reentry_attempts failed")
+                .return_value();
+
+            // the token tried to withdraw during the provision, the swap and the withdrawal, and failed every time
+            assert!(reentry_attempts >= 3);
+            assert_eq!(successful_reentries, 0);
+
+            // Build balance_of message to fetch the LP shares of the hostile token
+            let get_token_shares = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| trading_pair_azero.balance_of(psp22_acc_id));
+
+            let token_shares_after = client
+                .call(&ink_e2e::alice(), get_token_shares, 0, None)
+                .await
+                .expect("balance_of failed")
+                .return_value();
+
+            // none of the hostile token's shares were withdrawn
+            assert_eq!(token_shares_after, token_shares);
+
+            Ok(())
+        }
+
+        /// Tests included in "get_reserves_works":
+        /// 1. provide_to_pool stores the reserves
+        /// 2. swap_psp22 stores the reserves
+        /// 3. get_reserves
+        #[ink_e2e::test(additional_contracts = "../my_psp22/Cargo.toml")]
+        async fn get_reserves_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Instantiate a PSP22 token and a 1% LP fee pair of it without LP lock, then provide 100 PSP22 tokens and 10 A0 from Alice
+            let (psp22_acc_id, tpa_acc_id) = instantiate_provided_pair(&mut client, 0).await;
+
+            // Build get_reserves message
+            let get_reserves = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| trading_pair_azero.get_reserves());
+
+            let reserves_after_provide = client
+                .call(&ink_e2e::alice(), get_reserves, 0, None)
+                .await
+                .expect("get_reserves failed")
+                .return_value();
+
+            // the reserves stored by the provision are the deposited amounts (plus the contract's existential deposit)
+            assert_eq!(reserves_after_provide, (10001000000000, 100000000000000));
+
+            // Build PSP22 approve message: Alice approves trading pair for 1 more PSP22 token
+            let approve_psp22_to_swap = build_message::<MyPsp22Ref>(psp22_acc_id.clone())
+                .call(|my_psp22| my_psp22.approve(tpa_acc_id, 1000000000000));
 
             client
-                .call(&ink_e2e::alice(), transfer_lp_tokens_from_to, 0, None)
+                .call(&ink_e2e::alice(), approve_psp22_to_swap, 0, None)
                 .await
-                .expect("calling `transfer_lp_tokens_from_to` failed");
+                .expect("calling `approve_psp22_to_swap` failed");
 
-            // Get LP share balance of Alice after transfer
-            let get_lp_share_balance = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+            // Build TPA's swap PSP22 message: (1 PSP22 token, 0.09 A0 expected, 100% slippage)
+            let swap_psp22 = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone()).call(
+                |trading_pair_azero| {
+                    trading_pair_azero.swap_psp22(1000000000000, 90000000000, 100000000000000)
+                },
+            );
+
+            client
+                .call(&ink_e2e::alice(), swap_psp22, 0, None)
+                .await
+                .expect("calling `swap_psp22` failed");
+
+            // Build get_pool_state message to compare the stored reserves with the live balances
+            let get_pool_state = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| trading_pair_azero.get_pool_state());
+
+            let pool_state = client
+                .call(&ink_e2e::alice(), get_pool_state, 0, None)
+                .await
+                .expect("get_pool_state failed")
+                .return_value();
+
+            let get_reserves = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| trading_pair_azero.get_reserves());
+
+            let reserves_after_swap = client
+                .call(&ink_e2e::alice(), get_reserves, 0, None)
+                .await
+                .expect("get_reserves failed")
+                .return_value();
+
+            // the swap stored the reserves it left in the pool
+            assert_eq!(
+                reserves_after_swap,
+                (pool_state.a0_reserve, pool_state.psp22_reserve)
+            );
+            assert!(reserves_after_swap.0 < reserves_after_provide.0);
+            assert!(reserves_after_swap.1 > reserves_after_provide.1);
+
+            Ok(())
+        }
+
+        /// Tests included in "deployer_lp_lock_follows_the_shares":
+        /// 1. provide_to_pool by the deployer before the LP lock timestamp
+        /// 2. get_lp_lock and get_locked_liquidity
+        /// 3. transfer and withdraw_specific_amount of locked shares
+        #[ink_e2e::test(additional_contracts = "../my_psp22/Cargo.toml")]
+        async fn deployer_lp_lock_follows_the_shares(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            // Instantiate a PSP22 token and a 1% LP fee pair of it with the LP locked until 2100, then provide 100 PSP22 tokens and 10 A0 from Alice
+            let (_, tpa_acc_id) = instantiate_provided_pair(&mut client, 4102444800).await;
+
+            // Build get_lp_lock message for the deployer (Alice)
+            let get_lp_lock = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| trading_pair_azero.get_lp_lock(get_alice_account_id()));
+
+            let lp_lock = client
+                .call(&ink_e2e::alice(), get_lp_lock, 0, None)
+                .await
+                .expect("get_lp_lock failed")
+                .return_value();
+
+            // all the shares given to the deployer are locked until the LP lock timestamp
+            assert_eq!(lp_lock.shares, 1000000000000000);
+            assert_eq!(lp_lock.unlock_timestamp, 4102444800);
+
+            // Build get_locked_liquidity message for the deployer (Alice)
+            let get_locked_liquidity = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
                 .call(|trading_pair_azero| {
-                    trading_pair_azero.get_lp_token_of(get_alice_account_id())
+                    trading_pair_azero.get_locked_liquidity(get_alice_account_id())
                 });
 
-            let get_lp_share_res = client
-                .call(&ink_e2e::alice(), get_lp_share_balance, 0, None)
+            let Some(locked_liquidity) = client
+                .call(&ink_e2e::alice(), get_locked_liquidity, 0, None)
                 .await
-                .expect("get failed");
+                .expect("get_locked_liquidity failed")
+                .return_value()
+                .ok() else { panic!("test") };
 
-            assert_eq!(get_lp_share_res.return_value(), 500000000000000);
+            // the whole pool is locked
+            assert_eq!(locked_liquidity, (10001000000000, 100000000000000));
 
-            // Get LP share balance of Bob after transfer
-            let get_lp_share_balance = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+            // Build LP transfer message: Alice tries to move her locked shares to Bob
+            let transfer_lp = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
                 .call(|trading_pair_azero| {
-                    trading_pair_azero.get_lp_token_of(get_bob_account_id())
+                    trading_pair_azero.transfer(get_bob_account_id(), 1000000000000000, vec![])
                 });
 
-            let get_lp_share_res = client
-                .call(&ink_e2e::alice(), get_lp_share_balance, 0, None)
+            let transfer_lp_res = client
+                .call_dry_run(&ink_e2e::alice(), &transfer_lp, 0, None)
                 .await
-                .expect("get failed");
+                .return_value();
 
-            assert_eq!(get_lp_share_res.return_value(), 500000000000000);
+            assert_eq!(
+                transfer_lp_res,
+                Err(PSP22Error::Custom(String::from("LpStillLocked").into()))
+            );
+
+            // Build withdraw message: Alice tries to withdraw her locked shares
+            let withdraw = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                .call(|trading_pair_azero| {
+                    trading_pair_azero.withdraw_specific_amount(1000000000000000)
+                });
+
+            let withdraw_res = client
+                .call_dry_run(&ink_e2e::alice(), &withdraw, 0, None)
+                .await
+                .return_value();
+
+            assert_eq!(withdraw_res, Err(TradingPairErrors::LpStillLocked));
+
+            Ok(())
+        }
+
+        /// Tests included in "randomized_pool_messages_keep_invariant":
+        /// 1. random sequences of swap_psp22, swap_a0, provide_to_pool, withdraw_specific_amount and redeem_redeemable_amount
+        /// 2. get_reserves matches get_pool_state after every message
+        /// 3. swaps never lower the constant product, deposits and withdrawals never lower the reserves per share
+        /// 4. a redeem only takes the PSP22 paid to the caller from the pool
+        #[ink_e2e::test(additional_contracts = "../my_psp22/Cargo.toml")]
+        async fn randomized_pool_messages_keep_invariant(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            // Instantiate a PSP22 token and a 1% LP fee pair of it without LP lock, then provide 100 PSP22 tokens and 10 A0 from Alice
+            let (psp22_acc_id, tpa_acc_id) = instantiate_provided_pair(&mut client, 0).await;
+
+            // Build PSP22 approve message: Alice approves trading pair for all her PSP22 tokens
+            let approve_psp22 = build_message::<MyPsp22Ref>(psp22_acc_id.clone())
+                .call(|my_psp22| my_psp22.approve(tpa_acc_id, 10000000000000000));
+
+            client
+                .call(&ink_e2e::alice(), approve_psp22, 0, None)
+                .await
+                .expect("calling `approve_psp22` failed");
+
+            // 1% slippage for the quotes of the random messages
+            let slippage: Balance = 1000000000000;
+
+            let mut rng = Rng(42);
+
+            for _ in 0..50 {
+                let state_before = query_pool_state(&mut client, tpa_acc_id).await;
+                let alice_psp22_before =
+                    query_psp22_balance_of(&mut client, psp22_acc_id, get_alice_account_id()).await;
+
+                let step = rng.next() % 5;
+
+                match step {
+                    0 => {
+                        let psp22_amount_in = rng.amount(state_before.psp22_reserve / 10);
+
+                        let get_est_price_psp22_to_a0 =
+                            build_message::<TradingPairAzeroRef>(tpa_acc_id.clone()).call(
+                                |trading_pair_azero| {
+                                    trading_pair_azero.get_est_price_psp22_to_a0(psp22_amount_in)
+                                },
+                            );
+
+                        let Some(a0_amount_out) = client
+                            .call_dry_run(&ink_e2e::alice(), &get_est_price_psp22_to_a0, 0, None)
+                            .await
+                            .return_value()
+                            .ok() else { panic!("get_est_price_psp22_to_a0 failed") };
+
+                        let swap_psp22 = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                            .call(|trading_pair_azero| {
+                                trading_pair_azero.swap_psp22(
+                                    psp22_amount_in,
+                                    a0_amount_out,
+                                    slippage,
+                                )
+                            });
+
+                        client
+                            .call(&ink_e2e::alice(), swap_psp22, 0, None)
+                            .await
+                            .expect("calling `swap_psp22` failed");
+                    }
+                    1 => {
+                        let a0_amount_in = rng.amount(state_before.a0_reserve / 10);
+
+                        let get_est_price_a0_to_psp22_for_swap =
+                            build_message::<TradingPairAzeroRef>(tpa_acc_id.clone()).call(
+                                |trading_pair_azero| {
+                                    trading_pair_azero
+                                        .get_est_price_a0_to_psp22_for_swap(a0_amount_in)
+                                },
+                            );
+
+                        let Some(psp22_amount_out) = client
+                            .call_dry_run(
+                                &ink_e2e::alice(),
+                                &get_est_price_a0_to_psp22_for_swap,
+                                0,
+                                None,
+                            )
+                            .await
+                            .return_value()
+                            .ok() else { panic!("get_est_price_a0_to_psp22_for_swap failed") };
+
+                        let swap_a0 = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                            .call(|trading_pair_azero| {
+                                trading_pair_azero.swap_a0(psp22_amount_out, slippage)
+                            });
+
+                        client
+                            .call(&ink_e2e::alice(), swap_a0, a0_amount_in, None)
+                            .await
+                            .expect("calling `swap_a0` failed");
+                    }
+                    2 => {
+                        // a deposit at the pool ratio
+                        let a0_deposit_amount = rng.amount(state_before.a0_reserve / 5);
+                        let psp22_deposit_amount = a0_deposit_amount * state_before.psp22_reserve
+                            / state_before.a0_reserve
+                            + 1;
+
+                        let get_expected_lp_token_amount =
+                            build_message::<TradingPairAzeroRef>(tpa_acc_id.clone()).call(
+                                |trading_pair_azero| {
+                                    trading_pair_azero.get_expected_lp_token_amount(
+                                        a0_deposit_amount,
+                                        psp22_deposit_amount,
+                                    )
+                                },
+                            );
+
+                        let Some(expected_lp_tokens) = client
+                            .call_dry_run(&ink_e2e::alice(), &get_expected_lp_token_amount, 0, None)
+                            .await
+                            .return_value()
+                            .ok() else { panic!("get_expected_lp_token_amount failed") };
+
+                        let provide_to_tpa =
+                            build_message::<TradingPairAzeroRef>(tpa_acc_id.clone()).call(
+                                |trading_pair_azero| {
+                                    trading_pair_azero.provide_to_pool(
+                                        psp22_deposit_amount,
+                                        a0_deposit_amount,
+                                        expected_lp_tokens,
+                                        slippage,
+                                    )
+                                },
+                            );
+
+                        client
+                            .call(&ink_e2e::alice(), provide_to_tpa, a0_deposit_amount, None)
+                            .await
+                            .expect("calling `provide_to_tpa` failed");
+                    }
+                    3 => {
+                        // Alice is the only LP, she withdraws up to half of her shares
+                        let get_lp_token_of = build_message::<TradingPairAzeroRef>(
+                            tpa_acc_id.clone(),
+                        )
+                        .call(|trading_pair_azero| {
+                            trading_pair_azero.get_lp_token_of(get_alice_account_id())
+                        });
+
+                        let alice_shares = client
+                            .call_dry_run(&ink_e2e::alice(), &get_lp_token_of, 0, None)
+                            .await
+                            .return_value();
+
+                        let shares = rng.amount(alice_shares / 2);
+
+                        let withdraw = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                            .call(|trading_pair_azero| {
+                                trading_pair_azero.withdraw_specific_amount(shares)
+                            });
+
+                        client
+                            .call(&ink_e2e::alice(), withdraw, 0, None)
+                            .await
+                            .expect("calling `withdraw_specific_amount` failed");
+                    }
+                    _ => {
+                        let redeem = build_message::<TradingPairAzeroRef>(tpa_acc_id.clone())
+                            .call(|trading_pair_azero| trading_pair_azero.redeem_redeemable_amount());
+
+                        client
+                            .call(&ink_e2e::alice(), redeem, 0, None)
+                            .await
+                            .expect("calling `redeem_redeemable_amount` failed");
+                    }
+                }
+
+                let state_after = query_pool_state(&mut client, tpa_acc_id).await;
+                let reserves_after = query_reserves(&mut client, tpa_acc_id).await;
+
+                // every message stores the reserves it left in the pool
+                assert_eq!(
+                    reserves_after,
+                    (state_after.a0_reserve, state_after.psp22_reserve)
+                );
+
+                match step {
+                    0 | 1 => {
+                        // swaps keep the LP supply and never lower the constant product
+                        assert_eq!(state_after.total_supply, state_before.total_supply);
+                        assert!(
+                            U256::from(state_after.a0_reserve) * U256::from(state_after.psp22_reserve)
+                                >= U256::from(state_before.a0_reserve)
+                                    * U256::from(state_before.psp22_reserve)
+                        );
+                    }
+                    2 | 3 => {
+                        // deposits and withdrawals never lower the reserves per share
+                        assert!(
+                            U256::from(state_after.a0_reserve) * U256::from(state_before.total_supply)
+                                >= U256::from(state_before.a0_reserve)
+                                    * U256::from(state_after.total_supply)
+                        );
+                        assert!(
+                            U256::from(state_after.psp22_reserve)
+                                * U256::from(state_before.total_supply)
+                                >= U256::from(state_before.psp22_reserve)
+                                    * U256::from(state_after.total_supply)
+                        );
+                    }
+                    _ => {
+                        let alice_psp22_after =
+                            query_psp22_balance_of(&mut client, psp22_acc_id, get_alice_account_id())
+                                .await;
+
+                        // the redeem only pays the incentive out of the pooled PSP22
+                        assert_eq!(state_after.a0_reserve, state_before.a0_reserve);
+                        assert_eq!(state_after.total_supply, state_before.total_supply);
+                        assert_eq!(
+                            state_before.psp22_reserve - state_after.psp22_reserve,
+                            alice_psp22_after - alice_psp22_before
+                        );
+                    }
+                }
+            }
 
             Ok(())
         }
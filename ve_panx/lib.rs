@@ -0,0 +1,770 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::ve_panx::VePanxRef;
+
+#[ink::contract]
+pub mod ve_panx {
+
+    use ink::prelude::vec; // Importing vec from ink prelude
+    use ink::storage::Mapping; // Importing Mapping from ink storage
+    use openbrush::contracts::traits::psp22::{
+        PSP22Error,
+        PSP22Ref,
+    };
+
+    /// One week in seconds, unlock timestamps are rounded down to whole weeks.
+    pub const WEEK: u64 = 7 * 86400;
+
+    /// Shortest lock (one week, in seconds).
+    pub const MIN_LOCK_TIME: u64 = WEEK;
+
+    /// Longest lock (four years, in seconds), which gets one vePANX per locked PANX.
+    pub const MAX_LOCK_TIME: u64 = 4 * 365 * 86400;
+
+    /// Most weeks walked by a checkpoint or a total supply query.
+    const MAX_WEEKS_PER_CHECKPOINT: u32 = 255;
+
+    /// PANX locked by an account and the timestamp it unlocks at.
+    #[derive(Debug, Default, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct LockedBalance {
+        pub amount: Balance, // Locked PANX
+        pub end: u64,        // Timestamp (in seconds) the PANX unlocks at, a whole week
+    }
+
+    /// Voting power at a timestamp, decaying by `slope` every second.
+    #[derive(Debug, Default, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Point {
+        pub bias: Balance,  // Voting power at the timestamp
+        pub slope: Balance, // Voting power lost every second
+        pub timestamp: u64, // Timestamp (in seconds) of the point
+    }
+
+    impl Point {
+        /// function to get the voting power of the point at a later timestamp.
+        fn bias_at(&self, timestamp: u64) -> Balance {
+            self.bias.saturating_sub(
+                self.slope
+                    .saturating_mul(timestamp.saturating_sub(self.timestamp) as Balance),
+            )
+        }
+    }
+
+    /// function to get the voting power point of a lock at a timestamp, an empty point once it expired.
+    fn get_lock_point(lock: &LockedBalance, timestamp: u64) -> Point {
+        if lock.end <= timestamp || lock.amount == 0 {
+            return Point {
+                bias: 0,
+                slope: 0,
+                timestamp,
+            }
+        }
+
+        let slope = lock.amount / MAX_LOCK_TIME as Balance;
+
+        Point {
+            bias: slope.saturating_mul((lock.end - timestamp) as Balance),
+            slope,
+            timestamp,
+        }
+    }
+
+    #[ink(storage)]
+    pub struct VePanx {
+        // PANX PSP22 token
+        panx_token: AccountId,
+        // Lock of each account
+        locks: Mapping<AccountId, LockedBalance>,
+        // Overall locked PANX
+        total_locked: Balance,
+        // Latest epoch of the overall voting power history
+        epoch: u32,
+        // Overall voting power after each epoch
+        point_history: Mapping<u32, Point>,
+        // Latest epoch of the voting power history of each account
+        user_point_epoch: Mapping<AccountId, u32>,
+        // Voting power of each account after each of its epochs
+        user_point_history: Mapping<(AccountId, u32), Point>,
+        // Decrease of the overall slope at each week the locks end at
+        slope_changes: Mapping<u64, Balance>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+    pub enum VePanxErrors {
+        ZeroAmount,                          // Error code for locking 0 PANX
+        LockExists,                          // Error code for creating a lock while the previous one was not withdrawn
+        NoLock,                              // Error code for changing a lock that does not exist
+        LockExpired,                         // Error code for adding to a lock that already ended
+        LockNotExpired,                      // Error code for withdrawing before the lock ended
+        LockTooShort,                        // Error code for a lock shorter than MIN_LOCK_TIME
+        LockTooLong,                         // Error code for a lock longer than MAX_LOCK_TIME
+        UnlockTimeNotIncreased,              // Error code for extending a lock to the same or an earlier week
+        Overflow,                            // Error code for overflow
+        PSP22TransferFromFailed(PSP22Error), // Error code for a failed transfer of PANX to the contract
+        PSP22TransferFailed(PSP22Error),     // Error code for a failed transfer of PANX back to the account
+    }
+
+    #[ink(event)]
+    pub struct Locked {
+        #[ink(topic)]
+        account: AccountId, // Address of the account
+        amount: Balance,    // PANX added to the lock
+        end: u64,           // Timestamp (in seconds) the lock unlocks at
+    }
+
+    #[ink(event)]
+    pub struct Withdrawn {
+        #[ink(topic)]
+        account: AccountId, // Address of the account
+        amount: Balance,    // PANX sent back to the account
+    }
+
+    impl VePanx {
+        #[ink(constructor)]
+        pub fn new(panx_token: AccountId) -> Self {
+            let locks = Mapping::default(); // Mapping to store the locks of accounts
+            let total_locked: Balance = 0; // Overall locked PANX
+            let epoch: u32 = 0; // No overall voting power history yet
+            let point_history = Mapping::default(); // Mapping to store the overall voting power history
+            let user_point_epoch = Mapping::default(); // Mapping to store the latest epochs of accounts
+            let user_point_history = Mapping::default(); // Mapping to store the voting power history of accounts
+            let slope_changes = Mapping::default(); // Mapping to store the slope decreases of each week
+
+            Self {
+                panx_token,
+                locks,
+                total_locked,
+                epoch,
+                point_history,
+                user_point_epoch,
+                user_point_history,
+                slope_changes,
+            }
+        }
+
+        /// function to lock `amount` PANX until `unlock_timestamp` (rounded down to a whole week), between one week and four years.
+        /// the caller has to approve the contract first.
+        #[ink(message)]
+        pub fn create_lock(
+            &mut self,
+            amount: Balance,
+            unlock_timestamp: u64,
+        ) -> Result<(), VePanxErrors> {
+            let caller = self.env().caller();
+
+            if amount == 0 {
+                return Err(VePanxErrors::ZeroAmount)
+            }
+
+            let old_lock = self.get_lock(caller);
+
+            if old_lock.amount > 0 {
+                return Err(VePanxErrors::LockExists)
+            }
+
+            let end = self.get_valid_unlock_timestamp(unlock_timestamp)?;
+
+            self.deposit_for(caller, amount, end)?;
+
+            Ok(())
+        }
+
+        /// function to add `amount` PANX to the caller's lock, keeping its unlock timestamp.
+        #[ink(message)]
+        pub fn increase_amount(&mut self, amount: Balance) -> Result<(), VePanxErrors> {
+            let caller = self.env().caller();
+
+            if amount == 0 {
+                return Err(VePanxErrors::ZeroAmount)
+            }
+
+            let lock = self.get_active_lock(caller)?;
+
+            self.deposit_for(caller, amount, lock.end)?;
+
+            Ok(())
+        }
+
+        /// function to push the unlock timestamp of the caller's lock to a later week, up to four years from now.
+        #[ink(message)]
+        pub fn increase_unlock_time(&mut self, unlock_timestamp: u64) -> Result<(), VePanxErrors> {
+            let caller = self.env().caller();
+
+            let lock = self.get_active_lock(caller)?;
+
+            let end = self.get_valid_unlock_timestamp(unlock_timestamp)?;
+
+            if end <= lock.end {
+                return Err(VePanxErrors::UnlockTimeNotIncreased)
+            }
+
+            self.deposit_for(caller, 0, end)?;
+
+            Ok(())
+        }
+
+        /// function to withdraw the caller's PANX once its lock ended.
+        #[ink(message)]
+        pub fn withdraw(&mut self) -> Result<(), VePanxErrors> {
+            let caller = self.env().caller();
+
+            let lock = self.get_lock(caller);
+
+            if lock.amount == 0 {
+                return Err(VePanxErrors::NoLock)
+            }
+
+            if self.get_current_timestamp() < lock.end {
+                return Err(VePanxErrors::LockNotExpired)
+            }
+
+            self.locks.remove(caller);
+
+            // the lock amount is part of the total
+            self.total_locked -= lock.amount;
+
+            // the expired lock has no voting power left, only the history is updated
+            self.checkpoint_account(caller, &lock, &LockedBalance::default())?;
+
+            // cross contract call to the PANX token to send the locked PANX back
+            if let Err(error) = PSP22Ref::transfer(&self.panx_token, caller, lock.amount, vec![]) {
+                return Err(VePanxErrors::PSP22TransferFailed(error))
+            }
+
+            Self::env().emit_event(Withdrawn {
+                account: caller,
+                amount: lock.amount,
+            });
+
+            Ok(())
+        }
+
+        /// function to record the overall voting power history up to now, anyone can call it.
+        #[ink(message)]
+        pub fn checkpoint(&mut self) -> Result<(), VePanxErrors> {
+            let last_point = self.checkpoint_global();
+
+            self.point_history.insert(self.epoch, &last_point);
+
+            Ok(())
+        }
+
+        /// function to get the current voting power of an account
+        #[ink(message)]
+        pub fn balance_of(&self, account: AccountId) -> Balance {
+            self.balance_of_at(account, self.get_current_timestamp())
+        }
+
+        /// function to get the voting power of an account at a timestamp, past or future.
+        #[ink(message)]
+        pub fn balance_of_at(&self, account: AccountId, timestamp: u64) -> Balance {
+            let user_epoch = self.user_point_epoch.get(account).unwrap_or(0);
+
+            let Some(epoch) = self.find_epoch(user_epoch, timestamp, |epoch| {
+                self.user_point_history.get((account, epoch))
+            }) else {
+                return 0
+            };
+
+            self.user_point_history
+                .get((account, epoch))
+                .unwrap_or_default()
+                .bias_at(timestamp)
+        }
+
+        /// function to get the current overall voting power
+        #[ink(message)]
+        pub fn total_supply(&self) -> Balance {
+            self.total_supply_at(self.get_current_timestamp())
+        }
+
+        /// function to get the overall voting power at a timestamp, past or future.
+        #[ink(message)]
+        pub fn total_supply_at(&self, timestamp: u64) -> Balance {
+            let Some(epoch) = self.find_epoch(self.epoch, timestamp, |epoch| {
+                self.point_history.get(epoch)
+            }) else {
+                return 0
+            };
+
+            let mut point = self.point_history.get(epoch).unwrap_or_default();
+
+            // walking the weeks between the point and the timestamp, applying the locks that ended
+            let mut week_timestamp = point.timestamp / WEEK * WEEK;
+
+            for _ in 0..MAX_WEEKS_PER_CHECKPOINT {
+                week_timestamp += WEEK;
+
+                let mut slope_change: Balance = 0;
+
+                if week_timestamp > timestamp {
+                    week_timestamp = timestamp;
+                } else {
+                    slope_change = self.slope_changes.get(week_timestamp).unwrap_or(0);
+                }
+
+                point.bias = point.bias_at(week_timestamp);
+                point.slope = point.slope.saturating_sub(slope_change);
+                point.timestamp = week_timestamp;
+
+                if week_timestamp == timestamp {
+                    break
+                }
+            }
+
+            point.bias
+        }
+
+        /// function to get the voting power of an account the trading pairs base swap fee discounts and incentive boosts on.
+        #[ink(message)]
+        pub fn get_discount_balance(&self, account: AccountId) -> Balance {
+            self.balance_of(account)
+        }
+
+        /// function to get the lock of an account
+        #[ink(message)]
+        pub fn get_lock(&self, account: AccountId) -> LockedBalance {
+            self.locks.get(account).unwrap_or_default()
+        }
+
+        /// function to get the overall locked PANX
+        #[ink(message)]
+        pub fn get_total_locked(&self) -> Balance {
+            self.total_locked
+        }
+
+        /// function to get the PANX token address
+        #[ink(message)]
+        pub fn get_panx_token(&self) -> AccountId {
+            self.panx_token
+        }
+
+        /// function to get the current timestamp in seconds
+        #[ink(message)]
+        pub fn get_current_timestamp(&self) -> u64 {
+            self.env().block_timestamp() / 1000
+        }
+
+        /// function to round an unlock timestamp down to a whole week and check it's between one week and four years from now.
+        fn get_valid_unlock_timestamp(&self, unlock_timestamp: u64) -> Result<u64, VePanxErrors> {
+            let current_timestamp = self.get_current_timestamp();
+
+            let end = unlock_timestamp / WEEK * WEEK;
+
+            if end < current_timestamp.saturating_add(MIN_LOCK_TIME) {
+                return Err(VePanxErrors::LockTooShort)
+            }
+
+            if end > current_timestamp.saturating_add(MAX_LOCK_TIME) {
+                return Err(VePanxErrors::LockTooLong)
+            }
+
+            Ok(end)
+        }
+
+        /// function to get the lock of an account that can still be added to.
+        fn get_active_lock(&self, account: AccountId) -> Result<LockedBalance, VePanxErrors> {
+            let lock = self.get_lock(account);
+
+            if lock.amount == 0 {
+                return Err(VePanxErrors::NoLock)
+            }
+
+            if lock.end <= self.get_current_timestamp() {
+                return Err(VePanxErrors::LockExpired)
+            }
+
+            Ok(lock)
+        }
+
+        /// function to add `amount` PANX to the lock of an account, set its unlock timestamp and take the PANX from it.
+        fn deposit_for(
+            &mut self,
+            account: AccountId,
+            amount: Balance,
+            end: u64,
+        ) -> Result<(), VePanxErrors> {
+            self.update_lock(account, amount, end)?;
+
+            if amount > 0 {
+                // cross contract call to the PANX token to transfer the locked PANX
+                if let Err(error) = PSP22Ref::transfer_from(
+                    &self.panx_token,
+                    account,
+                    self.env().account_id(),
+                    amount,
+                    vec![],
+                ) {
+                    return Err(VePanxErrors::PSP22TransferFromFailed(error))
+                }
+            }
+
+            Self::env().emit_event(Locked {
+                account,
+                amount,
+                end,
+            });
+
+            Ok(())
+        }
+
+        /// function to add `amount` PANX to the lock of an account and set its unlock timestamp, updating the voting power history.
+        fn update_lock(
+            &mut self,
+            account: AccountId,
+            amount: Balance,
+            end: u64,
+        ) -> Result<(), VePanxErrors> {
+            let old_lock = self.get_lock(account);
+
+            let mut new_lock = old_lock.clone();
+
+            match new_lock.amount.checked_add(amount) {
+                Some(result) => {
+                    new_lock.amount = result;
+                }
+                None => return Err(VePanxErrors::Overflow),
+            };
+
+            new_lock.end = end;
+
+            match self.total_locked.checked_add(amount) {
+                Some(result) => {
+                    self.total_locked = result;
+                }
+                None => return Err(VePanxErrors::Overflow),
+            };
+
+            self.locks.insert(account, &new_lock);
+
+            self.checkpoint_account(account, &old_lock, &new_lock)
+        }
+
+        /// function to record the voting power of an account moving from `old_lock` to `new_lock` in both histories.
+        fn checkpoint_account(
+            &mut self,
+            account: AccountId,
+            old_lock: &LockedBalance,
+            new_lock: &LockedBalance,
+        ) -> Result<(), VePanxErrors> {
+            let current_timestamp = self.get_current_timestamp();
+
+            let old_point = get_lock_point(old_lock, current_timestamp);
+            let new_point = get_lock_point(new_lock, current_timestamp);
+
+            let mut last_point = self.checkpoint_global();
+
+            // swapping the account's old voting power for the new one, the old one is part of the overall
+            last_point.slope = last_point
+                .slope
+                .saturating_sub(old_point.slope)
+                .saturating_add(new_point.slope);
+            last_point.bias = last_point
+                .bias
+                .saturating_sub(old_point.bias)
+                .saturating_add(new_point.bias);
+
+            self.point_history.insert(self.epoch, &last_point);
+
+            // the old lock no longer ends the overall slope at its week, the new lock does
+            if old_lock.end > current_timestamp {
+                let slope_change = self.slope_changes.get(old_lock.end).unwrap_or(0);
+
+                self.slope_changes
+                    .insert(old_lock.end, &slope_change.saturating_sub(old_point.slope));
+            }
+
+            if new_lock.end > current_timestamp {
+                let slope_change = self.slope_changes.get(new_lock.end).unwrap_or(0);
+
+                self.slope_changes
+                    .insert(new_lock.end, &slope_change.saturating_add(new_point.slope));
+            }
+
+            let user_epoch = self.user_point_epoch.get(account).unwrap_or(0) + 1;
+
+            self.user_point_epoch.insert(account, &user_epoch);
+
+            self.user_point_history
+                .insert((account, user_epoch), &new_point);
+
+            Ok(())
+        }
+
+        /// function to record the overall voting power at every week since the last epoch, returns the point of the new current epoch.
+        fn checkpoint_global(&mut self) -> Point {
+            let current_timestamp = self.get_current_timestamp();
+
+            let mut last_point = self.point_history.get(self.epoch).unwrap_or(Point {
+                bias: 0,
+                slope: 0,
+                timestamp: current_timestamp,
+            });
+
+            let mut week_timestamp = last_point.timestamp / WEEK * WEEK;
+
+            for _ in 0..MAX_WEEKS_PER_CHECKPOINT {
+                week_timestamp += WEEK;
+
+                let mut slope_change: Balance = 0;
+
+                if week_timestamp > current_timestamp {
+                    week_timestamp = current_timestamp;
+                } else {
+                    slope_change = self.slope_changes.get(week_timestamp).unwrap_or(0);
+                }
+
+                last_point.bias = last_point.bias_at(week_timestamp);
+                last_point.slope = last_point.slope.saturating_sub(slope_change);
+                last_point.timestamp = week_timestamp;
+
+                self.epoch += 1;
+
+                if week_timestamp == current_timestamp {
+                    break
+                }
+
+                self.point_history.insert(self.epoch, &last_point);
+            }
+
+            last_point
+        }
+
+        /// function to binary search the latest epoch up to `max_epoch` whose point is not after `timestamp`.
+        fn find_epoch(
+            &self,
+            max_epoch: u32,
+            timestamp: u64,
+            get_point: impl Fn(u32) -> Option<Point>,
+        ) -> Option<u32> {
+            // epoch 0 has no point, the histories start at epoch 1
+            let mut min: u32 = 0;
+            let mut max: u32 = max_epoch;
+
+            while min < max {
+                let mid = min + (max - min + 1) / 2;
+
+                if matches!(get_point(mid), Some(point) if point.timestamp <= timestamp) {
+                    min = mid;
+                } else {
+                    max = mid - 1;
+                }
+            }
+
+            if min == 0 {
+                None
+            } else {
+                Some(min)
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn set_timestamp(seconds: u64) {
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(seconds * 1000);
+        }
+
+        #[ink::test]
+        fn voting_power_decays_linearly_until_the_lock_ends() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut ve_panx = VePanx::new(AccountId::from([0x01; 32]));
+
+            // Alice locks with a slope of 1000 for 104 weeks
+            set_timestamp(0);
+            ve_panx
+                .update_lock(accounts.alice, 1000 * MAX_LOCK_TIME as Balance, 104 * WEEK)
+                .unwrap();
+
+            assert_eq!(ve_panx.balance_of(accounts.alice), 1000 * 104 * WEEK as Balance);
+            assert_eq!(ve_panx.total_supply(), 1000 * 104 * WEEK as Balance);
+
+            // Bob locks with a slope of 500 half way through
+            set_timestamp(52 * WEEK);
+            ve_panx
+                .update_lock(accounts.bob, 500 * MAX_LOCK_TIME as Balance, 104 * WEEK)
+                .unwrap();
+
+            assert_eq!(ve_panx.balance_of(accounts.alice), 1000 * 52 * WEEK as Balance);
+            assert_eq!(ve_panx.balance_of(accounts.bob), 500 * 52 * WEEK as Balance);
+
+            // past, present and future queries
+            assert_eq!(
+                ve_panx.balance_of_at(accounts.alice, 10 * WEEK),
+                1000 * 94 * WEEK as Balance
+            );
+            assert_eq!(ve_panx.balance_of_at(accounts.bob, 10 * WEEK), 0);
+            assert_eq!(ve_panx.total_supply_at(10 * WEEK), 1000 * 94 * WEEK as Balance);
+            assert_eq!(
+                ve_panx.total_supply_at(60 * WEEK),
+                1500 * 44 * WEEK as Balance
+            );
+            assert_eq!(ve_panx.total_supply_at(200 * WEEK), 0);
+
+            // extending the lock restores the voting power
+            ve_panx.update_lock(accounts.alice, 0, 156 * WEEK).unwrap();
+
+            assert_eq!(ve_panx.balance_of(accounts.alice), 1000 * 104 * WEEK as Balance);
+            assert_eq!(
+                ve_panx.total_supply_at(120 * WEEK),
+                1000 * 36 * WEEK as Balance
+            );
+            assert_eq!(ve_panx.get_discount_balance(accounts.bob), 500 * 52 * WEEK as Balance);
+        }
+
+        #[ink::test]
+        fn lock_duration_is_between_one_week_and_four_years() {
+            let mut ve_panx = VePanx::new(AccountId::from([0x01; 32]));
+
+            set_timestamp(WEEK + 1);
+
+            assert_eq!(
+                ve_panx.create_lock(0, 10 * WEEK),
+                Err(VePanxErrors::ZeroAmount)
+            );
+            assert_eq!(
+                ve_panx.create_lock(1000, 2 * WEEK + 100),
+                Err(VePanxErrors::LockTooShort)
+            );
+            assert_eq!(
+                ve_panx.create_lock(1000, WEEK + 1 + MAX_LOCK_TIME + WEEK),
+                Err(VePanxErrors::LockTooLong)
+            );
+            assert_eq!(ve_panx.increase_amount(1000), Err(VePanxErrors::NoLock));
+            assert_eq!(ve_panx.withdraw(), Err(VePanxErrors::NoLock));
+        }
+    }
+
+    /// ink! end-to-end (E2E) tests
+    ///
+    /// cargo test --features e2e-tests -- --nocapture
+    ///
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink::primitives::AccountId;
+        use ink_e2e::build_message;
+        use my_psp22::my_psp22::MyPsp22Ref;
+        use openbrush::{
+            contracts::psp22::psp22_external::PSP22,
+            traits::String,
+        };
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        /// Helper to get Alice's account_id from `ink_e2e::alice()` PairSigner
+        fn get_alice_account_id() -> AccountId {
+            let alice = ink_e2e::alice::<ink_e2e::PolkadotConfig>();
+            let alice_account_id_32 = alice.account_id();
+            let alice_account_id = AccountId::try_from(alice_account_id_32.as_ref()).unwrap();
+
+            alice_account_id
+        }
+
+        /// Tests included in "create_lock_gives_decaying_voting_power":
+        /// 1. create_lock
+        /// 2. balance_of and total_supply
+        /// 3. withdraw before the lock ended
+        #[ink_e2e::test(additional_contracts = "../my_psp22/Cargo.toml")]
+        async fn create_lock_gives_decaying_voting_power(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            // PANX token, minted to Alice
+            let panx_constructor = MyPsp22Ref::new(
+                10000000000000000,
+                Some(String::from("PANX").into()),
+                Some(String::from("PANX").into()),
+                12,
+            );
+
+            let panx_acc_id = client
+                .instantiate("my_psp22", &ink_e2e::alice(), panx_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            let ve_panx_acc_id = client
+                .instantiate(
+                    "ve_panx",
+                    &ink_e2e::alice(),
+                    VePanxRef::new(panx_acc_id),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            let get_current_timestamp = build_message::<VePanxRef>(ve_panx_acc_id.clone())
+                .call(|ve_panx| ve_panx.get_current_timestamp());
+
+            let current_timestamp = client
+                .call(&ink_e2e::alice(), get_current_timestamp, 0, None)
+                .await
+                .expect("get_current_timestamp failed")
+                .return_value();
+
+            // Alice locks 1000 PANX for about two years
+            let approve = build_message::<MyPsp22Ref>(panx_acc_id.clone())
+                .call(|my_psp22| my_psp22.approve(ve_panx_acc_id, 1000000000000000));
+
+            client
+                .call(&ink_e2e::alice(), approve, 0, None)
+                .await
+                .expect("calling `approve` failed");
+
+            let create_lock = build_message::<VePanxRef>(ve_panx_acc_id.clone()).call(|ve_panx| {
+                ve_panx.create_lock(1000000000000000, current_timestamp + MAX_LOCK_TIME / 2)
+            });
+
+            client
+                .call(&ink_e2e::alice(), create_lock, 0, None)
+                .await
+                .expect("calling `create_lock` failed");
+
+            let balance_of = build_message::<VePanxRef>(ve_panx_acc_id.clone())
+                .call(|ve_panx| ve_panx.balance_of(get_alice_account_id()));
+
+            let balance_of_res = client
+                .call(&ink_e2e::alice(), balance_of, 0, None)
+                .await
+                .expect("balance_of failed")
+                .return_value();
+
+            let total_supply = build_message::<VePanxRef>(ve_panx_acc_id.clone())
+                .call(|ve_panx| ve_panx.total_supply());
+
+            let total_supply_res = client
+                .call(&ink_e2e::alice(), total_supply, 0, None)
+                .await
+                .expect("total_supply failed")
+                .return_value();
+
+            // roughly half of the locked PANX, minus the rounding to a whole week
+            assert!(balance_of_res > 0 && balance_of_res <= 500000000000000);
+            assert_eq!(total_supply_res, balance_of_res);
+
+            let withdraw = build_message::<VePanxRef>(ve_panx_acc_id.clone())
+                .call(|ve_panx| ve_panx.withdraw());
+
+            let withdraw_res = client
+                .call_dry_run(&ink_e2e::alice(), &withdraw, 0, None)
+                .await
+                .return_value();
+
+            assert_eq!(withdraw_res, Err(VePanxErrors::LockNotExpired));
+
+            Ok(())
+        }
+    }
+}